@@ -1,19 +1,59 @@
 #![no_std]
 
 use soroban_sdk::{
-    Address, Env, Map, String, Symbol, Vec, contract, contracterror, contractevent, contractimpl,
-    contracttype, symbol_short, token,
+    Address, Bytes, BytesN, Env, IntoVal, Map, String, Symbol, TryFromVal, Val, Vec, contract,
+    contracterror, contractevent, contractimpl, contracttype, panic_with_error, symbol_short,
+    token, xdr::ToXdr,
 };
 
 // --- Storage Keys ---
 const KEY_ADMIN: Symbol = symbol_short!("admin");
-const KEY_TOTAL_LOCKED: Symbol = symbol_short!("locked"); // Map<Address, i128>
-const KEY_VERSION: Symbol = symbol_short!("version");
+const KEY_LEGACY_LOCKED: Symbol = symbol_short!("locked"); // v2 Map<Address, i128>, now ("lock", token)
+const KEY_VERSION: Symbol = symbol_short!("version"); // free-form label from before KEY_LAYOUT, not trusted
+const KEY_LAYOUT: Symbol = symbol_short!("layout"); // u32, storage layout version
+const KEY_UPGRADE_TARGET: Symbol = symbol_short!("upg_ver"); // u32, layout the installed code expects
 const KEY_PKG_COUNTER: Symbol = symbol_short!("pkg_cnt");
 const KEY_CONFIG: Symbol = symbol_short!("config");
 const KEY_PKG_IDX: Symbol = symbol_short!("pkg_idx"); // Aggregation index counter
-const KEY_DISTRIBUTORS: Symbol = symbol_short!("dstrbtrs"); // Map<Address, bool>
-const KEY_PAUSED: Symbol = symbol_short!("paused");
+const KEY_LEGACY_DISTRIBUTORS: Symbol = symbol_short!("dstrbtrs"); // v2 Map<Address, DistributorRecord>, now ("dist", addr)
+const KEY_DIST_COUNT: Symbol = symbol_short!("dist_cnt"); // persistent u32, ("didx", i) distributor index entries
+const KEY_PAUSES: Symbol = symbol_short!("pauses"); // Map<(PauseScope, Option<Address>), PauseInfo>
+const KEY_MULTISIG: Symbol = symbol_short!("multisig");
+const KEY_PROPOSAL_COUNTER: Symbol = symbol_short!("prop_cnt");
+const KEY_PENDING_ADMIN: Symbol = symbol_short!("pend_adm");
+const KEY_RENOUNCED: Symbol = symbol_short!("renounced");
+const KEY_TIMELOCK: Symbol = symbol_short!("timelock");
+const KEY_QUEUE_COUNTER: Symbol = symbol_short!("queue_cnt");
+const KEY_QUEUED: Symbol = symbol_short!("queued"); // Vec<u64>, pending queued action ids
+const KEY_MIGRATION: Symbol = symbol_short!("migration"); // MigrationState while a migration runs
+const KEY_LEGACY_PAUSED: Symbol = symbol_short!("paused"); // v1 global pause flag
+const KEY_TTL: Symbol = symbol_short!("ttl"); // TtlConfig
+const KEY_INSTANCE_TTL: Symbol = symbol_short!("inst_ttl"); // u32, ledger the instance lives until
+
+// Upper bound on schedule length, keeps claim and view costs predictable.
+const MAX_TRANCHES: u32 = 64;
+
+// Storage layout version written by this code; `migrate` steps older contracts up to it.
+// v1: original layout. v2: current `Package`, `Config`, distributor records and scoped pauses.
+// v3: distributors and locked totals as per-key persistent entries.
+const LATEST_VERSION: u32 = 3;
+// Funders visited per FIFO attribution; the rest of a package stays unattributed.
+const MAX_FUNDER_SCAN: u32 = 16;
+// Distributors returned by `get_role_members`; `list_distributors` pages through the rest.
+const MAX_ROLE_MEMBERS: u32 = 100;
+
+// Shortest secret `claim_with_secret` accepts. The hash is public, so short codes can be
+// brute-forced offline and claimed by whoever commits first.
+const MIN_SECRET_LEN: u32 = 16;
+
+// Packages rewritten per migration call, keeps each transaction within resource limits.
+const MIGRATION_BATCH_SIZE: u32 = 50;
+
+// TTL defaults in ledgers (~5s each): entries closer than 30 days to archival are
+// extended to 120 days whenever they are touched.
+const DAY_IN_LEDGERS: u32 = 17_280;
+const DEFAULT_TTL_THRESHOLD: u32 = 30 * DAY_IN_LEDGERS;
+const DEFAULT_TTL_EXTEND_TO: u32 = 120 * DAY_IN_LEDGERS;
 
 // --- Data Types ---
 
@@ -28,6 +68,44 @@ pub enum PackageStatus {
     Refunded = 4,
 }
 
+/// How a package releases its funds to the recipient.
+#[contracttype]
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+#[repr(u32)]
+pub enum PackageKind {
+    /// The full amount is released at once.
+    Standard = 0,
+    /// The amount is released in tranches following a schedule.
+    Tranched = 1,
+    /// The amount accrues linearly between a start and an end timestamp.
+    Stream = 2,
+    /// Claimable by whoever presents an admin-issued ed25519 voucher signature.
+    /// The recipient is unknown until claim time.
+    Voucher = 3,
+    /// Claimable by whoever reveals the preimage of a sha256 hash (e.g. a scratch-card code).
+    /// The recipient is unknown until claim time.
+    HashLock = 4,
+}
+
+/// One installment of a tranched package.
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub struct Tranche {
+    pub amount: i128,
+    pub unlocks_at: u64,
+}
+
+/// Accrual window of a streaming package.
+/// While paused, accrual is frozen at `paused_at`; resuming shifts the window by the pause length.
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub struct StreamSchedule {
+    pub start_at: u64,
+    pub end_at: u64,
+    pub paused: bool,
+    pub paused_at: u64,
+}
+
 #[contracttype]
 #[derive(Clone, Debug, PartialEq)]
 pub struct Package {
@@ -39,6 +117,18 @@ pub struct Package {
     pub created_at: u64,
     pub expires_at: u64,
     pub metadata: Map<Symbol, String>,
+    pub kind: PackageKind,
+    /// Amount already released to the recipient. Only partial for tranched and streaming packages.
+    pub claimed_amount: i128,
+    /// Timestamp before which the package cannot be claimed (0 = claimable immediately).
+    pub claimable_from: u64,
+    /// Donor whose `fund` contributions back this package; refunds go back to them.
+    /// None = the general pool, attributed FIFO to funders (see `get_funding_shares`).
+    pub funder: Option<Address>,
+    /// Earmarked sub-pool the package draws on (None = general pool).
+    pub earmark: Option<Symbol>,
+    /// Operator (admin or distributor) that created the package.
+    pub created_by: Address,
 }
 
 #[contracttype]
@@ -47,6 +137,325 @@ pub struct Config {
     pub min_amount: i128,
     pub max_expires_in: u64,
     pub allowed_tokens: Vec<Address>,
+    /// Cool-down in seconds between requesting and approving a recipient reassignment (0 = none).
+    pub reassignment_delay: u64,
+    /// Metadata keys operators may set on packages (empty = any key).
+    pub metadata_keys: Vec<Symbol>,
+    /// Maximum number of metadata entries per package (0 = unlimited).
+    pub max_metadata_entries: u32,
+    /// Maximum length in bytes of a metadata value (0 = unlimited).
+    pub max_metadata_value_len: u32,
+}
+
+/// Optional settings for `create_package_with_options` and `batch_create_with_options`.
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub struct PackageOptions {
+    /// Initial metadata, e.g. campaign id, region code or verification reference.
+    pub metadata: Map<Symbol, String>,
+    /// Start of the claim window (0 = claimable immediately). Lets operators pre-stage
+    /// packages that stay locked until distribution day.
+    pub claimable_from: u64,
+    /// Funding source to draw on; must have enough unallocated contributions in the token.
+    pub funder: Option<Address>,
+    /// Earmark tag to draw on instead of the general pool; cannot be combined with `funder`.
+    pub earmark: Option<Symbol>,
+}
+
+/// Money given for one campaign or purpose, kept apart from the general pool.
+/// Available for new packages = `balance - locked`.
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub struct Earmark {
+    /// Earmarked money still held by the contract.
+    pub balance: i128,
+    /// Part of `balance` locked in `Created` packages.
+    pub locked: i128,
+}
+
+/// Per-token accounting of one funder's contributions.
+/// Unallocated general balance = `contributed - earmarked - locked - released - refunded`.
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub struct FunderAccount {
+    /// Total ever sent in through `fund` and `fund_earmarked`.
+    pub contributed: i128,
+    /// Part of `contributed` given to earmarks and not attributed to a package yet.
+    pub earmarked: i128,
+    /// Currently locked in packages backed by this funder, including cancelled or expired
+    /// packages until they are refunded.
+    pub locked: i128,
+    /// Paid out to recipients.
+    pub released: i128,
+    /// Returned to the funder by `refund` or `withdraw_surplus`.
+    pub refunded: i128,
+    /// Number of packages this funder's money was attributed to.
+    pub packages: u32,
+}
+
+/// A funder's part of a package, attributed when the package is created.
+/// A package's shares cover its amount in order; claims settle them first to last.
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub struct FundingShare {
+    pub funder: Address,
+    pub amount: i128,
+}
+
+/// What a donor's money has done so far in one token.
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub struct DonorImpact {
+    pub contributed: i128,
+    /// Locked in packages that have not been claimed or refunded yet.
+    pub committed: i128,
+    /// Delivered to recipients.
+    pub claimed: i128,
+    /// Returned to the donor.
+    pub refunded: i128,
+    /// Not attributed to any package; available for new packages or refunds.
+    pub available: i128,
+    pub packages_funded: u32,
+}
+
+/// A sponsor's matching campaign for one token. The sponsor pre-funds `cap`; each `fund`
+/// call before `deadline` is matched at `ratio_bps` (10_000 = 1:1) until the cap is used up.
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub struct MatchingPool {
+    pub sponsor: Address,
+    pub token: Address,
+    pub ratio_bps: u32,
+    pub cap: i128,
+    pub deadline: u64,
+    /// Amount moved into the general pool so far.
+    pub matched: i128,
+}
+
+/// What a distributor may create. Empty `allowed_tokens` and zero limits mean unrestricted.
+/// `max_expires_in` caps the claim window from when it opens, like the config limit (no
+/// open-ended packages when set), `campaign` requires packages to draw on that earmark,
+/// and after a non-zero `active_until` the distributor loses operator rights.
+/// `packages_created`, `total_committed` and `last_active` are activity stats kept by the
+/// contract; values passed to `add_distributor` are ignored.
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub struct DistributorRecord {
+    pub allowed_tokens: Vec<Address>,
+    pub max_amount: i128,
+    pub max_expires_in: u64,
+    pub campaign: Option<Symbol>,
+    pub active_until: u64,
+    pub packages_created: u32,
+    pub total_committed: i128,
+    pub last_active: u64,
+}
+
+/// A distributor's spending allowance in one token.
+/// `used` is consumed by package creation and given back when those packages are revoked,
+/// cancelled or expire. With `period` > 0, at most `period_limit` can be committed per window
+/// of `period` seconds; a window starts with the first creation after the previous one ended.
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub struct Allowance {
+    pub limit: i128,
+    pub used: i128,
+    pub period: u64,
+    pub period_limit: i128,
+    pub period_start: u64,
+    pub period_used: i128,
+}
+
+/// Operations that can be paused separately. `All` covers every scope (and expiry).
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[repr(u32)]
+pub enum PauseScope {
+    All = 0,
+    Creation = 1,
+    Claims = 2,
+    Disbursement = 3,
+    Withdrawal = 4,
+}
+
+/// An active pause. `expires_at` = 0 lasts until unpaused; otherwise it lapses on its own.
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub struct PauseInfo {
+    pub reason: Option<String>,
+    pub paused_by: Address,
+    pub paused_at: u64,
+    pub expires_at: u64,
+}
+
+/// Named operator roles the admin can grant. The admin implicitly holds every role.
+/// `Distributor` members are the `("dist", addr)` entries; `Auditor` gates nothing on-chain
+/// (views are public) and marks read-only reviewers for off-chain tooling.
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[repr(u32)]
+pub enum Role {
+    Pauser = 0,
+    Treasurer = 1,
+    Distributor = 2,
+    Canceller = 3,
+    Extender = 4,
+    Auditor = 5,
+    // can pause but not unpause
+    EmergencyGuardian = 6,
+}
+
+/// An admin handover waiting for the new admin to accept.
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub struct PendingAdmin {
+    pub new_admin: Address,
+    pub proposed_at: u64,
+    pub accept_after: u64,
+}
+
+/// Signers and threshold for M-of-N approval of high-risk admin actions.
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub struct Multisig {
+    pub signers: Vec<Address>,
+    pub threshold: u32,
+}
+
+/// High-risk admin actions that need an approved proposal once multisig is enabled.
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub enum AdminAction {
+    /// (to, amount, token)
+    WithdrawSurplus(Address, i128, Address),
+    SetConfig(Config),
+    Migrate(u32),
+    Refund(u64),
+    /// (signers, threshold); an empty signer list with threshold 0 disables multisig.
+    SetSigners(Vec<Address>, u32),
+    /// (token, allowed) adds or removes a token from `Config::allowed_tokens`.
+    SetTokenAllowed(Address, bool),
+    /// (delay, withdraw_threshold)
+    SetTimelock(u64, i128),
+    /// (wasm_hash, new_version)
+    Upgrade(BytesN<32>, u32),
+    /// (new_admin, delay) nominates a successor, as `propose_admin`.
+    ProposeAdmin(Address, u64),
+    RenounceAdmin,
+    /// (recipient, guardian, payout_to), as `set_guardian`.
+    SetGuardian(Address, Address, Option<Address>),
+    /// Applies a package's pending reassignment, as `approve_reassignment`.
+    ApproveReassignment(u64),
+    /// (addr, record), as `add_distributor`.
+    AddDistributor(Address, DistributorRecord),
+    /// (role, account), as `grant_role`.
+    GrantRole(Role, Address),
+}
+
+/// Progress of a storage migration that spans several transactions.
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub struct MigrationState {
+    pub from_version: u32,
+    pub to_version: u32,
+    pub cursor: u64,
+}
+
+/// `Package` as stored by v1 contracts.
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+struct PackageV1 {
+    pub id: u64,
+    pub recipient: Address,
+    pub amount: i128,
+    pub token: Address,
+    pub status: PackageStatus,
+    pub created_at: u64,
+    pub expires_at: u64,
+    pub metadata: Map<Symbol, String>,
+}
+
+/// `Config` as stored by v1 contracts.
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+struct ConfigV1 {
+    pub min_amount: i128,
+    pub max_expires_in: u64,
+    pub allowed_tokens: Vec<Address>,
+}
+
+/// Notice period for rule changes. With `delay` > 0, config and allowlist changes, `migrate`,
+/// timelock changes and surplus withdrawals must be queued. Withdrawals are exempt while the
+/// total withdrawn unqueued in a token within the current `delay`-long window stays at or
+/// below `withdraw_threshold`.
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub struct Timelock {
+    pub delay: u64,
+    pub withdraw_threshold: i128,
+}
+
+/// Storage TTL policy in ledgers. Packages, distributor records, locked totals and instance
+/// data with less than `threshold` ledgers left when read or written are extended to `extend_to`.
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub struct TtlConfig {
+    pub threshold: u32,
+    pub extend_to: u32,
+}
+
+/// An admin action waiting out the timelock. Reuses `ProposalStatus` for its lifecycle.
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub struct QueuedAction {
+    pub id: u64,
+    pub action: AdminAction,
+    pub queued_by: Address,
+    pub queued_at: u64,
+    pub eta: u64,
+    pub status: ProposalStatus,
+    /// Queued by executing an approved multisig proposal.
+    pub approved: bool,
+}
+
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[repr(u32)]
+pub enum ProposalStatus {
+    Pending = 0,
+    Executed = 1,
+    Cancelled = 2,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub struct Proposal {
+    pub id: u64,
+    pub action: AdminAction,
+    pub proposer: Address,
+    pub approvals: Vec<Address>,
+    pub status: ProposalStatus,
+    pub created_at: u64,
+    pub expires_at: u64,
+}
+
+/// A guardian allowed to claim on behalf of a recipient.
+/// `payout_to` = None pays the recipient; otherwise funds go to the guardian-held address.
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub struct Guardian {
+    pub guardian: Address,
+    pub payout_to: Option<Address>,
+}
+
+/// A pending request to move a package to a new recipient (e.g. after a lost wallet).
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub struct ReassignmentRequest {
+    pub new_recipient: Address,
+    pub requested_by: Address,
+    pub requested_at: u64,
+    pub approvable_at: u64,
 }
 
 #[contracttype]
@@ -75,6 +484,47 @@ pub enum Error {
     MismatchedArrays = 12,
     InsufficientSurplus = 13,
     ContractPaused = 14,
+    // tranche schedule is empty, unordered, too long or has non-positive amounts
+    InvalidSchedule = 15,
+    NothingToClaim = 16,
+    // operation is not available for this package kind
+    WrongPackageKind = 17,
+    // revealed secret does not match the package hash
+    InvalidSecret = 18,
+    // no matching claim commitment from an earlier ledger
+    InvalidCommitment = 19,
+    NoPendingReassignment = 20,
+    // reassignment cool-down has not elapsed yet
+    ReassignmentCoolingDown = 21,
+    // metadata key not allowed, value too long, too many entries or key missing
+    InvalidMetadata = 22,
+    // claim window has not opened yet (before `claimable_from`)
+    PackageNotYetClaimable = 23,
+    // distributor allowance or its per-period limit would be exceeded
+    AllowanceExceeded = 24,
+    // multisig is enabled; this action needs an approved proposal
+    MultisigRequired = 25,
+    ProposalNotFound = 26,
+    // proposal or queued action was already executed or cancelled
+    ProposalNotPending = 27,
+    ProposalExpired = 28,
+    // not enough signer approvals to execute
+    ThresholdNotMet = 29,
+    // admin was renounced; only claims and expiry still work
+    AdminRenounced = 30,
+    NoPendingAdmin = 31,
+    // pending admin cannot accept before `accept_after`
+    AdminTransferNotReady = 32,
+    // timelock is enabled; this action must be queued
+    TimelockRequired = 33,
+    QueuedActionNotFound = 34,
+    // queued action's delay has not passed yet
+    TimelockNotReady = 35,
+    // package falls outside the distributor's token, amount, horizon or campaign scope
+    DistributorScopeViolation = 36,
+    // migrations go one registered version up at a time; upgrades cannot downgrade
+    InvalidMigration = 37,
+    MigrationInProgress = 38,
 }
 
 // --- Contract Events (indexer-friendly; stable topics & payloads) ---
@@ -89,6 +539,165 @@ pub struct EscrowFunded {
     pub timestamp: u64,
 }
 
+/// Emitted when money is given to an earmarked sub-pool. Actor = funder.
+#[contractevent]
+pub struct EarmarkFunded {
+    pub tag: Symbol,
+    pub from: Address,
+    pub token: Address,
+    pub amount: i128,
+    pub timestamp: u64,
+}
+
+/// Emitted when a sponsor matches a gift. `amount` is the donor's gift, `matched` the sponsor's
+/// addition to the general pool.
+#[contractevent]
+pub struct DonationMatched {
+    pub sponsor: Address,
+    pub donor: Address,
+    pub token: Address,
+    pub amount: i128,
+    pub matched: i128,
+    pub timestamp: u64,
+}
+
+/// Emitted when a matching pool is set up. Actor = admin.
+#[contractevent]
+pub struct MatchingPoolCreated {
+    pub sponsor: Address,
+    pub token: Address,
+    pub ratio_bps: u32,
+    pub cap: i128,
+    pub deadline: u64,
+}
+
+/// Emitted when a matching pool closes after its deadline; `returned` went back to the sponsor.
+#[contractevent]
+pub struct MatchingPoolClosed {
+    pub sponsor: Address,
+    pub token: Address,
+    pub matched: i128,
+    pub returned: i128,
+    pub timestamp: u64,
+}
+
+/// Emitted whenever a distributor allowance is set, consumed or given back.
+#[contractevent]
+pub struct AllowanceUpdated {
+    pub distributor: Address,
+    pub token: Address,
+    pub limit: i128,
+    pub used: i128,
+    pub period_used: i128,
+    pub timestamp: u64,
+}
+
+/// Emitted when a distributor allowance is removed (the distributor is unlimited again).
+/// Actor = admin.
+#[contractevent]
+pub struct AllowanceRemoved {
+    pub distributor: Address,
+    pub token: Address,
+    pub timestamp: u64,
+}
+
+/// Emitted when a signer proposes a high-risk admin action.
+#[contractevent]
+pub struct ProposalCreated {
+    pub proposal_id: u64,
+    pub action: AdminAction,
+    pub proposer: Address,
+    pub expires_at: u64,
+    pub timestamp: u64,
+}
+
+#[contractevent]
+pub struct ProposalApproved {
+    pub proposal_id: u64,
+    pub signer: Address,
+    pub approvals: u32,
+    pub timestamp: u64,
+}
+
+/// Emitted when an approved proposal's action runs. Actor = whoever triggered execution.
+#[contractevent]
+pub struct ProposalExecuted {
+    pub proposal_id: u64,
+    pub actor: Address,
+    pub timestamp: u64,
+}
+
+#[contractevent]
+pub struct ProposalCancelled {
+    pub proposal_id: u64,
+    pub actor: Address,
+    pub timestamp: u64,
+}
+
+/// Emitted when the contract code is replaced. `new_version` is the storage version the new
+/// code expects; `migrate` brings storage up to it.
+#[contractevent]
+pub struct Upgraded {
+    pub old_version: u32,
+    pub new_version: u32,
+    pub wasm_hash: BytesN<32>,
+    pub timestamp: u64,
+}
+
+/// Emitted when a storage migration finishes.
+#[contractevent]
+pub struct Migrated {
+    pub from_version: u32,
+    pub to_version: u32,
+    pub timestamp: u64,
+}
+
+/// Emitted when an admin action enters the timelock queue.
+#[contractevent]
+pub struct ActionQueued {
+    pub queue_id: u64,
+    pub action: AdminAction,
+    pub queued_by: Address,
+    pub eta: u64,
+    pub timestamp: u64,
+}
+
+#[contractevent]
+pub struct QueuedActionExecuted {
+    pub queue_id: u64,
+    pub actor: Address,
+    pub timestamp: u64,
+}
+
+#[contractevent]
+pub struct QueuedActionCancelled {
+    pub queue_id: u64,
+    pub actor: Address,
+    pub timestamp: u64,
+}
+
+#[contractevent]
+pub struct TimelockUpdated {
+    pub delay: u64,
+    pub withdraw_threshold: i128,
+    pub timestamp: u64,
+}
+
+#[contractevent]
+pub struct TtlConfigUpdated {
+    pub threshold: u32,
+    pub extend_to: u32,
+    pub timestamp: u64,
+}
+
+/// Emitted when the multisig signer set or threshold changes.
+#[contractevent]
+pub struct SignersUpdated {
+    pub signers: Vec<Address>,
+    pub threshold: u32,
+    pub timestamp: u64,
+}
+
 /// Emitted when a package is created. Actor = operator (admin or distributor).
 #[contractevent]
 pub struct PackageCreated {
@@ -109,66 +718,299 @@ pub struct PackageClaimed {
     pub timestamp: u64,
 }
 
-/// Emitted when admin disburses a package. Actor = admin.
+/// Emitted alongside `PackageClaimed` when a recipient claims unlocked tranches. Actor = recipient.
 #[contractevent]
-pub struct PackageDisbursed {
+pub struct TrancheClaimed {
     pub package_id: u64,
     pub recipient: Address,
     pub amount: i128,
+    pub claimed_total: i128,
+    pub remaining: i128,
     pub actor: Address,
     pub timestamp: u64,
 }
 
-/// Emitted when a package is revoked/cancelled. Actor = admin.
+/// Emitted when a claimant commits to claiming a hash-locked package.
+/// Only the commitment is published; the secret and destination stay hidden until reveal.
 #[contractevent]
-pub struct PackageRevoked {
+pub struct ClaimCommitted {
     pub package_id: u64,
-    pub recipient: Address,
-    pub amount: i128,
-    pub actor: Address,
+    pub commitment: BytesN<32>,
+    pub ledger: u32,
     pub timestamp: u64,
 }
 
-/// Emitted when funds are refunded to admin after expire/cancel. Actor = admin.
+/// Emitted when an operator requests a new recipient for a package. Actor = operator.
 #[contractevent]
-pub struct PackageRefunded {
+pub struct ReassignmentRequested {
     pub package_id: u64,
-    pub recipient: Address,
-    pub amount: i128,
+    pub old_recipient: Address,
+    pub new_recipient: Address,
+    pub approvable_at: u64,
     pub actor: Address,
     pub timestamp: u64,
 }
 
+/// Emitted when admin approves a reassignment and the package recipient changes. Actor = admin.
 #[contractevent]
-pub struct BatchCreatedEvent {
-    pub ids: Vec<u64>,
-    pub admin: Address,
-    pub total_amount: i128,
+pub struct RecipientReassigned {
+    pub package_id: u64,
+    pub old_recipient: Address,
+    pub new_recipient: Address,
+    pub actor: Address,
+    pub timestamp: u64,
 }
 
+/// Emitted when admin cancels a pending reassignment. Actor = admin.
 #[contractevent]
-pub struct ExtendedEvent {
-    pub id: u64,
-    pub admin: Address,
-    pub old_expires_at: u64,
-    pub new_expires_at: u64,
+pub struct ReassignmentCancelled {
+    pub package_id: u64,
+    pub new_recipient: Address,
+    pub actor: Address,
+    pub timestamp: u64,
 }
 
+/// Emitted when admin registers or replaces a recipient's guardian. Actor = admin.
 #[contractevent]
-pub struct SurplusWithdrawnEvent {
-    pub to: Address,
-    pub token: Address,
-    pub amount: i128,
+pub struct GuardianSet {
+    pub recipient: Address,
+    pub guardian: Address,
+    pub payout_to: Option<Address>,
+    pub actor: Address,
+    pub timestamp: u64,
 }
 
+/// Emitted when admin removes a recipient's guardian. Actor = admin.
 #[contractevent]
-pub struct ContractPausedEvent {
-    pub admin: Address,
+pub struct GuardianRemoved {
+    pub recipient: Address,
+    pub guardian: Address,
+    pub actor: Address,
+    pub timestamp: u64,
 }
 
+/// Emitted when an operator sets (`value` = Some) or removes (`value` = None) a package
+/// metadata entry. Actor = operator.
 #[contractevent]
-pub struct ContractUnpausedEvent {
+pub struct PackageMetadataUpdated {
+    pub package_id: u64,
+    pub key: Symbol,
+    pub value: Option<String>,
+    pub actor: Address,
+    pub timestamp: u64,
+}
+
+/// Emitted when admin pauses a streaming package. Actor = admin.
+#[contractevent]
+pub struct StreamPaused {
+    pub package_id: u64,
+    pub actor: Address,
+    pub timestamp: u64,
+}
+
+/// Emitted when admin resumes a streaming package. Actor = admin.
+#[contractevent]
+pub struct StreamResumed {
+    pub package_id: u64,
+    pub new_end_at: u64,
+    pub actor: Address,
+    pub timestamp: u64,
+}
+
+/// Emitted when admin stops a streaming package. Accrued funds are paid out to the
+/// recipient and the unaccrued balance returns to the pool. Actor = admin.
+#[contractevent]
+pub struct StreamStopped {
+    pub package_id: u64,
+    pub recipient: Address,
+    pub paid_out: i128,
+    pub returned: i128,
+    pub actor: Address,
+    pub timestamp: u64,
+}
+
+/// Emitted when admin disburses a package. Actor = admin.
+#[contractevent]
+pub struct PackageDisbursed {
+    pub package_id: u64,
+    pub recipient: Address,
+    pub amount: i128,
+    pub actor: Address,
+    pub timestamp: u64,
+}
+
+/// Emitted when a package is revoked/cancelled. Actor = admin.
+#[contractevent]
+pub struct PackageRevoked {
+    pub package_id: u64,
+    pub recipient: Address,
+    pub amount: i128,
+    pub actor: Address,
+    pub timestamp: u64,
+}
+
+/// Emitted when an overdue package is marked `Expired` and its unclaimed amount is unlocked.
+#[contractevent]
+pub struct PackageExpired {
+    pub package_id: u64,
+    pub recipient: Address,
+    pub amount: i128,
+    pub timestamp: u64,
+}
+
+/// Emitted when unused money goes back to the donor who provided it. Actor = admin.
+/// `package_id` is set for package refunds and None for `withdraw_surplus`.
+#[contractevent]
+pub struct DonorRefunded {
+    pub funder: Address,
+    pub token: Address,
+    pub amount: i128,
+    pub package_id: Option<u64>,
+    pub actor: Address,
+    pub timestamp: u64,
+}
+
+/// Emitted when funds are refunded to admin after expire/cancel. Actor = admin.
+#[contractevent]
+pub struct PackageRefunded {
+    pub package_id: u64,
+    pub recipient: Address,
+    pub amount: i128,
+    pub actor: Address,
+    pub timestamp: u64,
+}
+
+#[contractevent]
+pub struct BatchCreatedEvent {
+    pub ids: Vec<u64>,
+    pub admin: Address,
+    pub total_amount: i128,
+}
+
+/// Summary of `batch_claim`; `ids` lists the packages that were claimed.
+#[contractevent]
+pub struct BatchClaimedEvent {
+    pub ids: Vec<u64>,
+    pub total_amount: i128,
+}
+
+/// Summary of `batch_disburse`; `ids` lists the packages that were disbursed.
+#[contractevent]
+pub struct BatchDisbursedEvent {
+    pub ids: Vec<u64>,
+    pub admin: Address,
+    pub total_amount: i128,
+}
+
+/// Summary of `batch_revoke`; `ids` lists the packages that were revoked.
+#[contractevent]
+pub struct BatchRevokedEvent {
+    pub ids: Vec<u64>,
+    pub admin: Address,
+    pub total_amount: i128,
+}
+
+/// Summary of `batch_refund`; `ids` lists the packages that were refunded.
+#[contractevent]
+pub struct BatchRefundedEvent {
+    pub ids: Vec<u64>,
+    pub admin: Address,
+    pub total_amount: i128,
+}
+
+#[contractevent]
+pub struct ExtendedEvent {
+    pub id: u64,
+    pub admin: Address,
+    pub old_expires_at: u64,
+    pub new_expires_at: u64,
+}
+
+#[contractevent]
+pub struct SurplusWithdrawnEvent {
+    pub to: Address,
+    pub token: Address,
+    pub amount: i128,
+}
+
+/// Emitted when a distributor is added or its scope replaced.
+#[contractevent]
+pub struct DistributorAdded {
+    pub distributor: Address,
+    pub actor: Address,
+    pub timestamp: u64,
+}
+
+#[contractevent]
+pub struct DistributorRemoved {
+    pub distributor: Address,
+    pub actor: Address,
+    pub timestamp: u64,
+}
+
+#[contractevent]
+pub struct RoleGranted {
+    pub role: Role,
+    pub account: Address,
+    pub admin: Address,
+    pub timestamp: u64,
+}
+
+#[contractevent]
+pub struct RoleRevoked {
+    pub role: Role,
+    pub account: Address,
+    pub admin: Address,
+    pub timestamp: u64,
+}
+
+/// Emitted when the admin nominates a successor.
+#[contractevent]
+pub struct AdminTransferProposed {
+    pub admin: Address,
+    pub new_admin: Address,
+    pub accept_after: u64,
+    pub timestamp: u64,
+}
+
+#[contractevent]
+pub struct AdminTransferCancelled {
+    pub admin: Address,
+    pub new_admin: Address,
+    pub timestamp: u64,
+}
+
+/// Emitted when the nominated admin accepts and takes over.
+#[contractevent]
+pub struct AdminTransferred {
+    pub previous_admin: Address,
+    pub new_admin: Address,
+    pub timestamp: u64,
+}
+
+/// Emitted when the admin gives up control; the contract becomes immutable.
+#[contractevent]
+pub struct AdminRenounced {
+    pub admin: Address,
+    pub timestamp: u64,
+}
+
+/// Emitted when a scope is paused, for every token (`token` = None) or one token.
+/// `admin` is the caller (admin, Pauser or EmergencyGuardian).
+#[contractevent]
+pub struct ContractPausedEvent {
+    pub admin: Address,
+    pub scope: PauseScope,
+    pub token: Option<Address>,
+    pub reason: Option<String>,
+    pub expires_at: u64,
+}
+
+#[contractevent]
+pub struct ContractUnpausedEvent {
     pub admin: Address,
+    pub scope: PauseScope,
+    pub token: Option<Address>,
 }
 
 #[contract]
@@ -183,725 +1025,4138 @@ impl AidEscrow {
             return Err(Error::AlreadyInitialized);
         }
         env.storage().instance().set(&KEY_ADMIN, &admin);
-        env.storage().instance().set(&KEY_VERSION, &1u32);
+        env.storage().instance().set(&KEY_LAYOUT, &LATEST_VERSION);
         let config = Config {
             min_amount: 1,
             max_expires_in: 0,
             allowed_tokens: Vec::new(&env),
+            reassignment_delay: 0,
+            metadata_keys: Vec::new(&env),
+            max_metadata_entries: 0,
+            max_metadata_value_len: 0,
         };
         env.storage().instance().set(&KEY_CONFIG, &config);
         Ok(())
     }
 
+    /// Fails with `AdminRenounced` once the admin has been renounced, which disables every
+    /// admin and distributor path.
     pub fn get_admin(env: Env) -> Result<Address, Error> {
-        env.storage()
+        Self::check_renounced(&env)?;
+        let admin = env
+            .storage()
             .instance()
             .get(&KEY_ADMIN)
-            .ok_or(Error::NotInitialized)
+            .ok_or(Error::NotInitialized)?;
+        Self::bump_instance(&env);
+        Ok(admin)
     }
 
-    pub fn get_version(env: Env) -> u32 {
-        env.storage().instance().get(&KEY_VERSION).unwrap_or(0)
+    /// Admin nominates `new_admin`, who can accept `delay` seconds from now.
+    /// Replaces any earlier nomination. With multisig on this goes through an
+    /// `AdminAction::ProposeAdmin` proposal.
+    pub fn propose_admin(env: Env, new_admin: Address, delay: u64) -> Result<(), Error> {
+        let admin = Self::require_single_admin(&env)?;
+        Self::apply_propose_admin(&env, &admin, new_admin, delay)
     }
 
-    pub fn migrate(env: Env, new_version: u32) -> Result<(), Error> {
-        let admin = Self::get_admin(env.clone())?;
-        admin.require_auth();
+    fn apply_propose_admin(
+        env: &Env,
+        admin: &Address,
+        new_admin: Address,
+        delay: u64,
+    ) -> Result<(), Error> {
+        if new_admin == *admin {
+            return Err(Error::InvalidState);
+        }
 
-        let current_version = Self::get_version(env.clone());
+        let now = env.ledger().timestamp();
+        let pending = PendingAdmin {
+            new_admin: new_admin.clone(),
+            proposed_at: now,
+            accept_after: now + delay,
+        };
+        env.storage().instance().set(&KEY_PENDING_ADMIN, &pending);
 
-        // Perform version-specific migrations
-        match (current_version, new_version) {
-            (1, 2) => {
-                // Future: Add migration logic for v1 -> v2
-            }
-            _ => {
-                // No-op for now, but structured for future use
-            }
+        AdminTransferProposed {
+            admin: admin.clone(),
+            new_admin,
+            accept_after: pending.accept_after,
+            timestamp: now,
         }
+        .publish(env);
 
-        env.storage().instance().set(&KEY_VERSION, &new_version);
         Ok(())
     }
 
-    pub fn add_distributor(env: Env, addr: Address) -> Result<(), Error> {
-        let admin = Self::get_admin(env.clone())?;
-        admin.require_auth();
-
-        let mut distributors: Map<Address, bool> = env
-            .storage()
-            .instance()
-            .get(&KEY_DISTRIBUTORS)
-            .unwrap_or(Map::new(&env));
-        distributors.set(addr, true);
-        env.storage()
-            .instance()
-            .set(&KEY_DISTRIBUTORS, &distributors);
+    /// The nominated admin takes over once the delay has passed.
+    pub fn accept_admin(env: Env) -> Result<(), Error> {
+        let previous_admin = Self::get_admin(env.clone())?;
+        let pending = Self::get_pending_admin(env.clone()).ok_or(Error::NoPendingAdmin)?;
+        pending.new_admin.require_auth();
 
-        Ok(())
-    }
+        let now = env.ledger().timestamp();
+        if now < pending.accept_after {
+            return Err(Error::AdminTransferNotReady);
+        }
 
-    pub fn remove_distributor(env: Env, addr: Address) -> Result<(), Error> {
-        let admin = Self::get_admin(env.clone())?;
-        admin.require_auth();
+        env.storage().instance().set(&KEY_ADMIN, &pending.new_admin);
+        env.storage().instance().remove(&KEY_PENDING_ADMIN);
 
-        let mut distributors: Map<Address, bool> = env
-            .storage()
-            .instance()
-            .get(&KEY_DISTRIBUTORS)
-            .unwrap_or(Map::new(&env));
-        distributors.remove(addr);
-        env.storage()
-            .instance()
-            .set(&KEY_DISTRIBUTORS, &distributors);
+        AdminTransferred {
+            previous_admin,
+            new_admin: pending.new_admin,
+            timestamp: now,
+        }
+        .publish(&env);
 
         Ok(())
     }
 
-    pub fn set_config(env: Env, config: Config) -> Result<(), Error> {
+    pub fn cancel_admin_transfer(env: Env) -> Result<(), Error> {
         let admin = Self::get_admin(env.clone())?;
         admin.require_auth();
+        let pending = Self::get_pending_admin(env.clone()).ok_or(Error::NoPendingAdmin)?;
 
-        if config.min_amount <= 0 {
-            return Err(Error::InvalidAmount);
+        env.storage().instance().remove(&KEY_PENDING_ADMIN);
+
+        AdminTransferCancelled {
+            admin,
+            new_admin: pending.new_admin,
+            timestamp: env.ledger().timestamp(),
         }
+        .publish(&env);
 
-        env.storage().instance().set(&KEY_CONFIG, &config);
         Ok(())
     }
 
-    pub fn pause(env: Env) -> Result<(), Error> {
-        let admin = Self::get_admin(env.clone())?;
-        admin.require_auth();
-        env.storage().instance().set(&KEY_PAUSED, &true);
-        ContractPausedEvent { admin }.publish(&env);
-        Ok(())
+    pub fn get_pending_admin(env: Env) -> Option<PendingAdmin> {
+        env.storage().instance().get(&KEY_PENDING_ADMIN)
     }
 
-    pub fn unpause(env: Env) -> Result<(), Error> {
-        let admin = Self::get_admin(env.clone())?;
-        admin.require_auth();
-        env.storage().instance().set(&KEY_PAUSED, &false);
-        ContractUnpausedEvent { admin }.publish(&env);
+    /// Admin permanently gives up control. Afterwards only recipient claims and expiry
+    /// work: no funding, package creation, admin actions or proposals. The contract is
+    /// unpaused so recipients can always claim. With multisig on this goes through an
+    /// `AdminAction::RenounceAdmin` proposal.
+    pub fn renounce_admin(env: Env) -> Result<(), Error> {
+        let admin = Self::require_single_admin(&env)?;
+        Self::apply_renounce(&env, admin);
         Ok(())
     }
 
-    pub fn is_paused(env: Env) -> bool {
-        env.storage().instance().get(&KEY_PAUSED).unwrap_or(false)
+    fn apply_renounce(env: &Env, admin: Address) {
+        env.storage().instance().remove(&KEY_PENDING_ADMIN);
+        env.storage().instance().remove(&KEY_PAUSES);
+        env.storage().instance().set(&KEY_RENOUNCED, &true);
+
+        AdminRenounced {
+            admin,
+            timestamp: env.ledger().timestamp(),
+        }
+        .publish(env);
     }
 
-    pub fn get_config(env: Env) -> Config {
-        env.storage().instance().get(&KEY_CONFIG).unwrap_or(Config {
-            min_amount: 1,
-            max_expires_in: 0,
-            allowed_tokens: Vec::new(&env),
-        })
+    pub fn is_renounced(env: Env) -> bool {
+        env.storage()
+            .instance()
+            .get(&KEY_RENOUNCED)
+            .unwrap_or(false)
     }
 
-    // --- Funding & Packages ---
+    /// The storage layout version. Contracts initialised before the layout marker existed are
+    /// on the v1 layout whatever their old version label says.
+    pub fn get_version(env: Env) -> u32 {
+        if let Some(layout) = env.storage().instance().get(&KEY_LAYOUT) {
+            return layout;
+        }
+        if env.storage().instance().has(&KEY_ADMIN) {
+            return 1;
+        }
+        0
+    }
 
-    /// Funds the contract (Pool Model).
-    /// Transfers `amount` of `token` from `from` to this contract.
-    /// This increases the contract's balance, allowing new packages to be created.
-    pub fn fund(env: Env, token: Address, from: Address, amount: i128) -> Result<(), Error> {
-        if amount <= 0 {
-            return Err(Error::InvalidAmount);
+    /// The layout version the last `upgrade` declared, while storage has not reached it yet.
+    pub fn get_upgrade_target(env: Env) -> Option<u32> {
+        env.storage().instance().get(&KEY_UPGRADE_TARGET)
+    }
+
+    /// Replaces the contract code. `new_version` is the storage version the new code expects
+    /// and cannot be below the current one. Until `migrate` has brought storage up to it,
+    /// package reads and fund movements fail with `MigrationInProgress`.
+    pub fn upgrade(env: Env, wasm_hash: BytesN<32>, new_version: u32) -> Result<(), Error> {
+        Self::require_single_admin(&env)?;
+        Self::check_not_timelocked(&env, &AdminAction::Upgrade(wasm_hash.clone(), new_version))?;
+        Self::apply_upgrade(&env, wasm_hash, new_version)
+    }
+
+    fn apply_upgrade(env: &Env, wasm_hash: BytesN<32>, new_version: u32) -> Result<(), Error> {
+        let old_version = Self::get_version(env.clone());
+        if new_version < old_version {
+            return Err(Error::InvalidMigration);
+        }
+        if env.storage().instance().has(&KEY_MIGRATION) {
+            return Err(Error::MigrationInProgress);
         }
-        from.require_auth();
 
-        // Perform transfer: From -> Contract
-        let token_client = token::Client::new(&env, &token);
-        token_client.transfer(&from, env.current_contract_address(), &amount);
+        env.deployer()
+            .update_current_contract_wasm(wasm_hash.clone());
+        if new_version > old_version {
+            env.storage()
+                .instance()
+                .set(&KEY_UPGRADE_TARGET, &new_version);
+        } else {
+            env.storage().instance().remove(&KEY_UPGRADE_TARGET);
+        }
 
-        let timestamp = env.ledger().timestamp();
-        EscrowFunded {
-            from,
-            token,
-            amount,
-            timestamp,
+        Upgraded {
+            old_version,
+            new_version,
+            wasm_hash,
+            timestamp: env.ledger().timestamp(),
         }
-        .publish(&env);
+        .publish(env);
 
         Ok(())
     }
 
-    /// Creates a package with a specific ID.
-    /// Locks funds from the available pool (Contract Balance - Total Locked).
-    pub fn create_package(
-        env: Env,
-        operator: Address,
-        id: u64,
-        recipient: Address,
-        amount: i128,
-        token: Address,
-        expires_at: u64,
-    ) -> Result<u64, Error> {
-        Self::check_paused(&env)?;
-        Self::require_admin_or_distributor(&env, &operator)?;
+    /// Starts the registered migration to `new_version`, which must be exactly one above the
+    /// current version. Small migrations finish in this call; large ones are continued with
+    /// `continue_migration`. Until it finishes, package reads and every call that moves funds
+    /// fail with `MigrationInProgress`.
+    pub fn migrate(env: Env, new_version: u32) -> Result<(), Error> {
+        Self::require_single_admin(&env)?;
+        Self::check_not_timelocked(&env, &AdminAction::Migrate(new_version))?;
+        Self::apply_migration(&env, new_version)
+    }
+
+    /// Migrates up to `limit` more items of the running migration. Anyone can call it.
+    /// Returns true once the migration has finished.
+    pub fn continue_migration(env: Env, limit: u32) -> Result<bool, Error> {
+        let state: MigrationState = env
+            .storage()
+            .instance()
+            .get(&KEY_MIGRATION)
+            .ok_or(Error::InvalidState)?;
+        Ok(Self::run_migration(&env, state, limit))
+    }
+
+    pub fn get_migration(env: Env) -> Option<MigrationState> {
+        env.storage().instance().get(&KEY_MIGRATION)
+    }
+
+    fn apply_migration(env: &Env, new_version: u32) -> Result<(), Error> {
+        if env.storage().instance().has(&KEY_MIGRATION) {
+            return Err(Error::MigrationInProgress);
+        }
+        let current_version = Self::get_version(env.clone());
+        if new_version != current_version + 1 || new_version > LATEST_VERSION {
+            return Err(Error::InvalidMigration);
+        }
+
+        Self::begin_migration_step(env, new_version);
+        let state = MigrationState {
+            from_version: current_version,
+            to_version: new_version,
+            cursor: 0,
+        };
+        Self::run_migration(env, state, MIGRATION_BATCH_SIZE);
+        Ok(())
+    }
+
+    /// Registered migration steps, part 1: small one-off changes made when the step starts.
+    fn begin_migration_step(env: &Env, to_version: u32) {
+        if to_version == 2 {
+            // Config gained reassignment and metadata settings
+            let old: ConfigV1 = env.storage().instance().get(&KEY_CONFIG).unwrap();
+            let config = Config {
+                min_amount: old.min_amount,
+                max_expires_in: old.max_expires_in,
+                allowed_tokens: old.allowed_tokens,
+                reassignment_delay: 0,
+                metadata_keys: Vec::new(env),
+                max_metadata_entries: 0,
+                max_metadata_value_len: 0,
+            };
+            env.storage().instance().set(&KEY_CONFIG, &config);
+
+            // Distributor flags become unrestricted distributor records
+            let old: Map<Address, bool> = env
+                .storage()
+                .instance()
+                .get(&KEY_LEGACY_DISTRIBUTORS)
+                .unwrap_or(Map::new(env));
+            let mut distributors = Map::new(env);
+            for (addr, enabled) in old.iter() {
+                if enabled {
+                    let record = DistributorRecord {
+                        allowed_tokens: Vec::new(env),
+                        max_amount: 0,
+                        max_expires_in: 0,
+                        campaign: None,
+                        active_until: 0,
+                        packages_created: 0,
+                        total_committed: 0,
+                        last_active: 0,
+                    };
+                    distributors.set(addr, record);
+                }
+            }
+            env.storage()
+                .instance()
+                .set(&KEY_LEGACY_DISTRIBUTORS, &distributors);
+
+            // The global pause flag becomes an `All` pause
+            let paused: bool = env
+                .storage()
+                .instance()
+                .get(&KEY_LEGACY_PAUSED)
+                .unwrap_or(false);
+            env.storage().instance().remove(&KEY_LEGACY_PAUSED);
+            if paused {
+                let mut pauses = Self::read_pauses(env);
+                let pause = PauseInfo {
+                    reason: None,
+                    paused_by: Self::migration_actor(env),
+                    paused_at: env.ledger().timestamp(),
+                    expires_at: 0,
+                };
+                pauses.set((PauseScope::All, None), pause);
+                env.storage().instance().set(&KEY_PAUSES, &pauses);
+            }
+        }
+
+        if to_version == 3 {
+            // Distributor records and locked totals move out of instance storage
+            let distributors: Map<Address, DistributorRecord> = env
+                .storage()
+                .instance()
+                .get(&KEY_LEGACY_DISTRIBUTORS)
+                .unwrap_or(Map::new(env));
+            for (addr, record) in distributors.iter() {
+                Self::write_distributor(env, &addr, &record);
+                Self::index_distributor(env, &addr);
+            }
+            env.storage().instance().remove(&KEY_LEGACY_DISTRIBUTORS);
+
+            let locked: Map<Address, i128> = env
+                .storage()
+                .instance()
+                .get(&KEY_LEGACY_LOCKED)
+                .unwrap_or(Map::new(env));
+            for (token, amount) in locked.iter() {
+                Self::write_locked(env, &token, amount);
+            }
+            env.storage().instance().remove(&KEY_LEGACY_LOCKED);
+        }
+    }
+
+    /// The stored admin, recorded as creator of migrated packages and author of the migrated
+    /// pause. Still readable after `renounce_admin`.
+    fn migration_actor(env: &Env) -> Address {
+        env.storage()
+            .instance()
+            .get(&KEY_ADMIN)
+            .unwrap_or(env.current_contract_address())
+    }
+
+    /// Registered migration steps, part 2: per-package rewrites run `limit` at a time.
+    /// Finishes the step (bumping `KEY_LAYOUT`) once every package is done.
+    fn run_migration(env: &Env, mut state: MigrationState, limit: u32) -> bool {
+        let total: u64 = match state.to_version {
+            2 => env.storage().instance().get(&KEY_PKG_IDX).unwrap_or(0),
+            _ => 0,
+        };
+        let end = total.min(state.cursor + limit as u64);
+        if state.to_version == 2 {
+            // Packages gained kind, partial claims, claim windows, funding and creator fields
+            let admin = Self::migration_actor(env);
+            for idx in state.cursor..end {
+                let id: u64 = env
+                    .storage()
+                    .persistent()
+                    .get(&(symbol_short!("pidx"), idx))
+                    .unwrap();
+                let old: PackageV1 = env
+                    .storage()
+                    .persistent()
+                    .get(&(symbol_short!("pkg"), id))
+                    .unwrap();
+                let package = Package {
+                    id: old.id,
+                    recipient: old.recipient,
+                    amount: old.amount,
+                    token: old.token,
+                    status: old.status,
+                    created_at: old.created_at,
+                    expires_at: old.expires_at,
+                    metadata: old.metadata,
+                    kind: PackageKind::Standard,
+                    claimed_amount: 0,
+                    claimable_from: 0,
+                    funder: None,
+                    earmark: None,
+                    created_by: admin.clone(),
+                };
+                Self::save_package(env, &package);
+            }
+        }
+        state.cursor = end;
+
+        if state.cursor < total {
+            env.storage().instance().set(&KEY_MIGRATION, &state);
+            return false;
+        }
+
+        env.storage().instance().remove(&KEY_MIGRATION);
+        env.storage().instance().remove(&KEY_VERSION);
+        env.storage().instance().set(&KEY_LAYOUT, &state.to_version);
+        if Self::get_upgrade_target(env.clone()).is_some_and(|target| target <= state.to_version) {
+            env.storage().instance().remove(&KEY_UPGRADE_TARGET);
+        }
+        Migrated {
+            from_version: state.from_version,
+            to_version: state.to_version,
+            timestamp: env.ledger().timestamp(),
+        }
+        .publish(env);
+        true
+    }
+
+    /// Adds a distributor, or replaces an existing distributor's scope (its stats are kept).
+    /// With multisig on this goes through an `AdminAction::AddDistributor` proposal.
+    pub fn add_distributor(
+        env: Env,
+        addr: Address,
+        record: DistributorRecord,
+    ) -> Result<(), Error> {
+        let admin = Self::require_single_admin(&env)?;
+        Self::apply_add_distributor(&env, &admin, addr, record)
+    }
+
+    fn apply_add_distributor(
+        env: &Env,
+        admin: &Address,
+        addr: Address,
+        record: DistributorRecord,
+    ) -> Result<(), Error> {
+        if record.max_amount < 0 {
+            return Err(Error::InvalidAmount);
+        }
+
+        Self::store_distributor(env, admin, addr, Some(record));
+        Ok(())
+    }
+
+    pub fn remove_distributor(env: Env, addr: Address) -> Result<(), Error> {
+        let admin = Self::get_admin(env.clone())?;
+        admin.require_auth();
+
+        Self::store_distributor(&env, &admin, addr, None);
+        Ok(())
+    }
+
+    pub fn get_distributor(env: Env, addr: Address) -> Option<DistributorRecord> {
+        env.storage()
+            .persistent()
+            .get(&(symbol_short!("dist"), addr))
+    }
+
+    /// Distributors in index order, `limit` entries starting at position `start`. Removing a
+    /// distributor moves the last one into its position.
+    pub fn list_distributors(
+        env: Env,
+        start: u32,
+        limit: u32,
+    ) -> Vec<(Address, DistributorRecord)> {
+        let storage = env.storage().persistent();
+        let count: u32 = storage.get(&KEY_DIST_COUNT).unwrap_or(0);
+        let end = count.min(start.saturating_add(limit));
+        let mut page = Vec::new(&env);
+        for i in start..end {
+            let addr: Address = storage.get(&(symbol_short!("didx"), i)).unwrap();
+            let record = Self::get_distributor(env.clone(), addr.clone()).unwrap();
+            page.push_back((addr, record));
+        }
+        page
+    }
+
+    fn read_distributor(env: &Env, addr: &Address) -> Option<DistributorRecord> {
+        Self::read_persistent(env, &(symbol_short!("dist"), addr.clone()))
+    }
+
+    fn write_distributor(env: &Env, addr: &Address, record: &DistributorRecord) {
+        Self::write_persistent(env, &(symbol_short!("dist"), addr.clone()), record);
+    }
+
+    /// Appends a distributor to the `("didx", i)` index, remembering its position.
+    fn index_distributor(env: &Env, addr: &Address) {
+        let count: u32 = Self::read_persistent(env, &KEY_DIST_COUNT).unwrap_or(0);
+        Self::write_persistent(env, &(symbol_short!("didx"), count), addr);
+        Self::write_persistent(env, &(symbol_short!("dpos"), addr.clone()), &count);
+        Self::write_persistent(env, &KEY_DIST_COUNT, &(count + 1));
+    }
+
+    /// Removes a distributor from the index, moving the last entry into its position.
+    fn unindex_distributor(env: &Env, addr: &Address) {
+        let pos_key = (symbol_short!("dpos"), addr.clone());
+        let Some(pos) = Self::read_persistent::<_, u32>(env, &pos_key) else {
+            return;
+        };
+        let count: u32 = Self::read_persistent(env, &KEY_DIST_COUNT).unwrap_or(0);
+        let last = count - 1;
+        let storage = env.storage().persistent();
+        if pos != last {
+            let moved: Address =
+                Self::read_persistent(env, &(symbol_short!("didx"), last)).unwrap();
+            Self::write_persistent(env, &(symbol_short!("didx"), pos), &moved);
+            Self::write_persistent(env, &(symbol_short!("dpos"), moved), &pos);
+        }
+        storage.remove(&(symbol_short!("didx"), last));
+        storage.remove(&pos_key);
+        Self::write_persistent(env, &KEY_DIST_COUNT, &last);
+    }
+
+    /// Adds, updates (keeping stats) or removes a distributor and emits the matching event.
+    fn store_distributor(
+        env: &Env,
+        actor: &Address,
+        addr: Address,
+        record: Option<DistributorRecord>,
+    ) {
+        let existing = Self::read_distributor(env, &addr);
+        let timestamp = env.ledger().timestamp();
+        match record {
+            Some(mut record) => {
+                let (packages_created, total_committed, last_active) = match &existing {
+                    Some(old) => (old.packages_created, old.total_committed, old.last_active),
+                    None => (0, 0, 0),
+                };
+                record.packages_created = packages_created;
+                record.total_committed = total_committed;
+                record.last_active = last_active;
+                Self::write_distributor(env, &addr, &record);
+                if existing.is_none() {
+                    Self::index_distributor(env, &addr);
+                }
+                DistributorAdded {
+                    distributor: addr,
+                    actor: actor.clone(),
+                    timestamp,
+                }
+                .publish(env);
+            }
+            None => {
+                if existing.is_none() {
+                    return;
+                }
+                env.storage()
+                    .persistent()
+                    .remove(&(symbol_short!("dist"), addr.clone()));
+                Self::unindex_distributor(env, &addr);
+                DistributorRemoved {
+                    distributor: addr,
+                    actor: actor.clone(),
+                    timestamp,
+                }
+                .publish(env);
+            }
+        }
+    }
+
+    /// Adds `packages` new packages worth `amount` to a distributor's stats; no-op for others.
+    fn record_distributor_activity(env: &Env, operator: &Address, packages: u32, amount: i128) {
+        let Some(mut record) = Self::read_distributor(env, operator) else {
+            return;
+        };
+        record.packages_created += packages;
+        record.total_committed += amount;
+        record.last_active = env.ledger().timestamp();
+        Self::write_distributor(env, operator, &record);
+    }
+
+    /// Admin grants `role` to `account`. Granting `Distributor` adds an unrestricted distributor
+    /// (an existing distributor keeps its scope).
+    /// With multisig on this goes through an `AdminAction::GrantRole` proposal.
+    pub fn grant_role(env: Env, role: Role, account: Address) -> Result<(), Error> {
+        let admin = Self::require_single_admin(&env)?;
+        Self::apply_grant_role(&env, admin, role, account);
+        Ok(())
+    }
+
+    fn apply_grant_role(env: &Env, admin: Address, role: Role, account: Address) {
+        if role == Role::Distributor {
+            if Self::read_distributor(env, &account).is_none() {
+                let record = DistributorRecord {
+                    allowed_tokens: Vec::new(env),
+                    max_amount: 0,
+                    max_expires_in: 0,
+                    campaign: None,
+                    active_until: 0,
+                    packages_created: 0,
+                    total_committed: 0,
+                    last_active: 0,
+                };
+                Self::store_distributor(env, &admin, account.clone(), Some(record));
+            }
+        } else {
+            let mut members = Self::load_role_members(env, role);
+            if !members.contains(&account) {
+                members.push_back(account.clone());
+                Self::write_persistent(env, &(symbol_short!("role"), role), &members);
+            }
+        }
+
+        RoleGranted {
+            role,
+            account,
+            admin,
+            timestamp: env.ledger().timestamp(),
+        }
+        .publish(env);
+    }
+
+    pub fn revoke_role(env: Env, role: Role, account: Address) -> Result<(), Error> {
+        let admin = Self::get_admin(env.clone())?;
+        admin.require_auth();
+
+        if role == Role::Distributor {
+            Self::store_distributor(&env, &admin, account.clone(), None);
+        } else {
+            let mut members = Self::load_role_members(&env, role);
+            if let Some(index) = members.first_index_of(&account) {
+                members.remove(index);
+                Self::write_persistent(&env, &(symbol_short!("role"), role), &members);
+            }
+        }
+
+        RoleRevoked {
+            role,
+            account,
+            admin,
+            timestamp: env.ledger().timestamp(),
+        }
+        .publish(&env);
+
+        Ok(())
+    }
+
+    /// Explicit membership only; the admin passes every role check without being listed.
+    pub fn has_role(env: Env, role: Role, account: Address) -> bool {
+        if role == Role::Distributor {
+            return Self::get_distributor(env, account).is_some();
+        }
+        Self::get_role_members(env, role).contains(&account)
+    }
+
+    /// Members of `role`. For `Distributor` only the first `MAX_ROLE_MEMBERS` (100) are
+    /// returned; use `list_distributors` to page through all of them.
+    pub fn get_role_members(env: Env, role: Role) -> Vec<Address> {
+        if role == Role::Distributor {
+            let mut members = Vec::new(&env);
+            for (addr, _) in Self::list_distributors(env.clone(), 0, MAX_ROLE_MEMBERS).iter() {
+                members.push_back(addr);
+            }
+            return members;
+        }
+        env.storage()
+            .persistent()
+            .get(&(symbol_short!("role"), role))
+            .unwrap_or(Vec::new(&env))
+    }
+
+    pub fn set_config(env: Env, config: Config) -> Result<(), Error> {
+        Self::require_single_admin(&env)?;
+        let action = AdminAction::SetConfig(config.clone());
+        Self::check_not_timelocked(&env, &action)?;
+        Self::apply_config(&env, config)
+    }
+
+    /// Adds or removes one token from the allowlist without resubmitting the whole config.
+    pub fn set_token_allowed(env: Env, token: Address, allowed: bool) -> Result<(), Error> {
+        Self::require_single_admin(&env)?;
+        let action = AdminAction::SetTokenAllowed(token.clone(), allowed);
+        Self::check_not_timelocked(&env, &action)?;
+        Self::apply_token_allowed(&env, token, allowed)
+    }
+
+    fn apply_token_allowed(env: &Env, token: Address, allowed: bool) -> Result<(), Error> {
+        let mut config = Self::get_config(env.clone());
+        let index = config.allowed_tokens.first_index_of(&token);
+        match (index, allowed) {
+            (None, true) => config.allowed_tokens.push_back(token),
+            (Some(i), false) => {
+                config.allowed_tokens.remove(i);
+            }
+            _ => return Ok(()),
+        }
+        Self::apply_config(env, config)
+    }
+
+    fn apply_config(env: &Env, config: Config) -> Result<(), Error> {
+        if config.min_amount <= 0 {
+            return Err(Error::InvalidAmount);
+        }
+
+        env.storage().instance().set(&KEY_CONFIG, &config);
+        Ok(())
+    }
+
+    /// Pauses `scope` for every token (`token` = None) or a single token, until unpaused or,
+    /// with `expires_at` > 0, until that timestamp. Pausing again replaces the entry.
+    /// The admin, Pausers and EmergencyGuardians can pause; guardians cannot shorten an
+    /// active pause.
+    pub fn pause(
+        env: Env,
+        caller: Address,
+        scope: PauseScope,
+        token: Option<Address>,
+        reason: Option<String>,
+        expires_at: u64,
+    ) -> Result<(), Error> {
+        Self::require_any_role(&env, &[Role::Pauser, Role::EmergencyGuardian], &caller)?;
+        let now = env.ledger().timestamp();
+        if expires_at != 0 && expires_at <= now {
+            return Err(Error::InvalidState);
+        }
+
+        let mut pauses = Self::read_pauses(&env);
+        // Guardians can only pause or lengthen a pause; shortening it would be an unpause
+        let guardian_only = caller != Self::get_admin(env.clone())?
+            && !Self::is_role_member(&env, Role::Pauser, &caller);
+        if guardian_only
+            && let Some(existing) = pauses.get((scope, token.clone()))
+            && (existing.expires_at == 0 || now < existing.expires_at)
+            && (existing.expires_at == 0 || (expires_at != 0 && expires_at <= existing.expires_at))
+        {
+            return Err(Error::NotAuthorized);
+        }
+        pauses.set(
+            (scope, token.clone()),
+            PauseInfo {
+                reason: reason.clone(),
+                paused_by: caller.clone(),
+                paused_at: now,
+                expires_at,
+            },
+        );
+        Self::store_pauses(&env, pauses);
+
+        ContractPausedEvent {
+            admin: caller,
+            scope,
+            token,
+            reason,
+            expires_at,
+        }
+        .publish(&env);
+        Ok(())
+    }
+
+    /// Lifts one pause entry; admin or Pauser only.
+    pub fn unpause(
+        env: Env,
+        caller: Address,
+        scope: PauseScope,
+        token: Option<Address>,
+    ) -> Result<(), Error> {
+        Self::require_role(&env, Role::Pauser, &caller)?;
+        let mut pauses = Self::read_pauses(&env);
+        pauses.remove((scope, token.clone()));
+        Self::store_pauses(&env, pauses);
+
+        ContractUnpausedEvent {
+            admin: caller,
+            scope,
+            token,
+        }
+        .publish(&env);
+        Ok(())
+    }
+
+    /// Whether `scope` is currently paused for `token` (None = checks only all-token pauses),
+    /// counting `All` pauses and ignoring lapsed ones. Everything counts as paused while a
+    /// migration runs.
+    pub fn is_paused(env: Env, scope: PauseScope, token: Option<Address>) -> bool {
+        Self::check_paused(&env, scope, token).is_err()
+    }
+
+    /// The pause entry stored for exactly this scope and token, if any (including lapsed ones).
+    pub fn get_pause(env: Env, scope: PauseScope, token: Option<Address>) -> Option<PauseInfo> {
+        Self::read_pauses(&env).get((scope, token))
+    }
+
+    pub fn get_config(env: Env) -> Config {
+        env.storage().instance().get(&KEY_CONFIG).unwrap_or(Config {
+            min_amount: 1,
+            max_expires_in: 0,
+            allowed_tokens: Vec::new(&env),
+            reassignment_delay: 0,
+            metadata_keys: Vec::new(&env),
+            max_metadata_entries: 0,
+            max_metadata_value_len: 0,
+        })
+    }
+
+    // --- Funding & Packages ---
+
+    /// Funds the contract (Pool Model).
+    /// Transfers `amount` of `token` from `from` to this contract.
+    /// This increases the contract's balance, allowing new packages to be created.
+    pub fn fund(env: Env, token: Address, from: Address, amount: i128) -> Result<(), Error> {
+        Self::check_renounced(&env)?;
+        if amount <= 0 {
+            return Err(Error::InvalidAmount);
+        }
+        from.require_auth();
+
+        // Perform transfer: From -> Contract
+        let token_client = token::Client::new(&env, &token);
+        token_client.transfer(&from, env.current_contract_address(), &amount);
+
+        // Track the contribution so unused money can be returned to this funder
+        Self::record_contribution(&env, &from, &token, &None, amount);
+
+        let timestamp = env.ledger().timestamp();
+        EscrowFunded {
+            from: from.clone(),
+            token: token.clone(),
+            amount,
+            timestamp,
+        }
+        .publish(&env);
+
+        Self::apply_match(&env, &from, &token, amount);
+
+        Ok(())
+    }
+
+    /// Funds the earmarked sub-pool `tag` instead of the general pool. Earmarked money can only
+    /// be used by packages created with the same `earmark` and is excluded from the general
+    /// pool's solvency checks, surplus and FIFO donor attribution.
+    pub fn fund_earmarked(
+        env: Env,
+        token: Address,
+        from: Address,
+        amount: i128,
+        tag: Symbol,
+    ) -> Result<(), Error> {
+        Self::check_renounced(&env)?;
+        if amount <= 0 {
+            return Err(Error::InvalidAmount);
+        }
+        from.require_auth();
+
+        let token_client = token::Client::new(&env, &token);
+        token_client.transfer(&from, env.current_contract_address(), &amount);
+
+        let mut earmark = Self::load_earmark(&env, &tag, &token);
+        earmark.balance += amount;
+        Self::store_earmark(&env, &tag, &token, &earmark, amount);
+        Self::record_contribution(&env, &from, &token, &Some(tag.clone()), amount);
+
+        EarmarkFunded {
+            tag,
+            from,
+            token,
+            amount,
+            timestamp: env.ledger().timestamp(),
+        }
+        .publish(&env);
+
+        Ok(())
+    }
+
+    pub fn get_earmark(env: Env, tag: Symbol, token: Address) -> Earmark {
+        let earmark_key = (symbol_short!("earmark"), tag, token);
+        env.storage()
+            .persistent()
+            .get(&earmark_key)
+            .unwrap_or(Earmark {
+                balance: 0,
+                locked: 0,
+            })
+    }
+
+    /// Creates a package with a specific ID.
+    /// Locks funds from the available pool (Contract Balance - Total Locked).
+    pub fn create_package(
+        env: Env,
+        operator: Address,
+        id: u64,
+        recipient: Address,
+        amount: i128,
+        token: Address,
+        expires_at: u64,
+    ) -> Result<u64, Error> {
+        let options = PackageOptions {
+            metadata: Map::new(&env),
+            claimable_from: 0,
+            funder: None,
+            earmark: None,
+        };
+        Self::create_package_with_options(
+            env, operator, id, recipient, amount, token, expires_at, options,
+        )
+    }
+
+    /// Same as `create_package`, with optional settings such as initial metadata or a claim
+    /// window start. Metadata must satisfy the key allowlist and size limits in `Config`, and
+    /// `claimable_from` must be before `expires_at`; `max_expires_in` is measured from it.
+    #[allow(clippy::too_many_arguments)]
+    pub fn create_package_with_options(
+        env: Env,
+        operator: Address,
+        id: u64,
+        recipient: Address,
+        amount: i128,
+        token: Address,
+        expires_at: u64,
+        options: PackageOptions,
+    ) -> Result<u64, Error> {
+        Self::check_paused(&env, PauseScope::Creation, Some(token.clone()))?;
+        Self::require_admin_or_distributor(&env, &operator)?;
+        let config = Self::get_config(env.clone());
+        Self::validate_metadata(&config, &options.metadata)?;
+
+        let package = Package {
+            id,
+            recipient,
+            amount,
+            token,
+            status: PackageStatus::Created,
+            created_at: env.ledger().timestamp(),
+            expires_at,
+            metadata: options.metadata,
+            kind: PackageKind::Standard,
+            claimed_amount: 0,
+            claimable_from: options.claimable_from,
+            funder: options.funder,
+            earmark: options.earmark,
+            created_by: operator.clone(),
+        };
+        Self::lock_new_package(&env, &operator, &package, expires_at)?;
+
+        Ok(id)
+    }
+
+    /// Creates a package whose amount is released in tranches.
+    /// The package amount is the sum of all tranches and is locked up front; the recipient
+    /// can `claim` whatever has unlocked so far while the remainder stays locked.
+    /// `tranches` must be ordered by `unlocks_at` and all unlock before `expires_at` (if set).
+    pub fn create_tranche_package(
+        env: Env,
+        operator: Address,
+        id: u64,
+        recipient: Address,
+        token: Address,
+        tranches: Vec<Tranche>,
+        expires_at: u64,
+    ) -> Result<u64, Error> {
+        Self::check_paused(&env, PauseScope::Creation, Some(token.clone()))?;
+        Self::require_admin_or_distributor(&env, &operator)?;
+
+        if tranches.is_empty() || tranches.len() > MAX_TRANCHES {
+            return Err(Error::InvalidSchedule);
+        }
+
+        let mut amount: i128 = 0;
+        let mut last_unlock: u64 = 0;
+        for tranche in tranches.iter() {
+            if tranche.amount <= 0 || tranche.unlocks_at < last_unlock {
+                return Err(Error::InvalidSchedule);
+            }
+            amount += tranche.amount;
+            last_unlock = tranche.unlocks_at;
+        }
+        if expires_at > 0 && last_unlock > expires_at {
+            return Err(Error::InvalidSchedule);
+        }
+
+        let package = Package {
+            id,
+            recipient,
+            amount,
+            token,
+            status: PackageStatus::Created,
+            created_at: env.ledger().timestamp(),
+            expires_at,
+            metadata: Map::new(&env),
+            kind: PackageKind::Tranched,
+            claimed_amount: 0,
+            claimable_from: 0,
+            funder: None,
+            earmark: None,
+            created_by: operator.clone(),
+        };
+        Self::lock_new_package(&env, &operator, &package, expires_at)?;
+
+        let sched_key = (symbol_short!("sched"), id);
+        Self::write_package_entry(&env, &sched_key, &tranches);
+
+        Ok(id)
+    }
+
+    /// Creates a package for a beneficiary who has no wallet yet.
+    /// Instead of a recipient, the package stores the ed25519 `voucher_key` whose signature
+    /// authorizes a claim (see `claim_with_voucher`). Until claimed, `recipient` holds this
+    /// contract's own address as a placeholder.
+    pub fn create_voucher_package(
+        env: Env,
+        operator: Address,
+        id: u64,
+        voucher_key: BytesN<32>,
+        amount: i128,
+        token: Address,
+        expires_at: u64,
+    ) -> Result<u64, Error> {
+        Self::check_paused(&env, PauseScope::Creation, Some(token.clone()))?;
+        Self::require_admin_or_distributor(&env, &operator)?;
+
+        let package = Package {
+            id,
+            recipient: env.current_contract_address(),
+            amount,
+            token,
+            status: PackageStatus::Created,
+            created_at: env.ledger().timestamp(),
+            expires_at,
+            metadata: Map::new(&env),
+            kind: PackageKind::Voucher,
+            claimed_amount: 0,
+            claimable_from: 0,
+            funder: None,
+            earmark: None,
+            created_by: operator.clone(),
+        };
+        Self::lock_new_package(&env, &operator, &package, expires_at)?;
+
+        let voucher_key_slot = (symbol_short!("vchr"), id);
+        Self::write_package_entry(&env, &voucher_key_slot, &voucher_key);
+
+        Ok(id)
+    }
+
+    /// Creates a package claimable by whoever knows the preimage of `secret_hash` (sha256),
+    /// e.g. a code distributed by SMS or scratch card. Claims go through `commit_claim` and
+    /// `claim_with_secret`. Until claimed, `recipient` holds this contract's own address.
+    /// The secret must be at least `MIN_SECRET_LEN` (16) random bytes or it cannot be claimed.
+    pub fn create_hashlock_package(
+        env: Env,
+        operator: Address,
+        id: u64,
+        secret_hash: BytesN<32>,
+        amount: i128,
+        token: Address,
+        expires_at: u64,
+    ) -> Result<u64, Error> {
+        Self::check_paused(&env, PauseScope::Creation, Some(token.clone()))?;
+        Self::require_admin_or_distributor(&env, &operator)?;
+
+        let package = Package {
+            id,
+            recipient: env.current_contract_address(),
+            amount,
+            token,
+            status: PackageStatus::Created,
+            created_at: env.ledger().timestamp(),
+            expires_at,
+            metadata: Map::new(&env),
+            kind: PackageKind::HashLock,
+            claimed_amount: 0,
+            claimable_from: 0,
+            funder: None,
+            earmark: None,
+            created_by: operator.clone(),
+        };
+        Self::lock_new_package(&env, &operator, &package, expires_at)?;
+
+        let hash_key = (symbol_short!("hlock"), id);
+        Self::write_package_entry(&env, &hash_key, &secret_hash);
+
+        Ok(id)
+    }
+
+    /// Creates a package whose amount accrues linearly from `start_at` to `end_at`.
+    /// The recipient can `claim` whatever has accrued at any time. Streams do not expire;
+    /// the admin can pause, resume or stop them instead. `max_expires_in` applies to `end_at`.
+    #[allow(clippy::too_many_arguments)]
+    pub fn create_stream_package(
+        env: Env,
+        operator: Address,
+        id: u64,
+        recipient: Address,
+        amount: i128,
+        token: Address,
+        start_at: u64,
+        end_at: u64,
+    ) -> Result<u64, Error> {
+        Self::check_paused(&env, PauseScope::Creation, Some(token.clone()))?;
+        Self::require_admin_or_distributor(&env, &operator)?;
+
+        if end_at <= start_at {
+            return Err(Error::InvalidSchedule);
+        }
+
+        let package = Package {
+            id,
+            recipient,
+            amount,
+            token,
+            status: PackageStatus::Created,
+            created_at: env.ledger().timestamp(),
+            expires_at: 0,
+            metadata: Map::new(&env),
+            kind: PackageKind::Stream,
+            claimed_amount: 0,
+            claimable_from: 0,
+            funder: None,
+            earmark: None,
+            created_by: operator.clone(),
+        };
+        Self::lock_new_package(&env, &operator, &package, end_at)?;
+
+        let stream = StreamSchedule {
+            start_at,
+            end_at,
+            paused: false,
+            paused_at: 0,
+        };
+        let stream_key = (symbol_short!("stream"), id);
+        Self::write_package_entry(&env, &stream_key, &stream);
+
+        Ok(id)
+    }
+
+    /// Creates multiple packages in a single transaction for multiple recipients.
+    /// Uses an auto-incrementing counter for package IDs.
+    pub fn batch_create_packages(
+        env: Env,
+        operator: Address,
+        recipients: Vec<Address>,
+        amounts: Vec<i128>,
+        token: Address,
+        expires_in: u64,
+    ) -> Result<Vec<u64>, Error> {
+        let options = PackageOptions {
+            metadata: Map::new(&env),
+            claimable_from: 0,
+            funder: None,
+            earmark: None,
+        };
+        Self::batch_create_with_options(
+            env, operator, recipients, amounts, token, expires_in, options,
+        )
+    }
+
+    /// Same as `batch_create_packages`; `options` (e.g. campaign metadata) apply to every package.
+    /// With `options.claimable_from` set, `expires_in` counts from when the claim window opens.
+    pub fn batch_create_with_options(
+        env: Env,
+        operator: Address,
+        recipients: Vec<Address>,
+        amounts: Vec<i128>,
+        token: Address,
+        expires_in: u64,
+        options: PackageOptions,
+    ) -> Result<Vec<u64>, Error> {
+        Self::check_paused(&env, PauseScope::Creation, Some(token.clone()))?;
+        Self::require_admin_or_distributor(&env, &operator)?;
+        let config = Self::get_config(env.clone());
+        Self::validate_metadata(&config, &options.metadata)?;
+
+        // Validate array lengths match
+        if recipients.len() != amounts.len() {
+            return Err(Error::MismatchedArrays);
+        }
+
+        let token_client = token::Client::new(&env, &token);
+        let contract_balance = token_client.balance(&env.current_contract_address());
+
+        let mut current_locked = Self::read_locked(&env, &token);
+        let reserved = if options.earmark.is_none() {
+            Self::reserved_total(&env, &token)
+        } else {
+            0
+        };
+
+        // Read the current package counter
+        let mut counter: u64 = env.storage().instance().get(&KEY_PKG_COUNTER).unwrap_or(0);
+        // Read the current aggregation index
+        let mut idx: u64 = env.storage().instance().get(&KEY_PKG_IDX).unwrap_or(0);
+
+        let created_at = env.ledger().timestamp();
+        let expires_at = created_at.max(options.claimable_from) + expires_in;
+
+        let mut created_ids: Vec<u64> = Vec::new(&env);
+        let mut total_amount: i128 = 0;
+
+        for i in 0..recipients.len() {
+            let recipient = recipients.get(i).unwrap();
+            let amount = amounts.get(i).unwrap();
+
+            // Validate amount
+            if amount <= 0 {
+                return Err(Error::InvalidAmount);
+            }
+
+            // Check solvency
+            if contract_balance - reserved < current_locked + amount {
+                return Err(Error::InsufficientFunds);
+            }
+
+            // Assign ID and increment counter
+            let id = counter;
+            counter += 1;
+
+            // Create package
+            let package = Package {
+                id,
+                recipient: recipient.clone(),
+                amount,
+                token: token.clone(),
+                status: PackageStatus::Created,
+                created_at,
+                expires_at,
+                metadata: options.metadata.clone(),
+                kind: PackageKind::Standard,
+                claimed_amount: 0,
+                claimable_from: options.claimable_from,
+                funder: options.funder.clone(),
+                earmark: options.earmark.clone(),
+                created_by: operator.clone(),
+            };
+
+            Self::check_distributor_scope(&env, &operator, &package, expires_at)?;
+            Self::consume_allowance(&env, &operator, &token, amount)?;
+            Self::allocate_funding(&env, &package)?;
+            Self::save_package(&env, &package);
+
+            // Track package index for aggregation
+            Self::store_index(&env, idx, id);
+            idx += 1;
+
+            // Update locked
+            current_locked += amount;
+            total_amount += amount;
+
+            PackageCreated {
+                package_id: id,
+                recipient: recipient.clone(),
+                amount,
+                actor: operator.clone(),
+                timestamp: created_at,
+            }
+            .publish(&env);
+
+            created_ids.push_back(id);
+        }
+
+        // Persist updated locked total, counter, and aggregation index
+        Self::write_locked(&env, &token, current_locked);
+        env.storage().instance().set(&KEY_PKG_COUNTER, &counter);
+        env.storage().instance().set(&KEY_PKG_IDX, &idx);
+        Self::record_distributor_activity(&env, &operator, created_ids.len(), total_amount);
+
+        // Emit batch event
+        BatchCreatedEvent {
+            ids: created_ids.clone(),
+            admin: operator,
+            total_amount,
+        }
+        .publish(&env);
+
+        Ok(created_ids)
+    }
+
+    // --- Recipient Actions ---
+
+    /// Recipient claims the package.
+    /// For tranched and streaming packages only the unlocked or accrued, not yet claimed part
+    /// is released; the package stays `Created` until everything has been claimed.
+    pub fn claim(env: Env, id: u64) -> Result<(), Error> {
+        let mut unlocks = Map::new(&env);
+        Self::claim_package(&env, id, None, &mut unlocks)?;
+        Self::release_locked(&env, &unlocks);
+        Ok(())
+    }
+
+    /// Claims several packages in one transaction; each package needs its recipient's auth.
+    /// With `atomic` the first failure aborts the whole batch. Otherwise failing packages are
+    /// skipped and the result holds one code per id: 0 on success, else the `Error` code.
+    pub fn batch_claim(env: Env, ids: Vec<u64>, atomic: bool) -> Result<Vec<u32>, Error> {
+        let (claimed, total_amount, results) =
+            Self::run_batch(&env, &ids, atomic, |id, unlocks| {
+                Self::claim_package(&env, id, None, unlocks)
+            })?;
+
+        BatchClaimedEvent {
+            ids: claimed,
+            total_amount,
+        }
+        .publish(&env);
+
+        Ok(results)
+    }
+
+    /// Guardian claims on behalf of a recipient who cannot sign (e.g. a minor).
+    /// `guardian` must be registered for the package recipient via `set_guardian`; funds go to
+    /// the guardian's registered payout address, or to the recipient if none was set.
+    pub fn claim_as_guardian(env: Env, guardian: Address, id: u64) -> Result<(), Error> {
+        let mut unlocks = Map::new(&env);
+        Self::claim_package(&env, id, Some(guardian), &mut unlocks)?;
+        Self::release_locked(&env, &unlocks);
+        Ok(())
+    }
+
+    /// Claims a voucher package to `destination`.
+    /// Anyone may submit the claim; it is authorized by `signature`, an ed25519 signature by the
+    /// package's voucher key over the XDR encoding of `(contract address, id, destination)`.
+    /// Binding the contract and package id prevents the signature from being replayed against
+    /// other packages or deployments, and the package can only leave `Created` once.
+    pub fn claim_with_voucher(
+        env: Env,
+        id: u64,
+        destination: Address,
+        signature: BytesN<64>,
+    ) -> Result<(), Error> {
+        let mut package = Self::load_package(&env, id)?;
+
+        if package.kind != PackageKind::Voucher {
+            return Err(Error::WrongPackageKind);
+        }
+        Self::check_claimable_by_destination(&env, &package)?;
+
+        // Auth: verify the voucher signature (traps on mismatch)
+        let voucher_key_slot = (symbol_short!("vchr"), id);
+        let voucher_key: BytesN<32> = env
+            .storage()
+            .persistent()
+            .get(&voucher_key_slot)
+            .ok_or(Error::InvalidState)?;
+        let message = (env.current_contract_address(), id, destination.clone()).to_xdr(&env);
+        env.crypto()
+            .ed25519_verify(&voucher_key, &message, &signature);
+
+        Self::release_to_destination(&env, &mut package, &destination);
+
+        Ok(())
+    }
+
+    /// First step of claiming a hash-locked package: publish
+    /// `commitment = sha256(secret || destination.to_xdr())` without revealing either.
+    /// The matching `claim_with_secret` is only accepted in a later ledger, so a secret seen
+    /// in a pending reveal cannot be front-run with a fresh commitment.
+    pub fn commit_claim(env: Env, id: u64, commitment: BytesN<32>) -> Result<(), Error> {
+        let package = Self::load_package(&env, id)?;
+
+        if package.kind != PackageKind::HashLock {
+            return Err(Error::WrongPackageKind);
+        }
+        Self::check_claimable_by_destination(&env, &package)?;
+
+        // Keep the earliest ledger if the same commitment is submitted twice
+        let commit_key = (symbol_short!("hcmt"), id, commitment.clone());
+        if env.storage().persistent().has(&commit_key) {
+            return Err(Error::InvalidCommitment);
+        }
+        let ledger = env.ledger().sequence();
+        Self::write_persistent(&env, &commit_key, &ledger);
+
+        ClaimCommitted {
+            package_id: id,
+            commitment,
+            ledger,
+            timestamp: env.ledger().timestamp(),
+        }
+        .publish(&env);
+
+        Ok(())
+    }
+
+    /// Second step of claiming a hash-locked package: reveal `secret` and receive the funds at
+    /// `destination`. Requires a commitment for exactly this secret and destination made in an
+    /// earlier ledger via `commit_claim`.
+    pub fn claim_with_secret(
+        env: Env,
+        id: u64,
+        destination: Address,
+        secret: Bytes,
+    ) -> Result<(), Error> {
+        let mut package = Self::load_package(&env, id)?;
+
+        if package.kind != PackageKind::HashLock {
+            return Err(Error::WrongPackageKind);
+        }
+        Self::check_claimable_by_destination(&env, &package)?;
+
+        // Auth: the secret must match the package hash
+        if secret.len() < MIN_SECRET_LEN {
+            return Err(Error::InvalidSecret);
+        }
+        let hash_key = (symbol_short!("hlock"), id);
+        let secret_hash: BytesN<32> = env
+            .storage()
+            .persistent()
+            .get(&hash_key)
+            .ok_or(Error::InvalidState)?;
+        if env.crypto().sha256(&secret).to_bytes() != secret_hash {
+            return Err(Error::InvalidSecret);
+        }
+
+        // Front-running protection: a commitment from an earlier ledger must exist
+        let mut preimage = secret.clone();
+        preimage.append(&destination.clone().to_xdr(&env));
+        let commitment = env.crypto().sha256(&preimage).to_bytes();
+        let commit_key = (symbol_short!("hcmt"), id, commitment);
+        let committed_at: u32 =
+            Self::read_persistent(&env, &commit_key).ok_or(Error::InvalidCommitment)?;
+        if committed_at >= env.ledger().sequence() {
+            return Err(Error::InvalidCommitment);
+        }
+        env.storage().persistent().remove(&commit_key);
+
+        Self::release_to_destination(&env, &mut package, &destination);
+
+        Ok(())
+    }
+
+    // --- Expiry ---
+
+    /// Marks overdue packages as `Expired` and unlocks their unclaimed amounts, so the pool's
+    /// available balance stays accurate without admin intervention. Anyone may call this.
+    /// IDs that are unknown, not `Created` or not yet overdue are skipped.
+    /// Returns the number of packages expired.
+    pub fn expire_packages(env: Env, ids: Vec<u64>) -> Result<u32, Error> {
+        Self::check_paused(&env, PauseScope::All, None)?;
+
+        let mut expired: u32 = 0;
+        let mut unlocks = Map::new(&env);
+        for id in ids.iter() {
+            let Ok(mut package) = Self::load_package(&env, id) else {
+                continue;
+            };
+            if Self::is_overdue(&env, &package) {
+                Self::expire_package(&env, &mut package, &mut unlocks);
+                expired += 1;
+            }
+        }
+        Self::release_locked(&env, &unlocks);
+
+        Ok(expired)
+    }
+
+    // --- Admin Actions ---
+
+    /// Admin manually triggers disbursement (overrides recipient claim need, strictly checks status).
+    pub fn disburse(env: Env, id: u64) -> Result<(), Error> {
+        let admin = Self::get_admin(env.clone())?;
+        admin.require_auth();
+
+        let mut unlocks = Map::new(&env);
+        Self::disburse_package(&env, &admin, id, &mut unlocks)?;
+        Self::release_locked(&env, &unlocks);
+        Ok(())
+    }
+
+    /// Disburses several packages in one transaction, updating locked totals once per token.
+    /// With `atomic` the first failure aborts the whole batch. Otherwise failing packages are
+    /// skipped and the result holds one code per id: 0 on success, else the `Error` code.
+    pub fn batch_disburse(env: Env, ids: Vec<u64>, atomic: bool) -> Result<Vec<u32>, Error> {
+        let admin = Self::get_admin(env.clone())?;
+        admin.require_auth();
+
+        let (disbursed, total_amount, results) =
+            Self::run_batch(&env, &ids, atomic, |id, unlocks| {
+                Self::disburse_package(&env, &admin, id, unlocks)
+            })?;
+
+        BatchDisbursedEvent {
+            ids: disbursed,
+            admin,
+            total_amount,
+        }
+        .publish(&env);
+
+        Ok(results)
+    }
+
+    /// Admin or Canceller revokes a package (Cancels it). The unclaimed remainder stays reserved
+    /// for `refund` and cannot back new packages or surplus withdrawals.
+    pub fn revoke(env: Env, caller: Address, id: u64) -> Result<(), Error> {
+        Self::require_role(&env, Role::Canceller, &caller)?;
+
+        let mut unlocks = Map::new(&env);
+        Self::revoke_package(&env, &caller, id, &mut unlocks)?;
+        Self::release_locked(&env, &unlocks);
+        Ok(())
+    }
+
+    /// Revokes several packages in one transaction; see `batch_disburse` for `atomic` and the result.
+    pub fn batch_revoke(
+        env: Env,
+        caller: Address,
+        ids: Vec<u64>,
+        atomic: bool,
+    ) -> Result<Vec<u32>, Error> {
+        Self::require_role(&env, Role::Canceller, &caller)?;
+
+        let (revoked, total_amount, results) =
+            Self::run_batch(&env, &ids, atomic, |id, unlocks| {
+                Self::revoke_package(&env, &caller, id, unlocks)
+            })?;
+
+        BatchRevokedEvent {
+            ids: revoked,
+            admin: caller,
+            total_amount,
+        }
+        .publish(&env);
+
+        Ok(results)
+    }
+
+    pub fn refund(env: Env, id: u64) -> Result<(), Error> {
+        let admin = Self::require_single_admin(&env)?;
+
+        let mut unlocks = Map::new(&env);
+        Self::refund_package(&env, &admin, id, &mut unlocks)?;
+        Self::release_locked(&env, &unlocks);
+        Ok(())
+    }
+
+    /// Refunds several expired or cancelled packages to their funders in one transaction;
+    /// see `batch_disburse` for `atomic` and the result.
+    pub fn batch_refund(env: Env, ids: Vec<u64>, atomic: bool) -> Result<Vec<u32>, Error> {
+        let admin = Self::require_single_admin(&env)?;
+
+        let (refunded, total_amount, results) =
+            Self::run_batch(&env, &ids, atomic, |id, unlocks| {
+                Self::refund_package(&env, &admin, id, unlocks)
+            })?;
+
+        BatchRefundedEvent {
+            ids: refunded,
+            admin,
+            total_amount,
+        }
+        .publish(&env);
+
+        Ok(results)
+    }
+
+    /// Package cancellation by the admin or a Canceller.
+    /// Requirements: caller auth, existing package, status must be 'Created'.
+    pub fn cancel_package(env: Env, caller: Address, package_id: u64) -> Result<(), Error> {
+        // 1. Only the admin or a Canceller can cancel
+        Self::require_role(&env, Role::Canceller, &caller)?;
+
+        // 2. Package must exist
+        let mut package = Self::load_package(&env, package_id)?;
+
+        // 3. Package status must be Created (not Claimed, Expired, or already Cancelled)
+        if package.status != PackageStatus::Created {
+            return Err(Error::PackageNotActive);
+        }
+
+        // Additional check: Ensure it hasn't expired yet (consistent with 'claim' logic)
+        if package.expires_at > 0 && env.ledger().timestamp() > package.expires_at {
+            return Err(Error::PackageExpired);
+        }
+
+        // 4. Update status to Cancelled and persist
+        package.status = PackageStatus::Cancelled;
+        Self::save_package(&env, &package);
+
+        // 5. Unlock funds (Decrement the global locked amount; the remainder is held for refund)
+        let amount = package.amount - package.claimed_amount;
+        Self::decrement_locked(&env, &package.token, amount);
+        Self::settle_shares(&env, &package, amount, false);
+
+        let timestamp = env.ledger().timestamp();
+        PackageRevoked {
+            package_id,
+            recipient: package.recipient.clone(),
+            amount,
+            actor: caller.clone(),
+            timestamp,
+        }
+        .publish(&env);
+
+        Ok(())
+    }
+    /// Package expiration extension by the admin or an Extender.
+    /// Requirements: caller auth, existing package, status must be 'Created', additional_time > 0.
+    /// Behavior: Adds additional_time to the package's expires_at timestamp.
+    /// Cannot extend unbounded packages (expires_at == 0).
+    pub fn extend_expiration(
+        env: Env,
+        caller: Address,
+        package_id: u64,
+        additional_time: u64,
+    ) -> Result<(), Error> {
+        // 1. Only the admin or an Extender can extend
+        Self::require_role(&env, Role::Extender, &caller)?;
+        let config = Self::get_config(env.clone());
+
+        // 2. Package must exist
+        let mut package = Self::load_package(&env, package_id)?;
+
+        // 3. Package status must be Created
+        if package.status != PackageStatus::Created {
+            return Err(Error::PackageNotActive);
+        }
+
+        // 4. additional_time must be greater than 0
+        if additional_time == 0 {
+            return Err(Error::InvalidAmount);
+        }
+
+        // 5. Package must not be unbounded (expires_at must be > 0)
+        if package.expires_at == 0 {
+            return Err(Error::InvalidState);
+        }
+
+        // 6. Package must not already be expired
+        if env.ledger().timestamp() > package.expires_at {
+            return Err(Error::PackageExpired);
+        }
+
+        // 7. Calculate new expiration and update
+        let old_expires_at = package.expires_at;
+        let new_expires_at = old_expires_at + additional_time;
+        if !Self::within_expiry_limit(
+            &env,
+            config.max_expires_in,
+            package.claimable_from,
+            new_expires_at,
+        ) {
+            return Err(Error::InvalidState);
+        }
+        package.expires_at = new_expires_at;
+        Self::save_package(&env, &package);
+
+        // 8. Emit Extended event
+        ExtendedEvent {
+            id: package_id,
+            admin: caller.clone(),
+            old_expires_at,
+            new_expires_at,
+        }
+        .publish(&env);
+
+        Ok(())
+    }
+
+    /// Withdraws surplus (unallocated) funds from the contract; admin or Treasurer.
+    /// Requirements: caller auth, valid amount, sufficient surplus available.
+    /// Behavior: Transfers amount of token from contract to the specified address.
+    pub fn withdraw_surplus(
+        env: Env,
+        caller: Address,
+        to: Address,
+        amount: i128,
+        token: Address,
+    ) -> Result<(), Error> {
+        // 1. Only the admin or a Treasurer can withdraw surplus
+        Self::require_role(&env, Role::Treasurer, &caller)?;
+        Self::check_multisig_disabled(&env)?;
+        let action = AdminAction::WithdrawSurplus(to.clone(), amount, token.clone());
+        Self::check_not_timelocked(&env, &action)?;
+        Self::record_withdrawal(&env, &token, amount);
+        Self::withdraw_surplus_as(&env, &caller, to, amount, token)
+    }
+
+    fn withdraw_surplus_as(
+        env: &Env,
+        admin: &Address,
+        to: Address,
+        amount: i128,
+        token: Address,
+    ) -> Result<(), Error> {
+        Self::check_paused(env, PauseScope::Withdrawal, Some(token.clone()))?;
+
+        // 2. Validate amount
+        if amount <= 0 {
+            return Err(Error::InvalidAmount);
+        }
+
+        // 3. Get contract's current balance for the token
+        let token_client = token::Client::new(env, &token);
+        let contract_balance = token_client.balance(&env.current_contract_address());
+
+        // 4. Get total locked amount for the token
+        let total_locked = Self::read_locked(env, &token);
+
+        // 5. Calculate available surplus and validate
+        let available_surplus = contract_balance - total_locked - Self::reserved_total(env, &token);
+        if amount > available_surplus {
+            return Err(Error::InsufficientSurplus);
+        }
+
+        // 6. Transfer funds from contract to recipient
+        token_client.transfer(&env.current_contract_address(), &to, &amount);
+
+        // Withdrawing to a funder returns their unallocated contributions first
+        let returned = amount.min(Self::funder_balance(env, &to, &token));
+        if returned > 0 {
+            Self::update_funder(env, &to, &token, |account| account.refunded += returned);
+            DonorRefunded {
+                funder: to.clone(),
+                token: token.clone(),
+                amount: returned,
+                package_id: None,
+                actor: admin.clone(),
+                timestamp: env.ledger().timestamp(),
+            }
+            .publish(env);
+        }
+
+        // 7. Emit event
+        SurplusWithdrawnEvent {
+            to: to.clone(),
+            token: token.clone(),
+            amount,
+        }
+        .publish(env);
+
+        Ok(())
+    }
+
+    // --- Multisig Proposals ---
+
+    /// Admin sets the signers and M-of-N threshold that must approve `withdraw_surplus`,
+    /// `set_config`, `migrate`, `upgrade`, refunds, admin transfers and the calls that can
+    /// redirect package funds: `set_guardian`, `approve_reassignment`, `add_distributor` and
+    /// `grant_role`. Only callable directly while multisig is disabled; afterwards changes go
+    /// through an `AdminAction::SetSigners` proposal. Enabling multisig drops a pending admin
+    /// nomination.
+    ///
+    /// Package creation and `disburse` stay with the single admin: multisig does not stop
+    /// the admin paying a package out to an address they control.
+    pub fn set_signers(env: Env, signers: Vec<Address>, threshold: u32) -> Result<(), Error> {
+        let admin = Self::require_single_admin(&env)?;
+        let enabling = !signers.is_empty();
+        Self::apply_signers(&env, signers, threshold)?;
+
+        if enabling && let Some(pending) = Self::get_pending_admin(env.clone()) {
+            env.storage().instance().remove(&KEY_PENDING_ADMIN);
+            AdminTransferCancelled {
+                admin,
+                new_admin: pending.new_admin,
+                timestamp: env.ledger().timestamp(),
+            }
+            .publish(&env);
+        }
+        Ok(())
+    }
+
+    pub fn get_multisig(env: Env) -> Option<Multisig> {
+        env.storage().instance().get(&KEY_MULTISIG)
+    }
+
+    /// A signer proposes `action`, approving it in the same call.
+    /// The proposal can be executed until `expires_in` seconds from now.
+    pub fn propose(
+        env: Env,
+        signer: Address,
+        action: AdminAction,
+        expires_in: u64,
+    ) -> Result<u64, Error> {
+        Self::require_signer(&env, &signer)?;
+        if expires_in == 0 {
+            return Err(Error::InvalidState);
+        }
+
+        let id: u64 = env
+            .storage()
+            .instance()
+            .get(&KEY_PROPOSAL_COUNTER)
+            .unwrap_or(0);
+        env.storage()
+            .instance()
+            .set(&KEY_PROPOSAL_COUNTER, &(id + 1));
+
+        let now = env.ledger().timestamp();
+        let mut approvals = Vec::new(&env);
+        approvals.push_back(signer.clone());
+        let proposal = Proposal {
+            id,
+            action: action.clone(),
+            proposer: signer.clone(),
+            approvals,
+            status: ProposalStatus::Pending,
+            created_at: now,
+            expires_at: now + expires_in,
+        };
+        Self::write_persistent(&env, &(symbol_short!("prop"), id), &proposal);
+
+        ProposalCreated {
+            proposal_id: id,
+            action,
+            proposer: signer,
+            expires_at: proposal.expires_at,
+            timestamp: now,
+        }
+        .publish(&env);
+
+        Ok(id)
+    }
+
+    pub fn approve(env: Env, signer: Address, id: u64) -> Result<(), Error> {
+        Self::require_signer(&env, &signer)?;
+        let mut proposal = Self::load_pending_proposal(&env, id)?;
+        if proposal.approvals.contains(&signer) {
+            return Err(Error::InvalidState);
+        }
+
+        proposal.approvals.push_back(signer.clone());
+        Self::write_persistent(&env, &(symbol_short!("prop"), id), &proposal);
+
+        ProposalApproved {
+            proposal_id: id,
+            signer,
+            approvals: proposal.approvals.len(),
+            timestamp: env.ledger().timestamp(),
+        }
+        .publish(&env);
+
+        Ok(())
+    }
+
+    /// Runs an unexpired proposal once approvals from current signers reach the threshold.
+    /// Signers removed since approving no longer count. Timelocked actions are queued instead
+    /// and run through `execute_queued` after the delay.
+    pub fn execute_proposal(env: Env, executor: Address, id: u64) -> Result<(), Error> {
+        executor.require_auth();
+        let mut proposal = Self::load_pending_proposal(&env, id)?;
+        let multisig = Self::get_multisig(env.clone()).ok_or(Error::InvalidState)?;
+        let approvals = proposal
+            .approvals
+            .iter()
+            .filter(|signer| multisig.signers.contains(signer))
+            .count() as u32;
+        if approvals < multisig.threshold {
+            return Err(Error::ThresholdNotMet);
+        }
+
+        // Mark executed first so the action cannot re-enter this proposal
+        proposal.status = ProposalStatus::Executed;
+        Self::write_persistent(&env, &(symbol_short!("prop"), id), &proposal);
+
+        // Timelocked actions are queued rather than applied
+        if Self::is_timelocked(&env, &proposal.action) {
+            Self::enqueue_action(&env, proposal.action, &executor, true);
+        } else {
+            if let AdminAction::WithdrawSurplus(_, amount, token) = &proposal.action {
+                Self::record_withdrawal(&env, token, *amount);
+            }
+            let admin = Self::get_admin(env.clone())?;
+            Self::apply_action(&env, proposal.action, &admin)?;
+        }
+
+        ProposalExecuted {
+            proposal_id: id,
+            actor: executor,
+            timestamp: env.ledger().timestamp(),
+        }
+        .publish(&env);
+
+        Ok(())
+    }
+
+    /// The proposer withdraws a pending proposal.
+    pub fn cancel_proposal(env: Env, signer: Address, id: u64) -> Result<(), Error> {
+        signer.require_auth();
+        let mut proposal = Self::load_proposal(&env, id)?;
+        if proposal.status != ProposalStatus::Pending {
+            return Err(Error::ProposalNotPending);
+        }
+        if proposal.proposer != signer {
+            return Err(Error::NotAuthorized);
+        }
+
+        proposal.status = ProposalStatus::Cancelled;
+        Self::write_persistent(&env, &(symbol_short!("prop"), id), &proposal);
+
+        ProposalCancelled {
+            proposal_id: id,
+            actor: signer,
+            timestamp: env.ledger().timestamp(),
+        }
+        .publish(&env);
+
+        Ok(())
+    }
+
+    pub fn get_proposal(env: Env, id: u64) -> Result<Proposal, Error> {
+        env.storage()
+            .persistent()
+            .get(&(symbol_short!("prop"), id))
+            .ok_or(Error::ProposalNotFound)
+    }
+
+    // --- Timelock ---
+
+    /// Admin enables the timelock or changes it while it is off (`delay` == 0). Once enabled,
+    /// changes go through the queue as `AdminAction::SetTimelock`.
+    pub fn set_timelock(env: Env, delay: u64, withdraw_threshold: i128) -> Result<(), Error> {
+        Self::require_single_admin(&env)?;
+        let action = AdminAction::SetTimelock(delay, withdraw_threshold);
+        Self::check_not_timelocked(&env, &action)?;
+        Self::apply_timelock(&env, delay, withdraw_threshold)
+    }
+
+    pub fn get_timelock(env: Env) -> Timelock {
+        env.storage()
+            .instance()
+            .get(&KEY_TIMELOCK)
+            .unwrap_or(Timelock {
+                delay: 0,
+                withdraw_threshold: 0,
+            })
+    }
+
+    /// Queues a timelocked action; it can be executed once the current delay has passed.
+    /// Surplus withdrawals need the admin or a Treasurer, everything else the admin. With
+    /// multisig enabled, actions are queued by executing an approved proposal instead.
+    pub fn queue_action(env: Env, caller: Address, action: AdminAction) -> Result<u64, Error> {
+        if let AdminAction::WithdrawSurplus(..) = action {
+            Self::require_role(&env, Role::Treasurer, &caller)?;
+        } else {
+            caller.require_auth();
+            if caller != Self::get_admin(env.clone())? {
+                return Err(Error::NotAuthorized);
+            }
+        }
+        Self::check_multisig_disabled(&env)?;
+        if !Self::is_timelocked(&env, &action) {
+            return Err(Error::InvalidState);
+        }
+
+        Ok(Self::enqueue_action(&env, action, &caller, false))
+    }
+
+    /// Anyone can run a pending queued action once its `eta` has passed. Actions queued
+    /// without a multisig proposal fail with `MultisigRequired` once multisig is enabled.
+    pub fn execute_queued(env: Env, executor: Address, id: u64) -> Result<(), Error> {
+        executor.require_auth();
+        let mut queued = Self::load_queued(&env, id)?;
+        if queued.status != ProposalStatus::Pending {
+            return Err(Error::ProposalNotPending);
+        }
+        if !queued.approved {
+            Self::check_multisig_disabled(&env)?;
+        }
+        if env.ledger().timestamp() < queued.eta {
+            return Err(Error::TimelockNotReady);
+        }
+
+        queued.status = ProposalStatus::Executed;
+        Self::store_queued(&env, &queued);
+        Self::apply_action(&env, queued.action, &queued.queued_by)?;
+
+        QueuedActionExecuted {
+            queue_id: id,
+            actor: executor,
+            timestamp: env.ledger().timestamp(),
+        }
+        .publish(&env);
+
+        Ok(())
+    }
+
+    /// Admin drops a pending queued action.
+    pub fn cancel_queued(env: Env, id: u64) -> Result<(), Error> {
+        let admin = Self::get_admin(env.clone())?;
+        admin.require_auth();
+        let mut queued = Self::load_queued(&env, id)?;
+        if queued.status != ProposalStatus::Pending {
+            return Err(Error::ProposalNotPending);
+        }
+
+        queued.status = ProposalStatus::Cancelled;
+        Self::store_queued(&env, &queued);
+
+        QueuedActionCancelled {
+            queue_id: id,
+            actor: admin,
+            timestamp: env.ledger().timestamp(),
+        }
+        .publish(&env);
+
+        Ok(())
+    }
+
+    pub fn get_queued_action(env: Env, id: u64) -> Result<QueuedAction, Error> {
+        env.storage()
+            .persistent()
+            .get(&(symbol_short!("queued"), id))
+            .ok_or(Error::QueuedActionNotFound)
+    }
+
+    /// Pending queued actions in queue order.
+    pub fn get_pending_actions(env: Env) -> Vec<QueuedAction> {
+        let ids: Vec<u64> = env
+            .storage()
+            .instance()
+            .get(&KEY_QUEUED)
+            .unwrap_or(Vec::new(&env));
+        let mut actions = Vec::new(&env);
+        for id in ids.iter() {
+            if let Ok(queued) = Self::get_queued_action(env.clone(), id) {
+                actions.push_back(queued);
+            }
+        }
+        actions
+    }
+
+    // --- Distributor Allowances ---
+
+    /// Admin limits how much `distributor` can commit in `token`: `limit` in total outstanding
+    /// packages and, with `period` > 0, `period_limit` per period (e.g. 86_400 for a daily cap).
+    /// Distributors without an allowance are unlimited. Existing usage is kept when updating.
+    pub fn set_allowance(
+        env: Env,
+        distributor: Address,
+        token: Address,
+        limit: i128,
+        period: u64,
+        period_limit: i128,
+    ) -> Result<(), Error> {
+        let admin = Self::get_admin(env.clone())?;
+        admin.require_auth();
+
+        if limit < 0 || period_limit < 0 {
+            return Err(Error::InvalidAmount);
+        }
+
+        let previous = Self::load_allowance(&env, &distributor, &token);
+        let allowance = Allowance {
+            limit,
+            used: previous.as_ref().map(|a| a.used).unwrap_or(0),
+            period,
+            period_limit,
+            period_start: previous.as_ref().map(|a| a.period_start).unwrap_or(0),
+            period_used: previous.map(|a| a.period_used).unwrap_or(0),
+        };
+        Self::store_allowance(&env, &distributor, &token, &allowance);
+
+        Ok(())
+    }
+
+    pub fn remove_allowance(env: Env, distributor: Address, token: Address) -> Result<(), Error> {
+        let admin = Self::get_admin(env.clone())?;
+        admin.require_auth();
+
+        let allowance_key = (symbol_short!("allow"), distributor.clone(), token.clone());
+        env.storage().persistent().remove(&allowance_key);
+
+        AllowanceRemoved {
+            distributor,
+            token,
+            timestamp: env.ledger().timestamp(),
+        }
+        .publish(&env);
+
+        Ok(())
+    }
+
+    pub fn get_allowance(env: Env, distributor: Address, token: Address) -> Option<Allowance> {
+        let allowance_key = (symbol_short!("allow"), distributor, token);
+        env.storage().persistent().get(&allowance_key)
+    }
+
+    /// Returns how much `distributor` can still commit in `token` right now, taking the
+    /// current period into account. None = unlimited.
+    pub fn get_remaining_allowance(env: Env, distributor: Address, token: Address) -> Option<i128> {
+        let allowance = Self::get_allowance(env.clone(), distributor, token)?;
+        let mut remaining = allowance.limit - allowance.used;
+        if allowance.period > 0 {
+            let period_used =
+                if env.ledger().timestamp() >= allowance.period_start + allowance.period {
+                    0
+                } else {
+                    allowance.period_used
+                };
+            remaining = remaining.min(allowance.period_limit - period_used);
+        }
+        Some(remaining.max(0))
+    }
+
+    // --- Matching Pools ---
+
+    /// Admin sets up a matching campaign for `token`; `sponsor` pre-funds the full `cap`, which
+    /// stays outside the general pool until matched. One pool per token at a time.
+    pub fn create_matching_pool(
+        env: Env,
+        sponsor: Address,
+        token: Address,
+        ratio_bps: u32,
+        cap: i128,
+        deadline: u64,
+    ) -> Result<(), Error> {
+        let admin = Self::get_admin(env.clone())?;
+        admin.require_auth();
+        sponsor.require_auth();
+
+        if ratio_bps == 0 || cap <= 0 {
+            return Err(Error::InvalidAmount);
+        }
+        if deadline <= env.ledger().timestamp() {
+            return Err(Error::InvalidState);
+        }
+        let pool_key = (symbol_short!("match"), token.clone());
+        if env.storage().persistent().has(&pool_key) {
+            return Err(Error::InvalidState);
+        }
+
+        let token_client = token::Client::new(&env, &token);
+        token_client.transfer(&sponsor, env.current_contract_address(), &cap);
+
+        let pool = MatchingPool {
+            sponsor: sponsor.clone(),
+            token: token.clone(),
+            ratio_bps,
+            cap,
+            deadline,
+            matched: 0,
+        };
+        Self::write_persistent(&env, &pool_key, &pool);
+
+        MatchingPoolCreated {
+            sponsor,
+            token,
+            ratio_bps,
+            cap,
+            deadline,
+        }
+        .publish(&env);
+
+        Ok(())
+    }
+
+    /// Closes the matching pool for `token` once its deadline has passed and returns the
+    /// unmatched allocation to the sponsor. Anyone may call this.
+    pub fn close_matching_pool(env: Env, token: Address) -> Result<i128, Error> {
+        let pool_key = (symbol_short!("match"), token.clone());
+        let pool: MatchingPool =
+            Self::read_persistent(&env, &pool_key).ok_or(Error::InvalidState)?;
+        if env.ledger().timestamp() <= pool.deadline {
+            return Err(Error::InvalidState);
+        }
+
+        env.storage().persistent().remove(&pool_key);
+        let returned = pool.cap - pool.matched;
+        if returned > 0 {
+            let token_client = token::Client::new(&env, &token);
+            token_client.transfer(&env.current_contract_address(), &pool.sponsor, &returned);
+        }
+
+        MatchingPoolClosed {
+            sponsor: pool.sponsor,
+            token,
+            matched: pool.matched,
+            returned,
+            timestamp: env.ledger().timestamp(),
+        }
+        .publish(&env);
+
+        Ok(returned)
+    }
+
+    pub fn get_matching_pool(env: Env, token: Address) -> Option<MatchingPool> {
+        let pool_key = (symbol_short!("match"), token);
+        env.storage().persistent().get(&pool_key)
+    }
+
+    // --- Funders ---
+
+    /// Returns a funder's contribution accounting for `token` (all zero if they never funded).
+    pub fn get_funder(env: Env, funder: Address, token: Address) -> FunderAccount {
+        let funder_key = (symbol_short!("fund"), funder, token);
+        env.storage()
+            .persistent()
+            .get(&funder_key)
+            .unwrap_or(FunderAccount {
+                contributed: 0,
+                earmarked: 0,
+                locked: 0,
+                released: 0,
+                refunded: 0,
+                packages: 0,
+            })
+    }
+
+    /// Returns the funder's unallocated general balance in `token`: contributions not given
+    /// to earmarks, locked in packages, paid out or refunded.
+    pub fn get_funder_balance(env: Env, funder: Address, token: Address) -> i128 {
+        let account = Self::get_funder(env, funder, token);
+        account.contributed
+            - account.earmarked
+            - account.locked
+            - account.released
+            - account.refunded
+    }
+
+    /// Returns what a donor's contributions in `token` have funded so far. Packages created
+    /// with an explicit `funder` are attributed to it; all others are attributed FIFO to the
+    /// funders of the general pool or of the package's earmark, in the order they joined.
+    pub fn get_donor_impact(env: Env, funder: Address, token: Address) -> DonorImpact {
+        let account = Self::get_funder(env, funder, token);
+        DonorImpact {
+            contributed: account.contributed,
+            committed: account.locked,
+            claimed: account.released,
+            refunded: account.refunded,
+            available: account.contributed - account.locked - account.released - account.refunded,
+            packages_funded: account.packages,
+        }
+    }
+
+    /// Returns how a package's amount is attributed to funders.
+    pub fn get_funding_shares(env: Env, id: u64) -> Vec<FundingShare> {
+        let shares_key = (symbol_short!("share"), id);
+        env.storage()
+            .persistent()
+            .get(&shares_key)
+            .unwrap_or(Vec::new(&env))
+    }
+
+    // --- Package Metadata ---
+
+    /// Sets one metadata entry on a `Created` package, subject to the `Config` allowlist and
+    /// size limits. Allowed for the admin, the package's creator, or a distributor whose
+    /// scope covers the package.
+    pub fn set_package_metadata(
+        env: Env,
+        operator: Address,
+        id: u64,
+        key: Symbol,
+        value: String,
+    ) -> Result<(), Error> {
+        Self::require_admin_or_distributor(&env, &operator)?;
+        let config = Self::get_config(env.clone());
+
+        let mut package = Self::load_package(&env, id)?;
+        Self::check_metadata_editor(&env, &operator, &package)?;
+
+        package.metadata.set(key.clone(), value.clone());
+        Self::validate_metadata(&config, &package.metadata)?;
+        Self::save_package(&env, &package);
+
+        PackageMetadataUpdated {
+            package_id: id,
+            key,
+            value: Some(value),
+            actor: operator,
+            timestamp: env.ledger().timestamp(),
+        }
+        .publish(&env);
+
+        Ok(())
+    }
+
+    /// Removes one metadata entry from a `Created` package; same permissions as
+    /// `set_package_metadata`.
+    pub fn remove_package_metadata(
+        env: Env,
+        operator: Address,
+        id: u64,
+        key: Symbol,
+    ) -> Result<(), Error> {
+        Self::require_admin_or_distributor(&env, &operator)?;
+
+        let mut package = Self::load_package(&env, id)?;
+        Self::check_metadata_editor(&env, &operator, &package)?;
+
+        if package.metadata.remove(key.clone()).is_none() {
+            return Err(Error::InvalidMetadata);
+        }
+        Self::save_package(&env, &package);
+
+        PackageMetadataUpdated {
+            package_id: id,
+            key,
+            value: None,
+            actor: operator,
+            timestamp: env.ledger().timestamp(),
+        }
+        .publish(&env);
+
+        Ok(())
+    }
+
+    // --- Guardians ---
+
+    /// Admin-only. Registers `guardian` as able to claim for `recipient`, replacing any
+    /// previous guardian. `payout_to` selects where guardian claims are paid (None = recipient).
+    /// With multisig on this goes through an `AdminAction::SetGuardian` proposal.
+    pub fn set_guardian(
+        env: Env,
+        recipient: Address,
+        guardian: Address,
+        payout_to: Option<Address>,
+    ) -> Result<(), Error> {
+        let admin = Self::require_single_admin(&env)?;
+        Self::apply_set_guardian(&env, admin, recipient, guardian, payout_to)
+    }
+
+    fn apply_set_guardian(
+        env: &Env,
+        admin: Address,
+        recipient: Address,
+        guardian: Address,
+        payout_to: Option<Address>,
+    ) -> Result<(), Error> {
+        if guardian == recipient {
+            return Err(Error::InvalidState);
+        }
+
+        let record = Guardian {
+            guardian: guardian.clone(),
+            payout_to: payout_to.clone(),
+        };
+        let guardian_key = (symbol_short!("guard"), recipient.clone());
+        Self::write_persistent(env, &guardian_key, &record);
+
+        GuardianSet {
+            recipient,
+            guardian,
+            payout_to,
+            actor: admin,
+            timestamp: env.ledger().timestamp(),
+        }
+        .publish(env);
+
+        Ok(())
+    }
+
+    /// Admin-only. Removes the guardian registered for `recipient`.
+    pub fn remove_guardian(env: Env, recipient: Address) -> Result<(), Error> {
+        let admin = Self::get_admin(env.clone())?;
+        admin.require_auth();
+
+        let record =
+            Self::get_guardian(env.clone(), recipient.clone()).ok_or(Error::InvalidState)?;
+        let guardian_key = (symbol_short!("guard"), recipient.clone());
+        env.storage().persistent().remove(&guardian_key);
+
+        GuardianRemoved {
+            recipient,
+            guardian: record.guardian,
+            actor: admin,
+            timestamp: env.ledger().timestamp(),
+        }
+        .publish(&env);
+
+        Ok(())
+    }
+
+    pub fn get_guardian(env: Env, recipient: Address) -> Option<Guardian> {
+        let guardian_key = (symbol_short!("guard"), recipient);
+        env.storage().persistent().get(&guardian_key)
+    }
+
+    // --- Recipient Reassignment ---
+
+    /// Requests moving a `Created` package to `new_recipient`, e.g. after a lost phone.
+    /// Takes effect only once the admin approves it with `approve_reassignment`, after the
+    /// configured `reassignment_delay`. A new request replaces any pending one.
+    pub fn request_reassignment(
+        env: Env,
+        operator: Address,
+        id: u64,
+        new_recipient: Address,
+    ) -> Result<(), Error> {
+        Self::require_admin_or_distributor(&env, &operator)?;
+        let config = Self::get_config(env.clone());
+
+        let package = Self::load_package(&env, id)?;
+
+        if package.status != PackageStatus::Created {
+            return Err(Error::PackageNotActive);
+        }
+        // Voucher and hash-lock packages pick their recipient at claim time
+        if Self::has_placeholder_recipient(&package) {
+            return Err(Error::WrongPackageKind);
+        }
+        if new_recipient == package.recipient {
+            return Err(Error::InvalidState);
+        }
+
+        let requested_at = env.ledger().timestamp();
+        let request = ReassignmentRequest {
+            new_recipient: new_recipient.clone(),
+            requested_by: operator.clone(),
+            requested_at,
+            approvable_at: requested_at + config.reassignment_delay,
+        };
+        let request_key = (symbol_short!("reasgn"), id);
+        Self::write_persistent(&env, &request_key, &request);
+
+        ReassignmentRequested {
+            package_id: id,
+            old_recipient: package.recipient,
+            new_recipient,
+            approvable_at: request.approvable_at,
+            actor: operator,
+            timestamp: requested_at,
+        }
+        .publish(&env);
+
+        Ok(())
+    }
+
+    /// Admin-only. Applies a pending reassignment once its cool-down has elapsed.
+    /// Fails if the package is no longer `Created` (e.g. the original recipient claimed it).
+    /// With multisig on this goes through an `AdminAction::ApproveReassignment` proposal.
+    pub fn approve_reassignment(env: Env, id: u64) -> Result<(), Error> {
+        let admin = Self::require_single_admin(&env)?;
+        Self::apply_reassignment(&env, admin, id)
+    }
+
+    fn apply_reassignment(env: &Env, admin: Address, id: u64) -> Result<(), Error> {
+        let mut package = Self::load_package(env, id)?;
+
+        let request_key = (symbol_short!("reasgn"), id);
+        let request: ReassignmentRequest =
+            Self::read_persistent(env, &request_key).ok_or(Error::NoPendingReassignment)?;
+
+        if package.status != PackageStatus::Created {
+            return Err(Error::PackageNotActive);
+        }
+        let timestamp = env.ledger().timestamp();
+        if timestamp < request.approvable_at {
+            return Err(Error::ReassignmentCoolingDown);
+        }
+
+        let old_recipient = package.recipient.clone();
+        package.recipient = request.new_recipient.clone();
+        Self::save_package(env, &package);
+        env.storage().persistent().remove(&request_key);
+
+        RecipientReassigned {
+            package_id: id,
+            old_recipient,
+            new_recipient: request.new_recipient,
+            actor: admin,
+            timestamp,
+        }
+        .publish(env);
+
+        Ok(())
+    }
+
+    /// Admin-only. Drops a pending reassignment request.
+    pub fn cancel_reassignment(env: Env, id: u64) -> Result<(), Error> {
+        let admin = Self::get_admin(env.clone())?;
+        admin.require_auth();
+
+        let request_key = (symbol_short!("reasgn"), id);
+        let request: ReassignmentRequest =
+            Self::read_persistent(&env, &request_key).ok_or(Error::NoPendingReassignment)?;
+        env.storage().persistent().remove(&request_key);
+
+        ReassignmentCancelled {
+            package_id: id,
+            new_recipient: request.new_recipient,
+            actor: admin,
+            timestamp: env.ledger().timestamp(),
+        }
+        .publish(&env);
+
+        Ok(())
+    }
+
+    /// Returns the pending reassignment request for a package, if any.
+    pub fn get_pending_reassignment(env: Env, id: u64) -> Option<ReassignmentRequest> {
+        let request_key = (symbol_short!("reasgn"), id);
+        env.storage().persistent().get(&request_key)
+    }
+
+    // --- Stream Controls ---
+
+    /// Admin-only. Freezes accrual of a streaming package; already accrued funds stay claimable.
+    pub fn pause_stream(env: Env, id: u64) -> Result<(), Error> {
+        let admin = Self::get_admin(env.clone())?;
+        admin.require_auth();
+
+        let (_, mut stream) = Self::load_active_stream(&env, id)?;
+        if stream.paused {
+            return Err(Error::InvalidState);
+        }
+
+        let timestamp = env.ledger().timestamp();
+        stream.paused = true;
+        stream.paused_at = timestamp;
+        let stream_key = (symbol_short!("stream"), id);
+        Self::write_package_entry(&env, &stream_key, &stream);
+
+        StreamPaused {
+            package_id: id,
+            actor: admin,
+            timestamp,
+        }
+        .publish(&env);
+
+        Ok(())
+    }
+
+    /// Admin-only. Resumes a paused stream, pushing its window back by the time spent paused
+    /// so the full amount still streams.
+    pub fn resume_stream(env: Env, id: u64) -> Result<(), Error> {
+        let admin = Self::get_admin(env.clone())?;
+        admin.require_auth();
+
+        let (_, mut stream) = Self::load_active_stream(&env, id)?;
+        if !stream.paused {
+            return Err(Error::InvalidState);
+        }
+
+        let timestamp = env.ledger().timestamp();
+        let paused_for = timestamp - stream.paused_at;
+        stream.start_at += paused_for;
+        stream.end_at += paused_for;
+        stream.paused = false;
+        stream.paused_at = 0;
+        let stream_key = (symbol_short!("stream"), id);
+        Self::write_package_entry(&env, &stream_key, &stream);
+
+        StreamResumed {
+            package_id: id,
+            new_end_at: stream.end_at,
+            actor: admin,
+            timestamp,
+        }
+        .publish(&env);
+
+        Ok(())
+    }
+
+    /// Admin-only. Ends a stream for good: the accrued, unclaimed part is paid out to the
+    /// recipient and the unaccrued balance is held for `refund`. Fails while disbursements
+    /// are paused.
+    pub fn stop_stream(env: Env, id: u64) -> Result<(), Error> {
+        let admin = Self::get_admin(env.clone())?;
+        admin.require_auth();
+
+        let (mut package, _) = Self::load_active_stream(&env, id)?;
+        Self::check_paused(&env, PauseScope::Disbursement, Some(package.token.clone()))?;
+
+        let accrued = Self::released_amount(&env, &package);
+        let paid_out = accrued - package.claimed_amount;
+        let returned = package.amount - accrued;
+
+        // State Transition
+        package.claimed_amount = accrued;
+        package.status = if returned > 0 {
+            PackageStatus::Cancelled
+        } else {
+            PackageStatus::Claimed
+        };
+        Self::save_package(&env, &package);
+
+        // Unlock everything that was still locked; `returned` is held for refund
+        Self::decrement_locked(&env, &package.token, paid_out + returned);
+        Self::settle_shares(&env, &package, paid_out, true);
+        Self::settle_shares(&env, &package, returned, false);
+
+        let timestamp = env.ledger().timestamp();
+        if paid_out > 0 {
+            let token_client = token::Client::new(&env, &package.token);
+            token_client.transfer(
+                &env.current_contract_address(),
+                &package.recipient,
+                &paid_out,
+            );
+
+            PackageDisbursed {
+                package_id: id,
+                recipient: package.recipient.clone(),
+                amount: paid_out,
+                actor: admin.clone(),
+                timestamp,
+            }
+            .publish(&env);
+        }
+
+        StreamStopped {
+            package_id: id,
+            recipient: package.recipient.clone(),
+            paid_out,
+            returned,
+            actor: admin,
+            timestamp,
+        }
+        .publish(&env);
+
+        Ok(())
+    }
+
+    // --- Storage TTL ---
+
+    /// Admin sets the TTL policy. `threshold` must be below `extend_to`, which cannot exceed
+    /// the network's maximum TTL.
+    pub fn set_ttl_config(env: Env, threshold: u32, extend_to: u32) -> Result<(), Error> {
+        let admin = Self::get_admin(env.clone())?;
+        admin.require_auth();
+        if threshold == 0 || threshold >= extend_to || extend_to > env.storage().max_ttl() {
+            return Err(Error::InvalidState);
+        }
+        env.storage().instance().set(
+            &KEY_TTL,
+            &TtlConfig {
+                threshold,
+                extend_to,
+            },
+        );
+
+        TtlConfigUpdated {
+            threshold,
+            extend_to,
+            timestamp: env.ledger().timestamp(),
+        }
+        .publish(&env);
+
+        Ok(())
+    }
+
+    pub fn get_ttl_config(env: Env) -> TtlConfig {
+        env.storage().instance().get(&KEY_TTL).unwrap_or(TtlConfig {
+            threshold: DEFAULT_TTL_THRESHOLD,
+            extend_to: DEFAULT_TTL_EXTEND_TO,
+        })
+    }
+
+    /// Extends the TTL of the given packages (with their schedules, streams and funding
+    /// shares) and of the instance data. Anyone can call it; unknown ids are skipped.
+    /// Returns how many packages were found.
+    pub fn bump_packages(env: Env, ids: Vec<u64>) -> u32 {
+        let mut found: u32 = 0;
+        for id in ids.iter() {
+            if env.storage().persistent().has(&(symbol_short!("pkg"), id)) {
+                Self::bump_package(&env, id);
+                found += 1;
+            }
+        }
+        Self::bump_instance(&env);
+        found
+    }
+
+    /// Extends the TTL of up to `limit` aggregation index entries starting at `start`.
+    /// Anyone can call it. Returns how many entries were extended.
+    pub fn bump_index(env: Env, start: u64, limit: u32) -> u32 {
+        let count: u64 = env.storage().instance().get(&KEY_PKG_IDX).unwrap_or(0);
+        let end = count.min(start.saturating_add(limit as u64));
+        let ttl = Self::get_ttl_config(env.clone());
+        let extend_to = ttl.extend_to.min(env.storage().max_ttl());
+        let mut bumped: u32 = 0;
+        for i in start..end {
+            let idx_key = (symbol_short!("pidx"), i);
+            if env.storage().persistent().has(&idx_key) {
+                env.storage()
+                    .persistent()
+                    .extend_ttl(&idx_key, ttl.threshold, extend_to);
+                bumped += 1;
+            }
+        }
+        Self::bump_instance(&env);
+        bumped
+    }
+
+    // --- Helpers ---
+
+    /// Reads a package without touching its TTL, for views.
+    fn read_package(env: &Env, id: u64) -> Result<Package, Error> {
+        Self::check_not_migrating(env)?;
+        env.storage()
+            .persistent()
+            .get(&(symbol_short!("pkg"), id))
+            .ok_or(Error::PackageNotFound)
+    }
+
+    /// Reads a package for a state change, extending its TTL and the instance's when they
+    /// run low.
+    fn load_package(env: &Env, id: u64) -> Result<Package, Error> {
+        let package = Self::read_package(env, id)?;
+        Self::bump_package(env, id);
+        Self::bump_instance(env);
+        Ok(package)
+    }
+
+    fn save_package(env: &Env, package: &Package) {
+        env.storage()
+            .persistent()
+            .set(&(symbol_short!("pkg"), package.id), package);
+        Self::bump_package(env, package.id);
+        Self::bump_instance(env);
+    }
+
+    fn store_index(env: &Env, idx: u64, id: u64) {
+        let idx_key = (symbol_short!("pidx"), idx);
+        env.storage().persistent().set(&idx_key, &id);
+        let ttl = Self::get_ttl_config(env.clone());
+        let extend_to = ttl.extend_to.min(env.storage().max_ttl());
+        env.storage()
+            .persistent()
+            .extend_ttl(&idx_key, extend_to, extend_to);
+    }
+
+    /// Extends a package and its side entries to `extend_to` once fewer than `threshold`
+    /// ledgers remain. Expiry is tracked under `("ttl", id)` because contracts cannot read
+    /// TTLs directly; extensions by others only make the real TTL longer.
+    fn bump_package(env: &Env, id: u64) {
+        let ttl = Self::get_ttl_config(env.clone());
+        let now = env.ledger().sequence();
+        let ttl_key = (symbol_short!("ttl"), id);
+        let storage = env.storage().persistent();
+        let live_until: u32 = storage.get(&ttl_key).unwrap_or(0);
+        if live_until.saturating_sub(now) >= ttl.threshold {
+            return;
+        }
+
+        let extend_to = ttl.extend_to.min(env.storage().max_ttl());
+        storage.extend_ttl(&(symbol_short!("pkg"), id), extend_to, extend_to);
+        for prefix in [
+            symbol_short!("sched"),
+            symbol_short!("stream"),
+            symbol_short!("vchr"),
+            symbol_short!("hlock"),
+            symbol_short!("share"),
+        ] {
+            let key = (prefix, id);
+            if storage.has(&key) {
+                storage.extend_ttl(&key, extend_to, extend_to);
+            }
+        }
+        storage.set(&ttl_key, &(now + extend_to));
+        storage.extend_ttl(&ttl_key, extend_to, extend_to);
+    }
+
+    /// Writes an entry kept alongside a package (`sched`, `stream`, ...) and extends it in full,
+    /// so it lives at least as long as the package whatever its `ttl` marker says.
+    fn write_package_entry<K: IntoVal<Env, Val>, V: IntoVal<Env, Val>>(
+        env: &Env,
+        key: &K,
+        val: &V,
+    ) {
+        let ttl = Self::get_ttl_config(env.clone());
+        let extend_to = ttl.extend_to.min(env.storage().max_ttl());
+        let storage = env.storage().persistent();
+        storage.set(key, val);
+        storage.extend_ttl(key, extend_to, extend_to);
+    }
+
+    /// Extends a persistent entry to `extend_to` once fewer than `threshold` ledgers remain.
+    fn extend_persistent<K: IntoVal<Env, Val>>(env: &Env, key: &K) {
+        let ttl = Self::get_ttl_config(env.clone());
+        let extend_to = ttl.extend_to.min(env.storage().max_ttl());
+        env.storage()
+            .persistent()
+            .extend_ttl(key, ttl.threshold, extend_to);
+    }
+
+    /// Reads a persistent entry, extending it when it exists.
+    fn read_persistent<K: IntoVal<Env, Val>, V: TryFromVal<Env, Val>>(
+        env: &Env,
+        key: &K,
+    ) -> Option<V> {
+        let value = env.storage().persistent().get(key);
+        if value.is_some() {
+            Self::extend_persistent(env, key);
+        }
+        value
+    }
+
+    /// Writes a persistent entry and extends it.
+    fn write_persistent<K: IntoVal<Env, Val>, V: IntoVal<Env, Val>>(env: &Env, key: &K, value: &V) {
+        env.storage().persistent().set(key, value);
+        Self::extend_persistent(env, key);
+    }
+
+    /// Members of a role other than `Distributor`, whose members are the distributor records.
+    fn load_role_members(env: &Env, role: Role) -> Vec<Address> {
+        Self::read_persistent(env, &(symbol_short!("role"), role)).unwrap_or(Vec::new(env))
+    }
+
+    fn is_role_member(env: &Env, role: Role, account: &Address) -> bool {
+        if role == Role::Distributor {
+            return Self::read_distributor(env, account).is_some();
+        }
+        Self::load_role_members(env, role).contains(account)
+    }
+
+    fn load_earmark(env: &Env, tag: &Symbol, token: &Address) -> Earmark {
+        let earmark_key = (symbol_short!("earmark"), tag.clone(), token.clone());
+        Self::read_persistent(env, &earmark_key).unwrap_or(Earmark {
+            balance: 0,
+            locked: 0,
+        })
+    }
+
+    fn load_proposal(env: &Env, id: u64) -> Result<Proposal, Error> {
+        Self::read_persistent(env, &(symbol_short!("prop"), id)).ok_or(Error::ProposalNotFound)
+    }
+
+    fn load_queued(env: &Env, id: u64) -> Result<QueuedAction, Error> {
+        Self::read_persistent(env, &(symbol_short!("queued"), id))
+            .ok_or(Error::QueuedActionNotFound)
+    }
+
+    fn load_allowance(env: &Env, distributor: &Address, token: &Address) -> Option<Allowance> {
+        let allowance_key = (symbol_short!("allow"), distributor.clone(), token.clone());
+        Self::read_persistent(env, &allowance_key)
+    }
+
+    fn load_matching_pool(env: &Env, token: &Address) -> Option<MatchingPool> {
+        Self::read_persistent(env, &(symbol_short!("match"), token.clone()))
+    }
+
+    fn load_guardian(env: &Env, recipient: &Address) -> Option<Guardian> {
+        Self::read_persistent(env, &(symbol_short!("guard"), recipient.clone()))
+    }
+
+    fn load_funder(env: &Env, funder: &Address, token: &Address) -> FunderAccount {
+        let funder_key = (symbol_short!("fund"), funder.clone(), token.clone());
+        Self::read_persistent(env, &funder_key).unwrap_or(FunderAccount {
+            contributed: 0,
+            earmarked: 0,
+            locked: 0,
+            released: 0,
+            refunded: 0,
+            packages: 0,
+        })
+    }
+
+    fn funder_balance(env: &Env, funder: &Address, token: &Address) -> i128 {
+        let account = Self::load_funder(env, funder, token);
+        account.contributed
+            - account.earmarked
+            - account.locked
+            - account.released
+            - account.refunded
+    }
+
+    /// Instance counterpart of `bump_package`, covering admin, config and other settings.
+    fn bump_instance(env: &Env) {
+        let ttl = Self::get_ttl_config(env.clone());
+        let now = env.ledger().sequence();
+        let live_until: u32 = env.storage().instance().get(&KEY_INSTANCE_TTL).unwrap_or(0);
+        if live_until.saturating_sub(now) >= ttl.threshold {
+            return;
+        }
+
+        let extend_to = ttl.extend_to.min(env.storage().max_ttl());
+        env.storage()
+            .instance()
+            .set(&KEY_INSTANCE_TTL, &(now + extend_to));
+        env.storage().instance().extend_ttl(extend_to, extend_to);
+    }
+
+    /// Validates a new package against the config and pool, locks its funds, stores it,
+    /// indexes it for aggregation and emits `PackageCreated`.
+    /// `horizon` is the timestamp checked against `max_expires_in` (the stream end for streams).
+    fn lock_new_package(
+        env: &Env,
+        operator: &Address,
+        package: &Package,
+        horizon: u64,
+    ) -> Result<(), Error> {
         let config = Self::get_config(env.clone());
 
-        if amount <= 0 {
+        if package.amount <= 0 {
             return Err(Error::InvalidAmount);
         }
 
-        if amount < config.min_amount {
+        if package.amount < config.min_amount {
             return Err(Error::InvalidAmount);
         }
 
-        if !config.allowed_tokens.is_empty() && !config.allowed_tokens.contains(token.clone()) {
+        if !config.allowed_tokens.is_empty()
+            && !config.allowed_tokens.contains(package.token.clone())
+        {
             return Err(Error::InvalidState);
         }
 
-        if config.max_expires_in > 0 {
-            let now = env.ledger().timestamp();
-            if expires_at == 0 || expires_at <= now || expires_at - now > config.max_expires_in {
-                return Err(Error::InvalidState);
-            }
+        // The claim window must open before the package expires
+        if package.expires_at > 0 && package.claimable_from >= package.expires_at {
+            return Err(Error::InvalidState);
+        }
+
+        if !Self::within_expiry_limit(env, config.max_expires_in, package.claimable_from, horizon) {
+            return Err(Error::InvalidState);
         }
 
+        Self::check_distributor_scope(env, operator, package, horizon)?;
+
         // 1. Check ID Uniqueness
-        let key = (symbol_short!("pkg"), id);
+        let key = (symbol_short!("pkg"), package.id);
         if env.storage().persistent().has(&key) {
             return Err(Error::PackageIdExists);
         }
 
         // 2. Check Solvency (Available Balance vs Locked)
-        let token_client = token::Client::new(&env, &token);
+        let token_client = token::Client::new(env, &package.token);
         let contract_balance = token_client.balance(&env.current_contract_address());
 
-        let mut locked_map: Map<Address, i128> = env
-            .storage()
-            .instance()
-            .get(&KEY_TOTAL_LOCKED)
-            .unwrap_or(Map::new(&env));
-        let current_locked = locked_map.get(token.clone()).unwrap_or(0);
+        let current_locked = Self::read_locked(env, &package.token);
 
-        // Ensure we don't over-promise funds
-        if contract_balance < current_locked + amount {
+        // Ensure we don't over-promise funds; unlocked earmarked money and unmatched sponsor
+        // allocations are not available to general packages (earmarked packages are checked
+        // against their earmark instead)
+        let reserved = if package.earmark.is_none() {
+            Self::reserved_total(env, &package.token)
+        } else {
+            0
+        };
+        if contract_balance - reserved < current_locked + package.amount {
             return Err(Error::InsufficientFunds);
         }
+        Self::consume_allowance(env, operator, &package.token, package.amount)?;
+        Self::allocate_funding(env, package)?;
+
+        // 3. Update Locked State
+        Self::write_locked(env, &package.token, current_locked + package.amount);
+
+        // 4. Store Package
+        Self::save_package(env, package);
+
+        Self::record_distributor_activity(env, operator, 1, package.amount);
+
+        // 5. Track package index for aggregation
+        let idx: u64 = env.storage().instance().get(&KEY_PKG_IDX).unwrap_or(0);
+        Self::store_index(env, idx, package.id);
+        env.storage().instance().set(&KEY_PKG_IDX, &(idx + 1));
+
+        PackageCreated {
+            package_id: package.id,
+            recipient: package.recipient.clone(),
+            amount: package.amount,
+            actor: operator.clone(),
+            timestamp: package.created_at,
+        }
+        .publish(env);
+
+        Ok(())
+    }
+
+    fn validate_metadata(config: &Config, metadata: &Map<Symbol, String>) -> Result<(), Error> {
+        if config.max_metadata_entries > 0 && metadata.len() > config.max_metadata_entries {
+            return Err(Error::InvalidMetadata);
+        }
+        for (key, value) in metadata.iter() {
+            if !config.metadata_keys.is_empty() && !config.metadata_keys.contains(key) {
+                return Err(Error::InvalidMetadata);
+            }
+            if config.max_metadata_value_len > 0 && value.len() > config.max_metadata_value_len {
+                return Err(Error::InvalidMetadata);
+            }
+        }
+        Ok(())
+    }
+
+    /// Total amount released to the recipient so far, claimed or not.
+    fn released_amount(env: &Env, package: &Package) -> i128 {
+        match package.kind {
+            PackageKind::Standard | PackageKind::Voucher | PackageKind::HashLock => package.amount,
+            PackageKind::Tranched => {
+                let now = env.ledger().timestamp();
+                let tranches = Self::read_tranches(env, package.id);
+                let mut released: i128 = 0;
+                for tranche in tranches.iter() {
+                    if tranche.unlocks_at > now {
+                        break;
+                    }
+                    released += tranche.amount;
+                }
+                released
+            }
+            PackageKind::Stream => {
+                let stream = Self::read_stream(env, package.id);
+                let now = if stream.paused {
+                    stream.paused_at
+                } else {
+                    env.ledger().timestamp()
+                };
+                if now <= stream.start_at {
+                    0
+                } else if now >= stream.end_at {
+                    package.amount
+                } else {
+                    let elapsed = (now - stream.start_at) as i128;
+                    let duration = (stream.end_at - stream.start_at) as i128;
+                    package.amount * elapsed / duration
+                }
+            }
+        }
+    }
+
+    /// Shared claim path for recipients and their guardians. The released amount is added to
+    /// `unlocks` instead of being unlocked directly; returns it.
+    fn claim_package(
+        env: &Env,
+        id: u64,
+        guardian: Option<Address>,
+        unlocks: &mut Map<Address, i128>,
+    ) -> Result<i128, Error> {
+        let mut package = Self::load_package(env, id)?;
+        Self::check_paused(env, PauseScope::Claims, Some(package.token.clone()))?;
+
+        // Validations
+        if package.status != PackageStatus::Created {
+            return Err(Error::PackageNotActive);
+        }
+        // Check expiry; the status is persisted by `expire_packages`, since writes here would
+        // be rolled back with the error
+        if package.expires_at > 0 && env.ledger().timestamp() > package.expires_at {
+            return Err(Error::PackageExpired);
+        }
+        if env.ledger().timestamp() < package.claimable_from {
+            return Err(Error::PackageNotYetClaimable);
+        }
+
+        // Voucher and hash-locked packages have a placeholder recipient and their own claim paths
+        if Self::has_placeholder_recipient(&package) {
+            return Err(Error::WrongPackageKind);
+        }
+
+        // Auth: the recipient, or their registered guardian
+        let (actor, payee) = match guardian {
+            None => {
+                package.recipient.require_auth();
+                (package.recipient.clone(), package.recipient.clone())
+            }
+            Some(guardian) => {
+                let record =
+                    Self::load_guardian(env, &package.recipient).ok_or(Error::NotAuthorized)?;
+                if record.guardian != guardian {
+                    return Err(Error::NotAuthorized);
+                }
+                guardian.require_auth();
+                let payee = record.payout_to.unwrap_or(package.recipient.clone());
+                (guardian, payee)
+            }
+        };
+
+        let amount = Self::released_amount(env, &package) - package.claimed_amount;
+        if amount <= 0 {
+            return Err(Error::NothingToClaim);
+        }
+
+        // State Transition: Created -> Claimed (once everything is released)
+        // Checks passed, update state FIRST (Re-entrancy protection)
+        package.claimed_amount += amount;
+        if package.claimed_amount == package.amount {
+            package.status = PackageStatus::Claimed;
+        }
+        Self::save_package(env, &package);
+
+        // Update Global Locked
+        Self::add_unlock(unlocks, &package.token, amount);
+        Self::settle_shares(env, &package, amount, true);
+
+        // Effect: Transfer Funds
+        let token_client = token::Client::new(env, &package.token);
+        token_client.transfer(&env.current_contract_address(), &payee, &amount);
+
+        let timestamp = env.ledger().timestamp();
+        PackageClaimed {
+            package_id: id,
+            recipient: package.recipient.clone(),
+            amount,
+            actor: actor.clone(),
+            timestamp,
+        }
+        .publish(env);
+
+        if package.kind == PackageKind::Tranched {
+            TrancheClaimed {
+                package_id: id,
+                recipient: package.recipient.clone(),
+                amount,
+                claimed_total: package.claimed_amount,
+                remaining: package.amount - package.claimed_amount,
+                actor,
+                timestamp,
+            }
+            .publish(env);
+        }
+
+        Ok(amount)
+    }
+
+    /// Releases the unclaimed remainder of a `Created` package to its recipient, ignoring any
+    /// tranche schedule. The amount is added to `unlocks`; returns it.
+    fn disburse_package(
+        env: &Env,
+        admin: &Address,
+        id: u64,
+        unlocks: &mut Map<Address, i128>,
+    ) -> Result<i128, Error> {
+        let mut package = Self::load_package(env, id)?;
+        Self::check_paused(env, PauseScope::Disbursement, Some(package.token.clone()))?;
+
+        if package.status != PackageStatus::Created {
+            return Err(Error::PackageNotActive);
+        }
+
+        // There is no recipient to disburse to before a voucher or code is redeemed
+        if Self::has_placeholder_recipient(&package) {
+            return Err(Error::WrongPackageKind);
+        }
+
+        // Pre-staged packages stay locked until their claim window opens
+        if env.ledger().timestamp() < package.claimable_from {
+            return Err(Error::PackageNotYetClaimable);
+        }
+
+        // Disbursement releases everything not yet claimed, ignoring any tranche schedule.
+        let amount = package.amount - package.claimed_amount;
+
+        // State Transition
+        package.status = PackageStatus::Claimed;
+        package.claimed_amount = package.amount;
+        Self::save_package(env, &package);
+
+        // Update Locked
+        Self::add_unlock(unlocks, &package.token, amount);
+        Self::settle_shares(env, &package, amount, true);
+
+        // Transfer
+        let token_client = token::Client::new(env, &package.token);
+        token_client.transfer(&env.current_contract_address(), &package.recipient, &amount);
+
+        let timestamp = env.ledger().timestamp();
+        PackageDisbursed {
+            package_id: id,
+            recipient: package.recipient.clone(),
+            amount,
+            actor: admin.clone(),
+            timestamp,
+        }
+        .publish(env);
+
+        Ok(amount)
+    }
+
+    /// Created -> Cancelled; the unclaimed remainder is added to `unlocks`. Returns it.
+    fn revoke_package(
+        env: &Env,
+        admin: &Address,
+        id: u64,
+        unlocks: &mut Map<Address, i128>,
+    ) -> Result<i128, Error> {
+        let mut package = Self::load_package(env, id)?;
+
+        if package.status != PackageStatus::Created {
+            return Err(Error::InvalidState);
+        }
+
+        // Only the unclaimed remainder is still locked.
+        let amount = package.amount - package.claimed_amount;
+
+        // State Transition
+        package.status = PackageStatus::Cancelled;
+        Self::save_package(env, &package);
+
+        // Unlock funds (held for refund)
+        Self::add_unlock(unlocks, &package.token, amount);
+        Self::settle_shares(env, &package, amount, false);
+
+        let timestamp = env.ledger().timestamp();
+        PackageRevoked {
+            package_id: id,
+            recipient: package.recipient.clone(),
+            amount,
+            actor: admin.clone(),
+            timestamp,
+        }
+        .publish(env);
+
+        Ok(amount)
+    }
+
+    /// Expired or Cancelled -> Refunded, transferring the unclaimed remainder to the package's
+    /// funders (the admin gets any unattributed part). Overdue `Created` packages are expired
+    /// first. Returns the refunded amount.
+    fn refund_package(
+        env: &Env,
+        admin: &Address,
+        id: u64,
+        unlocks: &mut Map<Address, i128>,
+    ) -> Result<i128, Error> {
+        let mut package = Self::load_package(env, id)?;
+
+        // Only the unclaimed remainder of a partly claimed package is refunded.
+        let amount = package.amount - package.claimed_amount;
+
+        // Can only refund if Expired or Cancelled.
+        // If Created, must Revoke first. If Claimed, impossible.
+        // If Refunded, impossible.
+        if package.status == PackageStatus::Created {
+            // Check if actually expired
+            if Self::is_overdue(env, &package) {
+                // If we just expired it, we need to unlock the funds first
+                Self::expire_package(env, &mut package, unlocks);
+            } else {
+                return Err(Error::InvalidState);
+            }
+        } else if package.status == PackageStatus::Claimed
+            || package.status == PackageStatus::Refunded
+        {
+            return Err(Error::InvalidState);
+        }
 
-        // 3. Update Locked State
-        locked_map.set(token.clone(), current_locked + amount);
-        env.storage().instance().set(&KEY_TOTAL_LOCKED, &locked_map);
-
-        // 4. Create Package
-        let created_at = env.ledger().timestamp();
-        let package = Package {
-            id,
-            recipient: recipient.clone(),
-            amount,
-            token: token.clone(),
-            status: PackageStatus::Created,
-            created_at,
-            expires_at,
-            metadata: Map::new(&env),
-        };
+        // If Cancelled, funds were already unlocked in `revoke`.
+        // If Expired (logic above), funds were just unlocked.
 
-        env.storage().persistent().set(&key, &package);
+        // State Transition
+        package.status = PackageStatus::Refunded;
+        Self::save_package(env, &package);
+
+        // Refunded earmarked money leaves its earmark
+        if let Some(tag) = &package.earmark {
+            let mut earmark = Self::load_earmark(env, tag, &package.token);
+            earmark.locked -= amount;
+            earmark.balance -= amount;
+            Self::store_earmark(env, tag, &package.token, &earmark, -amount);
+        } else {
+            Self::add_held(env, &package.token, -amount);
+        }
 
-        // 5. Track package index for aggregation
-        let idx: u64 = env.storage().instance().get(&KEY_PKG_IDX).unwrap_or(0);
-        let idx_key = (symbol_short!("pidx"), idx);
-        env.storage().persistent().set(&idx_key, &id);
-        env.storage().instance().set(&KEY_PKG_IDX, &(idx + 1));
+        // Transfer Contract -> Funders the package was attributed to; the admin receives
+        // any part not backed by `fund` or `fund_earmarked` contributions
+        let timestamp = env.ledger().timestamp();
+        let token_client = token::Client::new(env, &package.token);
+        let unattributed = Self::for_each_share(
+            env,
+            &package,
+            package.claimed_amount,
+            package.amount,
+            |funder, part| {
+                Self::update_funder(env, funder, &package.token, |account| {
+                    account.locked -= part;
+                    account.refunded += part;
+                });
+                token_client.transfer(&env.current_contract_address(), funder, &part);
+                DonorRefunded {
+                    funder: funder.clone(),
+                    token: package.token.clone(),
+                    amount: part,
+                    package_id: Some(id),
+                    actor: admin.clone(),
+                    timestamp,
+                }
+                .publish(env);
+            },
+        );
+        if unattributed > 0 {
+            token_client.transfer(&env.current_contract_address(), admin, &unattributed);
+        }
 
-        PackageCreated {
+        PackageRefunded {
             package_id: id,
-            recipient: recipient.clone(),
+            recipient: package.recipient.clone(),
             amount,
-            actor: operator,
-            timestamp: created_at,
+            actor: admin.clone(),
+            timestamp,
         }
-        .publish(&env);
+        .publish(env);
 
-        Ok(id)
+        Ok(amount)
     }
 
-    /// Creates multiple packages in a single transaction for multiple recipients.
-    /// Uses an auto-incrementing counter for package IDs.
-    pub fn batch_create_packages(
-        env: Env,
-        operator: Address,
-        recipients: Vec<Address>,
-        amounts: Vec<i128>,
-        token: Address,
-        expires_in: u64,
-    ) -> Result<Vec<u64>, Error> {
-        Self::check_paused(&env)?;
-        Self::require_admin_or_distributor(&env, &operator)?;
+    /// True for `Created` packages past their (non-zero) `expires_at`.
+    fn is_overdue(env: &Env, package: &Package) -> bool {
+        package.status == PackageStatus::Created
+            && package.expires_at > 0
+            && env.ledger().timestamp() > package.expires_at
+    }
 
-        // Validate array lengths match
-        if recipients.len() != amounts.len() {
-            return Err(Error::MismatchedArrays);
-        }
+    /// Created -> Expired: stores the package, adds its unclaimed amount to `unlocks` and emits
+    /// `PackageExpired`.
+    fn expire_package(env: &Env, package: &mut Package, unlocks: &mut Map<Address, i128>) {
+        let amount = package.amount - package.claimed_amount;
+        package.status = PackageStatus::Expired;
+        Self::save_package(env, package);
 
-        let token_client = token::Client::new(&env, &token);
-        let contract_balance = token_client.balance(&env.current_contract_address());
+        Self::add_unlock(unlocks, &package.token, amount);
+        Self::settle_shares(env, package, amount, false);
 
-        let mut locked_map: Map<Address, i128> = env
-            .storage()
-            .instance()
-            .get(&KEY_TOTAL_LOCKED)
-            .unwrap_or(Map::new(&env));
-        let mut current_locked = locked_map.get(token.clone()).unwrap_or(0);
+        PackageExpired {
+            package_id: package.id,
+            recipient: package.recipient.clone(),
+            amount,
+            timestamp: env.ledger().timestamp(),
+        }
+        .publish(env);
+    }
 
-        // Read the current package counter
-        let mut counter: u64 = env.storage().instance().get(&KEY_PKG_COUNTER).unwrap_or(0);
-        // Read the current aggregation index
-        let mut idx: u64 = env.storage().instance().get(&KEY_PKG_IDX).unwrap_or(0);
+    /// Charges a new package to the operator's allowance, if they have one.
+    fn consume_allowance(
+        env: &Env,
+        operator: &Address,
+        token: &Address,
+        amount: i128,
+    ) -> Result<(), Error> {
+        let Some(mut allowance) = Self::load_allowance(env, operator, token) else {
+            return Ok(());
+        };
+        if allowance.used + amount > allowance.limit {
+            return Err(Error::AllowanceExceeded);
+        }
+        if allowance.period > 0 {
+            let now = env.ledger().timestamp();
+            if now >= allowance.period_start + allowance.period {
+                allowance.period_start = now;
+                allowance.period_used = 0;
+            }
+            if allowance.period_used + amount > allowance.period_limit {
+                return Err(Error::AllowanceExceeded);
+            }
+            allowance.period_used += amount;
+        }
+        allowance.used += amount;
+        Self::store_allowance(env, operator, token, &allowance);
+        Ok(())
+    }
 
-        let created_at = env.ledger().timestamp();
-        let expires_at = created_at + expires_in;
+    /// Gives an unlocked package amount back to its creator's allowance. Period usage is not
+    /// given back, so the per-period cap still limits how fast money can be committed.
+    fn return_allowance(env: &Env, package: &Package, amount: i128) {
+        let Some(mut allowance) = Self::load_allowance(env, &package.created_by, &package.token)
+        else {
+            return;
+        };
+        allowance.used = (allowance.used - amount).max(0);
+        Self::store_allowance(env, &package.created_by, &package.token, &allowance);
+    }
 
-        let mut created_ids: Vec<u64> = Vec::new(&env);
-        let mut total_amount: i128 = 0;
+    fn store_allowance(env: &Env, distributor: &Address, token: &Address, allowance: &Allowance) {
+        let allowance_key = (symbol_short!("allow"), distributor.clone(), token.clone());
+        Self::write_persistent(env, &allowance_key, allowance);
 
-        for i in 0..recipients.len() {
-            let recipient = recipients.get(i).unwrap();
-            let amount = amounts.get(i).unwrap();
+        AllowanceUpdated {
+            distributor: distributor.clone(),
+            token: token.clone(),
+            limit: allowance.limit,
+            used: allowance.used,
+            period_used: allowance.period_used,
+            timestamp: env.ledger().timestamp(),
+        }
+        .publish(env);
+    }
 
-            // Validate amount
-            if amount <= 0 {
-                return Err(Error::InvalidAmount);
+    /// Adds to a funder's contributions to the general pool (`pool` = None) or an earmark;
+    /// funders not already queued for that pool join its FIFO attribution order.
+    fn record_contribution(
+        env: &Env,
+        from: &Address,
+        token: &Address,
+        pool: &Option<Symbol>,
+        amount: i128,
+    ) {
+        // A funder keeps their place in the queue until the head moves past them
+        let queued_key = (
+            symbol_short!("fqueued"),
+            token.clone(),
+            pool.clone(),
+            from.clone(),
+        );
+        if !env.storage().persistent().has(&queued_key) {
+            let (head, len) = Self::read_funder_queue(env, token, pool);
+            let entry_key = (symbol_short!("fq"), token.clone(), pool.clone(), len);
+            Self::write_persistent(env, &entry_key, from);
+            Self::write_persistent(env, &queued_key, &true);
+            Self::write_funder_queue(env, token, pool, head, len + 1);
+        }
+        Self::update_funder(env, from, token, |account| {
+            account.contributed += amount;
+            if pool.is_some() {
+                account.earmarked += amount;
             }
+        });
+        if let Some(tag) = pool {
+            Self::add_earmark_gift(env, tag, token, from, amount);
+        }
+    }
 
-            // Check solvency
-            if contract_balance < current_locked + amount {
-                return Err(Error::InsufficientFunds);
+    /// `(head, len)` of a pool's FIFO funder queue stored as `("fq", token, pool, i)` entries.
+    /// Entries before `head` have no balance left; each queued funder is listed once.
+    fn read_funder_queue(env: &Env, token: &Address, pool: &Option<Symbol>) -> (u32, u32) {
+        let queue_key = (symbol_short!("fqueue"), token.clone(), pool.clone());
+        Self::read_persistent(env, &queue_key).unwrap_or((0, 0))
+    }
+
+    fn write_funder_queue(env: &Env, token: &Address, pool: &Option<Symbol>, head: u32, len: u32) {
+        let queue_key = (symbol_short!("fqueue"), token.clone(), pool.clone());
+        Self::write_persistent(env, &queue_key, &(head, len));
+    }
+
+    /// A funder's money in the general pool (`pool` = None) or an earmark that is not
+    /// attributed to any package.
+    fn pool_balance(env: &Env, funder: &Address, token: &Address, pool: &Option<Symbol>) -> i128 {
+        match pool {
+            None => Self::funder_balance(env, funder, token),
+            Some(tag) => {
+                let gift_key = (
+                    symbol_short!("egift"),
+                    tag.clone(),
+                    token.clone(),
+                    funder.clone(),
+                );
+                Self::read_persistent(env, &gift_key).unwrap_or(0)
             }
+        }
+    }
 
-            // Assign ID and increment counter
-            let id = counter;
-            counter += 1;
+    fn add_earmark_gift(env: &Env, tag: &Symbol, token: &Address, funder: &Address, delta: i128) {
+        let gift_key = (
+            symbol_short!("egift"),
+            tag.clone(),
+            token.clone(),
+            funder.clone(),
+        );
+        let gift: i128 = Self::read_persistent(env, &gift_key).unwrap_or(0);
+        Self::write_persistent(env, &gift_key, &(gift + delta));
+    }
 
-            let key = (symbol_short!("pkg"), id);
+    /// Matches a gift from the token's matching pool, if one is open. The matched amount is
+    /// released into the general pool and attributed to the sponsor.
+    fn apply_match(env: &Env, donor: &Address, token: &Address, amount: i128) {
+        let Some(mut pool) = Self::load_matching_pool(env, token) else {
+            return;
+        };
+        if env.ledger().timestamp() > pool.deadline || *donor == pool.sponsor {
+            return;
+        }
+        let matched = (amount * pool.ratio_bps as i128 / 10_000).min(pool.cap - pool.matched);
+        if matched <= 0 {
+            return;
+        }
 
-            // Create package
-            let package = Package {
-                id,
-                recipient: recipient.clone(),
-                amount,
-                token: token.clone(),
-                status: PackageStatus::Created,
-                created_at,
-                expires_at,
-                metadata: Map::new(&env),
-            };
+        pool.matched += matched;
+        let pool_key = (symbol_short!("match"), token.clone());
+        Self::write_persistent(env, &pool_key, &pool);
+        Self::record_contribution(env, &pool.sponsor, token, &None, matched);
 
-            env.storage().persistent().set(&key, &package);
+        DonationMatched {
+            sponsor: pool.sponsor,
+            donor: donor.clone(),
+            token: token.clone(),
+            amount,
+            matched,
+            timestamp: env.ledger().timestamp(),
+        }
+        .publish(env);
+    }
 
-            // Track package index for aggregation
-            let idx_key = (symbol_short!("pidx"), idx);
-            env.storage().persistent().set(&idx_key, &id);
-            idx += 1;
+    fn update_funder<F: FnOnce(&mut FunderAccount)>(
+        env: &Env,
+        funder: &Address,
+        token: &Address,
+        update: F,
+    ) {
+        let mut account = Self::load_funder(env, funder, token);
+        update(&mut account);
+        let funder_key = (symbol_short!("fund"), funder.clone(), token.clone());
+        Self::write_persistent(env, &funder_key, &account);
+    }
 
-            // Update locked
-            current_locked += amount;
-            total_amount += amount;
+    /// Attributes a new package to funders and locks their shares: the whole amount to the
+    /// explicit `funder`, or FIFO across the funders of its earmark (or the general pool) with
+    /// unallocated balance. Any part not covered by contributions stays unattributed.
+    fn allocate_funding(env: &Env, package: &Package) -> Result<(), Error> {
+        let token = &package.token;
+        let shares = match (&package.earmark, &package.funder) {
+            (Some(tag), funder) => {
+                // Earmarked packages draw on their earmark only
+                if funder.is_some() {
+                    return Err(Error::InvalidState);
+                }
+                let mut earmark = Self::load_earmark(env, tag, token);
+                if earmark.balance - earmark.locked < package.amount {
+                    return Err(Error::InsufficientFunds);
+                }
+                earmark.locked += package.amount;
+                Self::store_earmark(env, tag, token, &earmark, -package.amount);
+                Self::take_queued_shares(env, token, &package.earmark, package.amount)
+            }
+            (None, Some(funder)) => {
+                let balance = Self::funder_balance(env, funder, token);
+                if balance < package.amount {
+                    return Err(Error::InsufficientFunds);
+                }
+                Vec::from_array(
+                    env,
+                    [FundingShare {
+                        funder: funder.clone(),
+                        amount: package.amount,
+                    }],
+                )
+            }
+            (None, None) => Self::take_queued_shares(env, token, &None, package.amount),
+        };
 
-            PackageCreated {
-                package_id: id,
-                recipient: recipient.clone(),
-                amount,
-                actor: operator.clone(),
-                timestamp: created_at,
+        if shares.is_empty() {
+            return Ok(());
+        }
+        for share in shares.iter() {
+            Self::update_funder(env, &share.funder, token, |account| {
+                account.locked += share.amount;
+                account.packages += 1;
+                if package.earmark.is_some() {
+                    account.earmarked -= share.amount;
+                }
+            });
+            if let Some(tag) = &package.earmark {
+                Self::add_earmark_gift(env, tag, token, &share.funder, -share.amount);
             }
-            .publish(&env);
+        }
+        let shares_key = (symbol_short!("share"), package.id);
+        Self::write_package_entry(env, &shares_key, &shares);
+        Ok(())
+    }
 
-            created_ids.push_back(id);
+    /// Splits `amount` FIFO across a pool's queued funders. Walks the queue from its head, at
+    /// most `MAX_FUNDER_SCAN` funders per package, moving the head past funders that end up
+    /// with nothing left.
+    fn take_queued_shares(
+        env: &Env,
+        token: &Address,
+        pool: &Option<Symbol>,
+        amount: i128,
+    ) -> Vec<FundingShare> {
+        let mut shares: Vec<FundingShare> = Vec::new(env);
+        let (start_head, len) = Self::read_funder_queue(env, token, pool);
+        let mut head = start_head;
+        let end = len.min(start_head.saturating_add(MAX_FUNDER_SCAN));
+        let mut left = amount;
+        for pos in start_head..end {
+            if left == 0 {
+                break;
+            }
+            let entry_key = (symbol_short!("fq"), token.clone(), pool.clone(), pos);
+            let funder: Address = Self::read_persistent(env, &entry_key).unwrap();
+            // Shares taken earlier in this walk are not locked yet
+            let taken: i128 = shares
+                .iter()
+                .filter(|share| share.funder == funder)
+                .map(|share| share.amount)
+                .sum();
+            let balance = Self::pool_balance(env, &funder, token, pool) - taken;
+            let part = left.min(balance);
+            if part > 0 {
+                shares.push_back(FundingShare {
+                    funder: funder.clone(),
+                    amount: part,
+                });
+                left -= part;
+            }
+            if pos == head && balance - part <= 0 {
+                env.storage().persistent().remove(&entry_key);
+                let queued_key = (
+                    symbol_short!("fqueued"),
+                    token.clone(),
+                    pool.clone(),
+                    funder,
+                );
+                env.storage().persistent().remove(&queued_key);
+                head += 1;
+            }
         }
+        if head != start_head {
+            Self::write_funder_queue(env, token, pool, head, len);
+        }
+        shares
+    }
 
-        // Persist updated locked map, counter, and aggregation index
-        locked_map.set(token.clone(), current_locked);
-        env.storage().instance().set(&KEY_TOTAL_LOCKED, &locked_map);
-        env.storage().instance().set(&KEY_PKG_COUNTER, &counter);
-        env.storage().instance().set(&KEY_PKG_IDX, &idx);
+    /// Calls `f` with each funder's part of the package amount range `[from, to)`.
+    /// Returns the part of the range not attributed to any funder.
+    fn for_each_share<F: FnMut(&Address, i128)>(
+        env: &Env,
+        package: &Package,
+        from: i128,
+        to: i128,
+        mut f: F,
+    ) -> i128 {
+        let shares = Self::get_funding_shares(env.clone(), package.id);
+        let mut start: i128 = 0;
+        let mut attributed: i128 = 0;
+        for share in shares.iter() {
+            let end = start + share.amount;
+            let part = end.min(to) - start.max(from);
+            if part > 0 {
+                f(&share.funder, part);
+                attributed += part;
+            }
+            start = end;
+        }
+        (to - from) - attributed
+    }
 
-        // Emit batch event
-        BatchCreatedEvent {
-            ids: created_ids.clone(),
-            admin: operator,
-            total_amount,
+    /// Settles `amount` of the package's funder shares (or earmark), either paid out to the
+    /// recipient (`released`, ending at `claimed_amount`) or unlocked when the package is
+    /// cancelled or expires. Unlocked shares stay locked to their funders (and earmark) until
+    /// `refund` pays them back, so they cannot be attributed to another package meanwhile, and
+    /// the remainder of a general package is held out of the pool until then; they are given
+    /// back to the creating distributor's allowance straight away.
+    fn settle_shares(env: &Env, package: &Package, amount: i128, released: bool) {
+        if !released {
+            Self::return_allowance(env, package, amount);
+        }
+        if let Some(tag) = &package.earmark {
+            let mut earmark = Self::load_earmark(env, tag, &package.token);
+            if released {
+                earmark.locked -= amount;
+                earmark.balance -= amount;
+                Self::store_earmark(env, tag, &package.token, &earmark, 0);
+            } else {
+                // Out of the general locked total, so it must be reserved from the pool
+                Self::store_earmark(env, tag, &package.token, &earmark, amount);
+            }
+        } else if !released {
+            // Out of the general locked total, so it must be reserved from the pool
+            Self::add_held(env, &package.token, amount);
+        }
+        if !released {
+            return;
         }
-        .publish(&env);
 
-        Ok(created_ids)
+        let end = package.claimed_amount;
+        Self::for_each_share(env, package, end - amount, end, |funder, part| {
+            Self::update_funder(env, funder, &package.token, |account| {
+                account.locked -= part;
+                account.released += part;
+            });
+        });
     }
 
-    // --- Recipient Actions ---
-
-    /// Recipient claims the package.
-    pub fn claim(env: Env, id: u64) -> Result<(), Error> {
-        Self::check_paused(&env)?;
-        let key = (symbol_short!("pkg"), id);
-        let mut package: Package = env
-            .storage()
-            .persistent()
-            .get(&key)
-            .ok_or(Error::PackageNotFound)?;
+    /// Voucher and hash-locked packages carry this contract's address until they are claimed.
+    fn has_placeholder_recipient(package: &Package) -> bool {
+        matches!(package.kind, PackageKind::Voucher | PackageKind::HashLock)
+    }
 
-        // Validations
+    fn check_claimable_by_destination(env: &Env, package: &Package) -> Result<(), Error> {
+        Self::check_paused(env, PauseScope::Claims, Some(package.token.clone()))?;
         if package.status != PackageStatus::Created {
             return Err(Error::PackageNotActive);
         }
-        // Check expiry
         if package.expires_at > 0 && env.ledger().timestamp() > package.expires_at {
-            // Auto-expire if accessed after date
-            package.status = PackageStatus::Expired;
-            env.storage().persistent().set(&key, &package);
             return Err(Error::PackageExpired);
         }
+        if env.ledger().timestamp() < package.claimable_from {
+            return Err(Error::PackageNotYetClaimable);
+        }
+        Ok(())
+    }
 
-        // Auth
-        package.recipient.require_auth();
-
-        // State Transition: Created -> Claimed
-        // Checks passed, update state FIRST (Re-entrancy protection)
+    /// Completes a voucher or hash-lock claim: the destination becomes the recipient and
+    /// receives the full amount.
+    fn release_to_destination(env: &Env, package: &mut Package, destination: &Address) {
+        // State Transition: Created -> Claimed, recipient becomes the destination
+        let amount = package.amount;
+        package.recipient = destination.clone();
+        package.claimed_amount = amount;
         package.status = PackageStatus::Claimed;
-        env.storage().persistent().set(&key, &package);
+        Self::save_package(env, package);
 
-        // Update Global Locked
-        Self::decrement_locked(&env, &package.token, package.amount);
+        Self::decrement_locked(env, &package.token, amount);
+        Self::settle_shares(env, package, amount, true);
 
-        // Effect: Transfer Funds
-        let token_client = token::Client::new(&env, &package.token);
-        token_client.transfer(
-            &env.current_contract_address(),
-            &package.recipient,
-            &package.amount,
-        );
+        let token_client = token::Client::new(env, &package.token);
+        token_client.transfer(&env.current_contract_address(), destination, &amount);
 
         let timestamp = env.ledger().timestamp();
         PackageClaimed {
-            package_id: id,
-            recipient: package.recipient.clone(),
-            amount: package.amount,
-            actor: package.recipient.clone(),
+            package_id: package.id,
+            recipient: destination.clone(),
+            amount,
+            actor: destination.clone(),
             timestamp,
         }
-        .publish(&env);
-
-        Ok(())
+        .publish(env);
     }
 
-    // --- Admin Actions ---
-
-    /// Admin manually triggers disbursement (overrides recipient claim need, strictly checks status).
-    pub fn disburse(env: Env, id: u64) -> Result<(), Error> {
-        let admin = Self::get_admin(env.clone())?;
-        admin.require_auth();
-
-        let key = (symbol_short!("pkg"), id);
-        let mut package: Package = env
-            .storage()
+    fn read_stream(env: &Env, id: u64) -> StreamSchedule {
+        let stream_key = (symbol_short!("stream"), id);
+        env.storage()
             .persistent()
-            .get(&key)
-            .ok_or(Error::PackageNotFound)?;
+            .get(&stream_key)
+            .unwrap_or(StreamSchedule {
+                start_at: 0,
+                end_at: 0,
+                paused: false,
+                paused_at: 0,
+            })
+    }
+
+    /// Loads a streaming package that is still running (status `Created`).
+    fn load_active_stream(env: &Env, id: u64) -> Result<(Package, StreamSchedule), Error> {
+        let package = Self::load_package(env, id)?;
 
+        if package.kind != PackageKind::Stream {
+            return Err(Error::WrongPackageKind);
+        }
         if package.status != PackageStatus::Created {
             return Err(Error::PackageNotActive);
         }
 
-        // State Transition
-        package.status = PackageStatus::Claimed;
-        env.storage().persistent().set(&key, &package);
-
-        // Update Locked
-        Self::decrement_locked(&env, &package.token, package.amount);
+        let stream = Self::read_stream(env, id);
+        Ok((package, stream))
+    }
 
-        // Transfer
-        let token_client = token::Client::new(&env, &package.token);
-        token_client.transfer(
-            &env.current_contract_address(),
-            &package.recipient,
-            &package.amount,
-        );
+    fn read_tranches(env: &Env, id: u64) -> Vec<Tranche> {
+        let sched_key = (symbol_short!("sched"), id);
+        env.storage()
+            .persistent()
+            .get(&sched_key)
+            .unwrap_or(Vec::new(env))
+    }
 
-        let timestamp = env.ledger().timestamp();
-        PackageDisbursed {
-            package_id: id,
-            recipient: package.recipient.clone(),
-            amount: package.amount,
-            actor: admin.clone(),
-            timestamp,
+    fn check_renounced(env: &Env) -> Result<(), Error> {
+        if Self::is_renounced(env.clone()) {
+            return Err(Error::AdminRenounced);
         }
-        .publish(&env);
-
         Ok(())
     }
 
-    /// Admin revokes a package (Cancels it). Funds are effectively unlocked but remain in contract pool.
-    pub fn revoke(env: Env, id: u64) -> Result<(), Error> {
-        let admin = Self::get_admin(env.clone())?;
-        admin.require_auth();
-
-        let key = (symbol_short!("pkg"), id);
-        let mut package: Package = env
-            .storage()
-            .persistent()
-            .get(&key)
-            .ok_or(Error::PackageNotFound)?;
+    fn read_pauses(env: &Env) -> Map<(PauseScope, Option<Address>), PauseInfo> {
+        env.storage()
+            .instance()
+            .get(&KEY_PAUSES)
+            .unwrap_or(Map::new(env))
+    }
 
-        if package.status != PackageStatus::Created {
-            return Err(Error::InvalidState);
+    /// Writes the pause map without lapsed entries, so it does not grow on every claim's path.
+    fn store_pauses(env: &Env, pauses: Map<(PauseScope, Option<Address>), PauseInfo>) {
+        let now = env.ledger().timestamp();
+        let mut active = Map::new(env);
+        for (key, pause) in pauses.iter() {
+            if pause.expires_at == 0 || now < pause.expires_at {
+                active.set(key, pause);
+            }
         }
+        if active.is_empty() {
+            env.storage().instance().remove(&KEY_PAUSES);
+        } else {
+            env.storage().instance().set(&KEY_PAUSES, &active);
+        }
+    }
 
-        // State Transition
-        package.status = PackageStatus::Cancelled;
-        env.storage().persistent().set(&key, &package);
-
-        // Unlock funds (return to pool)
-        Self::decrement_locked(&env, &package.token, package.amount);
+    /// Fails if `scope` or `All` is paused for every token or for `token`.
+    /// Packages may still be in the old layout while a migration runs or after an upgrade
+    /// to a newer layout, so nothing that reads them or moves funds may proceed until
+    /// storage has caught up.
+    fn check_not_migrating(env: &Env) -> Result<(), Error> {
+        if env.storage().instance().has(&KEY_MIGRATION) {
+            return Err(Error::MigrationInProgress);
+        }
+        if Self::get_upgrade_target(env.clone())
+            .is_some_and(|target| target > Self::get_version(env.clone()))
+        {
+            return Err(Error::MigrationInProgress);
+        }
+        Ok(())
+    }
 
-        let timestamp = env.ledger().timestamp();
-        PackageRevoked {
-            package_id: id,
-            recipient: package.recipient.clone(),
-            amount: package.amount,
-            actor: admin.clone(),
-            timestamp,
+    fn check_paused(env: &Env, scope: PauseScope, token: Option<Address>) -> Result<(), Error> {
+        Self::check_not_migrating(env)?;
+        let pauses = Self::read_pauses(env);
+        if pauses.is_empty() {
+            return Ok(());
+        }
+        let now = env.ledger().timestamp();
+        let mut keys = Vec::new(env);
+        keys.push_back((PauseScope::All, None));
+        keys.push_back((scope, None));
+        if token.is_some() {
+            keys.push_back((PauseScope::All, token.clone()));
+            keys.push_back((scope, token));
+        }
+        for key in keys.iter() {
+            if let Some(pause) = pauses.get(key)
+                && (pause.expires_at == 0 || now < pause.expires_at)
+            {
+                return Err(Error::ContractPaused);
+            }
         }
-        .publish(&env);
-
         Ok(())
     }
 
-    pub fn refund(env: Env, id: u64) -> Result<(), Error> {
-        let admin = Self::get_admin(env.clone())?;
-        admin.require_auth();
-
-        let key = (symbol_short!("pkg"), id);
-        let mut package: Package = env
-            .storage()
-            .persistent()
-            .get(&key)
-            .ok_or(Error::PackageNotFound)?;
+    /// Runs `op` for every id, collecting per-token unlocks and applying them once at the end.
+    /// Returns the ids that succeeded, their total amount and one result code per id
+    /// (0 = success, else the `Error` code). With `atomic` the first error is returned instead.
+    fn run_batch<F>(
+        env: &Env,
+        ids: &Vec<u64>,
+        atomic: bool,
+        mut op: F,
+    ) -> Result<(Vec<u64>, i128, Vec<u32>), Error>
+    where
+        F: FnMut(u64, &mut Map<Address, i128>) -> Result<i128, Error>,
+    {
+        let mut unlocks = Map::new(env);
+        let mut succeeded = Vec::new(env);
+        let mut results = Vec::new(env);
+        let mut total_amount: i128 = 0;
 
-        // Can only refund if Expired or Cancelled.
-        // If Created, must Revoke first. If Claimed, impossible.
-        // If Refunded, impossible.
-        if package.status == PackageStatus::Created {
-            // Check if actually expired
-            if package.expires_at > 0 && env.ledger().timestamp() > package.expires_at {
-                package.status = PackageStatus::Expired;
-                // If we just expired it, we need to unlock the funds first
-                Self::decrement_locked(&env, &package.token, package.amount);
-            } else {
-                return Err(Error::InvalidState);
+        for id in ids.iter() {
+            match op(id, &mut unlocks) {
+                Ok(amount) => {
+                    succeeded.push_back(id);
+                    results.push_back(0);
+                    total_amount += amount;
+                }
+                Err(err) if atomic => return Err(err),
+                Err(err) => results.push_back(err as u32),
             }
-        } else if package.status == PackageStatus::Claimed
-            || package.status == PackageStatus::Refunded
-        {
-            return Err(Error::InvalidState);
         }
 
-        // If Cancelled, funds were already unlocked in `revoke`.
-        // If Expired (logic above), funds were just unlocked.
-
-        // State Transition
-        package.status = PackageStatus::Refunded;
-        env.storage().persistent().set(&key, &package);
+        Self::release_locked(env, &unlocks);
+        Ok((succeeded, total_amount, results))
+    }
 
-        // Transfer Contract -> Admin
-        let token_client = token::Client::new(&env, &package.token);
-        token_client.transfer(&env.current_contract_address(), &admin, &package.amount);
+    fn add_unlock(unlocks: &mut Map<Address, i128>, token: &Address, amount: i128) {
+        let current = unlocks.get(token.clone()).unwrap_or(0);
+        unlocks.set(token.clone(), current + amount);
+    }
 
-        let timestamp = env.ledger().timestamp();
-        PackageRefunded {
-            package_id: id,
-            recipient: package.recipient.clone(),
-            amount: package.amount,
-            actor: admin.clone(),
-            timestamp,
+    /// Applies accumulated per-token unlocks with a single write per token.
+    fn release_locked(env: &Env, unlocks: &Map<Address, i128>) {
+        for (token, amount) in unlocks.iter() {
+            let current = Self::read_locked(env, &token);
+            Self::write_locked(env, &token, (current - amount).max(0));
         }
-        .publish(&env);
+    }
 
-        Ok(())
+    /// Money in `token` held for a purpose: unlocked earmarked funds, cancelled or expired
+    /// remainders awaiting refund, and the unmatched part of a matching pool. General packages
+    /// and surplus withdrawals cannot use it.
+    fn reserved_total(env: &Env, token: &Address) -> i128 {
+        let unmatched = Self::get_matching_pool(env.clone(), token.clone())
+            .map(|pool| pool.cap - pool.matched)
+            .unwrap_or(0);
+        Self::earmarked_total(env, token) + Self::held_total(env, token) + unmatched
     }
 
-    /// Admin-only package cancellation.
-    /// Requirements: Admin auth, existing package, status must be 'Created'.
-    pub fn cancel_package(env: Env, package_id: u64) -> Result<(), Error> {
-        // 1. Only the admin can cancel (check stored admin and require_auth)
-        let admin = Self::get_admin(env.clone())?;
-        admin.require_auth();
+    /// Unclaimed remainders of cancelled or expired general packages not yet refunded.
+    fn held_total(env: &Env, token: &Address) -> i128 {
+        Self::read_persistent(env, &(symbol_short!("held"), token.clone())).unwrap_or(0)
+    }
 
-        // 2. Package must exist
-        let key = (symbol_short!("pkg"), package_id);
-        let mut package: Package = env
-            .storage()
-            .persistent()
-            .get(&key)
-            .ok_or(Error::PackageNotFound)?;
+    fn add_held(env: &Env, token: &Address, delta: i128) {
+        let held = Self::held_total(env, token) + delta;
+        Self::write_persistent(env, &(symbol_short!("held"), token.clone()), &held);
+    }
 
-        // 3. Package status must be Created (not Claimed, Expired, or already Cancelled)
-        if package.status != PackageStatus::Created {
-            return Err(Error::PackageNotActive);
-        }
+    /// Unlocked earmarked funds in `token`, across all earmarks.
+    fn earmarked_total(env: &Env, token: &Address) -> i128 {
+        Self::read_persistent(env, &(symbol_short!("earmarked"), token.clone())).unwrap_or(0)
+    }
 
-        // Additional check: Ensure it hasn't expired yet (consistent with 'claim' logic)
-        if package.expires_at > 0 && env.ledger().timestamp() > package.expires_at {
-            return Err(Error::PackageExpired);
+    /// Stores an earmark and moves `unlocked_delta` into (or out of) the earmarked total.
+    fn store_earmark(
+        env: &Env,
+        tag: &Symbol,
+        token: &Address,
+        earmark: &Earmark,
+        unlocked_delta: i128,
+    ) {
+        let earmark_key = (symbol_short!("earmark"), tag.clone(), token.clone());
+        Self::write_persistent(env, &earmark_key, earmark);
+
+        if unlocked_delta != 0 {
+            let total = Self::earmarked_total(env, token) + unlocked_delta;
+            Self::write_persistent(env, &(symbol_short!("earmarked"), token.clone()), &total);
         }
+    }
 
-        // 4. Update status to Cancelled and persist
-        package.status = PackageStatus::Cancelled;
-        env.storage().persistent().set(&key, &package);
+    fn decrement_locked(env: &Env, token: &Address, amount: i128) {
+        let current = Self::read_locked(env, token);
+        let new_locked = if current > amount {
+            current - amount
+        } else {
+            0
+        };
 
-        // 5. Unlock funds (Decrement the global locked amount so funds return to the pool)
-        Self::decrement_locked(&env, &package.token, package.amount);
+        Self::write_locked(env, token, new_locked);
+    }
 
-        let timestamp = env.ledger().timestamp();
-        PackageRevoked {
-            package_id,
-            recipient: package.recipient.clone(),
-            amount: package.amount,
-            actor: admin.clone(),
-            timestamp,
-        }
-        .publish(&env);
+    fn read_locked(env: &Env, token: &Address) -> i128 {
+        Self::read_persistent(env, &(symbol_short!("lock"), token.clone())).unwrap_or(0)
+    }
 
-        Ok(())
+    fn write_locked(env: &Env, token: &Address, amount: i128) {
+        Self::write_persistent(env, &(symbol_short!("lock"), token.clone()), &amount);
     }
 
-    /// Admin-only package expiration extension.
-    /// Requirements: Admin auth, existing package, status must be 'Created', additional_time > 0.
-    /// Behavior: Adds additional_time to the package's expires_at timestamp.
-    /// Cannot extend unbounded packages (expires_at == 0).
-    pub fn extend_expiration(env: Env, package_id: u64, additional_time: u64) -> Result<(), Error> {
-        // 1. Only the admin can extend (check stored admin and require_auth)
+    /// Applies an approved or queued admin action as the stored admin. Withdrawals are
+    /// attributed to `requested_by`.
+    fn apply_action(env: &Env, action: AdminAction, requested_by: &Address) -> Result<(), Error> {
         let admin = Self::get_admin(env.clone())?;
-        admin.require_auth();
-        let config = Self::get_config(env.clone());
-
-        // 2. Package must exist
-        let key = (symbol_short!("pkg"), package_id);
-        let mut package: Package = env
-            .storage()
-            .persistent()
-            .get(&key)
-            .ok_or(Error::PackageNotFound)?;
-
-        // 3. Package status must be Created
-        if package.status != PackageStatus::Created {
-            return Err(Error::PackageNotActive);
+        match action {
+            AdminAction::WithdrawSurplus(to, amount, token) => {
+                Self::withdraw_surplus_as(env, requested_by, to, amount, token)?
+            }
+            AdminAction::SetConfig(config) => Self::apply_config(env, config)?,
+            AdminAction::Migrate(new_version) => Self::apply_migration(env, new_version)?,
+            AdminAction::Refund(package_id) => {
+                let mut unlocks = Map::new(env);
+                Self::refund_package(env, &admin, package_id, &mut unlocks)?;
+                Self::release_locked(env, &unlocks);
+            }
+            AdminAction::SetSigners(signers, threshold) => {
+                Self::apply_signers(env, signers, threshold)?
+            }
+            AdminAction::SetTokenAllowed(token, allowed) => {
+                Self::apply_token_allowed(env, token, allowed)?
+            }
+            AdminAction::SetTimelock(delay, withdraw_threshold) => {
+                Self::apply_timelock(env, delay, withdraw_threshold)?
+            }
+            AdminAction::Upgrade(wasm_hash, new_version) => {
+                Self::apply_upgrade(env, wasm_hash, new_version)?
+            }
+            AdminAction::ProposeAdmin(new_admin, delay) => {
+                Self::apply_propose_admin(env, &admin, new_admin, delay)?
+            }
+            AdminAction::RenounceAdmin => Self::apply_renounce(env, admin),
+            AdminAction::SetGuardian(recipient, guardian, payout_to) => {
+                Self::apply_set_guardian(env, admin, recipient, guardian, payout_to)?
+            }
+            AdminAction::ApproveReassignment(package_id) => {
+                Self::apply_reassignment(env, admin, package_id)?
+            }
+            AdminAction::AddDistributor(addr, record) => {
+                Self::apply_add_distributor(env, &admin, addr, record)?
+            }
+            AdminAction::GrantRole(role, account) => {
+                Self::apply_grant_role(env, admin, role, account)
+            }
         }
+        Ok(())
+    }
 
-        // 4. additional_time must be greater than 0
-        if additional_time == 0 {
-            return Err(Error::InvalidAmount);
+    fn is_timelocked(env: &Env, action: &AdminAction) -> bool {
+        let timelock = Self::get_timelock(env.clone());
+        if timelock.delay == 0 {
+            return false;
         }
-
-        // 5. Package must not be unbounded (expires_at must be > 0)
-        if package.expires_at == 0 {
-            return Err(Error::InvalidState);
+        match action {
+            AdminAction::WithdrawSurplus(_, amount, token) => {
+                Self::withdrawn_in_window(env, token, timelock.delay) + *amount
+                    > timelock.withdraw_threshold
+            }
+            AdminAction::SetConfig(_)
+            | AdminAction::Migrate(_)
+            | AdminAction::SetTokenAllowed(..)
+            | AdminAction::SetTimelock(..)
+            | AdminAction::Upgrade(..) => true,
+            AdminAction::Refund(_)
+            | AdminAction::SetSigners(..)
+            | AdminAction::ProposeAdmin(..)
+            | AdminAction::RenounceAdmin
+            | AdminAction::SetGuardian(..)
+            | AdminAction::ApproveReassignment(_)
+            | AdminAction::AddDistributor(..)
+            | AdminAction::GrantRole(..) => false,
         }
+    }
 
-        // 6. Package must not already be expired
-        if env.ledger().timestamp() > package.expires_at {
-            return Err(Error::PackageExpired);
+    /// Unqueued withdrawals of `token` in the current `delay`-long window.
+    fn withdrawn_in_window(env: &Env, token: &Address, delay: u64) -> i128 {
+        let window_key = (symbol_short!("withdrawn"), token.clone());
+        match Self::read_persistent::<_, (u64, i128)>(env, &window_key) {
+            Some((start, total)) if env.ledger().timestamp() < start + delay => total,
+            _ => 0,
         }
+    }
 
-        // 7. Calculate new expiration and update
-        let old_expires_at = package.expires_at;
-        let new_expires_at = old_expires_at + additional_time;
-        if config.max_expires_in > 0 {
-            let now = env.ledger().timestamp();
-            if new_expires_at <= now || new_expires_at - now > config.max_expires_in {
-                return Err(Error::InvalidState);
-            }
+    /// Counts an unqueued withdrawal towards the window checked by `is_timelocked`.
+    fn record_withdrawal(env: &Env, token: &Address, amount: i128) {
+        let delay = Self::get_timelock(env.clone()).delay;
+        if delay == 0 {
+            return;
         }
-        package.expires_at = new_expires_at;
-        env.storage().persistent().set(&key, &package);
+        // `(window start, total withdrawn in it)`
+        let window_key = (symbol_short!("withdrawn"), token.clone());
+        let now = env.ledger().timestamp();
+        let window = match Self::read_persistent::<_, (u64, i128)>(env, &window_key) {
+            Some((start, total)) if now < start + delay => (start, total + amount),
+            _ => (now, amount),
+        };
+        Self::write_persistent(env, &window_key, &window);
+    }
 
-        // 8. Emit Extended event
-        ExtendedEvent {
-            id: package_id,
-            admin: admin.clone(),
-            old_expires_at,
-            new_expires_at,
+    fn check_not_timelocked(env: &Env, action: &AdminAction) -> Result<(), Error> {
+        if Self::is_timelocked(env, action) {
+            return Err(Error::TimelockRequired);
         }
-        .publish(&env);
-
         Ok(())
     }
 
-    /// Admin-only function to withdraw surplus (unallocated) funds from the contract.
-    /// Requirements: Admin auth, valid amount, sufficient surplus available.
-    /// Behavior: Transfers amount of token from contract to the specified address.
-    pub fn withdraw_surplus(
-        env: Env,
-        to: Address,
-        amount: i128,
-        token: Address,
-    ) -> Result<(), Error> {
-        // 1. Only the admin can withdraw surplus
-        let admin = Self::get_admin(env.clone())?;
-        admin.require_auth();
+    fn enqueue_action(env: &Env, action: AdminAction, queued_by: &Address, approved: bool) -> u64 {
+        let id: u64 = env
+            .storage()
+            .instance()
+            .get(&KEY_QUEUE_COUNTER)
+            .unwrap_or(0);
+        env.storage().instance().set(&KEY_QUEUE_COUNTER, &(id + 1));
 
-        // 2. Validate amount
-        if amount <= 0 {
-            return Err(Error::InvalidAmount);
+        let now = env.ledger().timestamp();
+        let queued = QueuedAction {
+            id,
+            action: action.clone(),
+            queued_by: queued_by.clone(),
+            queued_at: now,
+            eta: now + Self::get_timelock(env.clone()).delay,
+            status: ProposalStatus::Pending,
+            approved,
+        };
+        Self::store_queued(env, &queued);
+
+        ActionQueued {
+            queue_id: id,
+            action,
+            queued_by: queued_by.clone(),
+            eta: queued.eta,
+            timestamp: now,
         }
+        .publish(env);
 
-        // 3. Get contract's current balance for the token
-        let token_client = token::Client::new(&env, &token);
-        let contract_balance = token_client.balance(&env.current_contract_address());
+        id
+    }
 
-        // 4. Get total locked amount for the token
-        let locked_map: Map<Address, i128> = env
+    /// Saves a queued action and keeps the pending id list in sync with its status.
+    fn store_queued(env: &Env, queued: &QueuedAction) {
+        Self::write_persistent(env, &(symbol_short!("queued"), queued.id), queued);
+
+        let mut pending: Vec<u64> = env
             .storage()
             .instance()
-            .get(&KEY_TOTAL_LOCKED)
-            .unwrap_or(Map::new(&env));
-        let total_locked = locked_map.get(token.clone()).unwrap_or(0);
-
-        // 5. Calculate available surplus and validate
-        let available_surplus = contract_balance - total_locked;
-        if amount > available_surplus {
-            return Err(Error::InsufficientSurplus);
+            .get(&KEY_QUEUED)
+            .unwrap_or(Vec::new(env));
+        let index = pending.first_index_of(queued.id);
+        match (index, queued.status) {
+            (None, ProposalStatus::Pending) => pending.push_back(queued.id),
+            (Some(i), ProposalStatus::Executed | ProposalStatus::Cancelled) => {
+                pending.remove(i);
+            }
+            _ => return,
         }
+        env.storage().instance().set(&KEY_QUEUED, &pending);
+    }
 
-        // 6. Transfer funds from contract to recipient
-        token_client.transfer(&env.current_contract_address(), &to, &amount);
+    fn apply_timelock(env: &Env, delay: u64, withdraw_threshold: i128) -> Result<(), Error> {
+        if withdraw_threshold < 0 {
+            return Err(Error::InvalidAmount);
+        }
+        env.storage().instance().set(
+            &KEY_TIMELOCK,
+            &Timelock {
+                delay,
+                withdraw_threshold,
+            },
+        );
 
-        // 7. Emit event
-        SurplusWithdrawnEvent {
-            to: to.clone(),
-            token: token.clone(),
-            amount,
+        TimelockUpdated {
+            delay,
+            withdraw_threshold,
+            timestamp: env.ledger().timestamp(),
         }
-        .publish(&env);
+        .publish(env);
 
         Ok(())
     }
 
-    // --- Helpers ---
+    /// Admin auth for high-risk actions that must go through a proposal once multisig is enabled.
+    fn require_single_admin(env: &Env) -> Result<Address, Error> {
+        let admin = Self::get_admin(env.clone())?;
+        admin.require_auth();
+        Self::check_multisig_disabled(env)?;
+        Ok(admin)
+    }
 
-    fn check_paused(env: &Env) -> Result<(), Error> {
-        if env.storage().instance().get(&KEY_PAUSED).unwrap_or(false) {
-            return Err(Error::ContractPaused);
+    fn check_multisig_disabled(env: &Env) -> Result<(), Error> {
+        if env.storage().instance().has(&KEY_MULTISIG) {
+            return Err(Error::MultisigRequired);
         }
         Ok(())
     }
 
-    fn decrement_locked(env: &Env, token: &Address, amount: i128) {
-        let mut locked_map: Map<Address, i128> = env
-            .storage()
-            .instance()
-            .get(&KEY_TOTAL_LOCKED)
-            .unwrap_or(Map::new(env));
+    /// `caller` must authorize and be the admin or hold `role`.
+    fn require_role(env: &Env, role: Role, caller: &Address) -> Result<(), Error> {
+        Self::require_any_role(env, &[role], caller)
+    }
 
-        let current = locked_map.get(token.clone()).unwrap_or(0);
-        let new_locked = if current > amount {
-            current - amount
+    fn require_any_role(env: &Env, roles: &[Role], caller: &Address) -> Result<(), Error> {
+        caller.require_auth();
+        let admin = Self::get_admin(env.clone())?;
+        if *caller == admin
+            || roles
+                .iter()
+                .any(|role| Self::is_role_member(env, *role, caller))
+        {
+            Ok(())
         } else {
-            0
-        };
+            Err(Error::NotAuthorized)
+        }
+    }
+
+    fn require_signer(env: &Env, signer: &Address) -> Result<(), Error> {
+        Self::check_renounced(env)?;
+        signer.require_auth();
+        let multisig = Self::get_multisig(env.clone()).ok_or(Error::NotAuthorized)?;
+        if multisig.signers.contains(signer) {
+            Ok(())
+        } else {
+            Err(Error::NotAuthorized)
+        }
+    }
+
+    fn load_pending_proposal(env: &Env, id: u64) -> Result<Proposal, Error> {
+        let proposal = Self::load_proposal(env, id)?;
+        if proposal.status != ProposalStatus::Pending {
+            return Err(Error::ProposalNotPending);
+        }
+        if env.ledger().timestamp() > proposal.expires_at {
+            return Err(Error::ProposalExpired);
+        }
+        Ok(proposal)
+    }
+
+    /// Empty signers with threshold 0 disables multisig; otherwise 1 <= threshold <= N
+    /// and signers must be unique.
+    fn apply_signers(env: &Env, signers: Vec<Address>, threshold: u32) -> Result<(), Error> {
+        if signers.is_empty() && threshold == 0 {
+            env.storage().instance().remove(&KEY_MULTISIG);
+        } else {
+            if threshold == 0 || threshold > signers.len() {
+                return Err(Error::InvalidState);
+            }
+            for (i, signer) in signers.iter().enumerate() {
+                if signers.first_index_of(&signer) != Some(i as u32) {
+                    return Err(Error::InvalidState);
+                }
+            }
+            env.storage().instance().set(
+                &KEY_MULTISIG,
+                &Multisig {
+                    signers: signers.clone(),
+                    threshold,
+                },
+            );
+        }
+
+        SignersUpdated {
+            signers,
+            threshold,
+            timestamp: env.ledger().timestamp(),
+        }
+        .publish(env);
 
-        locked_map.set(token.clone(), new_locked);
-        env.storage().instance().set(&KEY_TOTAL_LOCKED, &locked_map);
+        Ok(())
     }
 
     fn require_admin_or_distributor(env: &Env, operator: &Address) -> Result<(), Error> {
@@ -912,24 +5167,107 @@ impl AidEscrow {
             return Ok(());
         }
 
-        let distributors: Map<Address, bool> = env
-            .storage()
-            .instance()
-            .get(&KEY_DISTRIBUTORS)
-            .unwrap_or(Map::new(env));
-        if distributors.get(operator.clone()).unwrap_or(false) {
-            Ok(())
+        // Distributors past their `active_until` lose operator rights
+        match Self::read_distributor(env, operator) {
+            Some(record)
+                if record.active_until == 0 || env.ledger().timestamp() <= record.active_until =>
+            {
+                Ok(())
+            }
+            _ => Err(Error::NotAuthorized),
+        }
+    }
+
+    /// Metadata is frozen once a package leaves `Created`. Before that, only the admin, the
+    /// package's creator, or a distributor whose scope covers the package may edit it.
+    fn check_metadata_editor(
+        env: &Env,
+        operator: &Address,
+        package: &Package,
+    ) -> Result<(), Error> {
+        if package.status != PackageStatus::Created {
+            return Err(Error::PackageNotActive);
+        }
+        if *operator == package.created_by {
+            return Ok(());
+        }
+        let horizon = if package.kind == PackageKind::Stream {
+            Self::read_stream(env, package.id).end_at
         } else {
-            Err(Error::NotAuthorized)
+            package.expires_at
+        };
+        Self::check_distributor_scope(env, operator, package, horizon)
+    }
+
+    /// Shared by the config and distributor `max_expires_in` limits (0 = unlimited), so both
+    /// measure the claim window from when it opens, not the pre-staging period.
+    fn within_expiry_limit(
+        env: &Env,
+        max_expires_in: u64,
+        claimable_from: u64,
+        horizon: u64,
+    ) -> bool {
+        if max_expires_in == 0 {
+            return true;
+        }
+        let opens_at = env.ledger().timestamp().max(claimable_from);
+        horizon > opens_at && horizon - opens_at <= max_expires_in
+    }
+
+    /// Checks a new package against its creator's distributor scope; the admin is unscoped.
+    /// `horizon` is the package's last timestamp (the stream end for streams).
+    fn check_distributor_scope(
+        env: &Env,
+        operator: &Address,
+        package: &Package,
+        horizon: u64,
+    ) -> Result<(), Error> {
+        let Some(record) = Self::read_distributor(env, operator) else {
+            return Ok(());
+        };
+        if *operator == Self::get_admin(env.clone())? {
+            return Ok(());
         }
+
+        if !record.allowed_tokens.is_empty() && !record.allowed_tokens.contains(&package.token) {
+            return Err(Error::DistributorScopeViolation);
+        }
+        if record.max_amount > 0 && package.amount > record.max_amount {
+            return Err(Error::DistributorScopeViolation);
+        }
+        if !Self::within_expiry_limit(env, record.max_expires_in, package.claimable_from, horizon) {
+            return Err(Error::DistributorScopeViolation);
+        }
+        if record.campaign.is_some() && record.campaign != package.earmark {
+            return Err(Error::DistributorScopeViolation);
+        }
+        Ok(())
     }
 
     pub fn get_package(env: Env, id: u64) -> Result<Package, Error> {
-        let key = (symbol_short!("pkg"), id);
-        env.storage()
+        Self::read_package(&env, id)
+    }
+
+    /// Ledgers left until the point this contract last extended the package to. Contracts
+    /// cannot read real TTLs; the real one is never shorter, since anyone else extending the
+    /// entries can only lengthen it.
+    pub fn get_package_extended_ttl(env: Env, id: u64) -> Result<u32, Error> {
+        if !env.storage().persistent().has(&(symbol_short!("pkg"), id)) {
+            return Err(Error::PackageNotFound);
+        }
+        let live_until: u32 = env
+            .storage()
             .persistent()
-            .get(&key)
-            .ok_or(Error::PackageNotFound)
+            .get(&(symbol_short!("ttl"), id))
+            .unwrap_or(0);
+        Ok(live_until.saturating_sub(env.ledger().sequence()))
+    }
+
+    /// Same as `get_package_extended_ttl`, for the instance data (admin, config and other
+    /// settings).
+    pub fn get_instance_extended_ttl(env: Env) -> u32 {
+        let live_until: u32 = env.storage().instance().get(&KEY_INSTANCE_TTL).unwrap_or(0);
+        live_until.saturating_sub(env.ledger().sequence())
     }
 
     /// Returns only the status of a package.
@@ -939,18 +5277,50 @@ impl AidEscrow {
         Ok(pkg.status)
     }
 
+    /// Returns the release schedule of a tranched package (empty for standard packages).
+    pub fn get_tranches(env: Env, id: u64) -> Result<Vec<Tranche>, Error> {
+        let pkg = Self::get_package(env.clone(), id)?;
+        Ok(Self::read_tranches(&env, pkg.id))
+    }
+
+    /// Returns the accrual window of a streaming package.
+    pub fn get_stream(env: Env, id: u64) -> Result<StreamSchedule, Error> {
+        let pkg = Self::get_package(env.clone(), id)?;
+        if pkg.kind != PackageKind::Stream {
+            return Err(Error::WrongPackageKind);
+        }
+        Ok(Self::read_stream(&env, id))
+    }
+
+    /// Returns the amount the recipient could claim right now.
+    pub fn get_claimable(env: Env, id: u64) -> Result<i128, Error> {
+        let pkg = Self::get_package(env.clone(), id)?;
+        let now = env.ledger().timestamp();
+        if pkg.status != PackageStatus::Created
+            || (pkg.expires_at > 0 && now > pkg.expires_at)
+            || now < pkg.claimable_from
+        {
+            return Ok(0);
+        }
+        Ok(Self::released_amount(&env, &pkg) - pkg.claimed_amount)
+    }
+
     // --- Analytics ---
 
     /// Returns aggregate statistics for a given token.
     ///
     /// Iterates across all created packages and computes:
-    /// - `total_committed`: sum of amounts for packages still in `Created` status,
-    /// - `total_claimed`: sum of amounts for packages in `Claimed` status,
-    /// - `total_expired_cancelled`: sum of amounts for packages in `Expired`,
+    /// - `total_committed`: unclaimed amounts of packages still in `Created` status,
+    /// - `total_claimed`: amounts released to recipients, including the claimed part
+    ///    of partly claimed packages,
+    /// - `total_expired_cancelled`: unclaimed amounts of packages in `Expired`,
     ///    `Cancelled`, or `Refunded` status.
     ///
     /// This is a read-only view intended for dashboards and analytics.
     pub fn get_aggregates(env: Env, token: Address) -> Aggregates {
+        if let Err(err) = Self::check_not_migrating(&env) {
+            panic_with_error!(&env, err);
+        }
         let count: u64 = env.storage().instance().get(&KEY_PKG_IDX).unwrap_or(0);
 
         let mut total_committed: i128 = 0;
@@ -964,17 +5334,19 @@ impl AidEscrow {
                 if let Some(package) = env.storage().persistent().get::<_, Package>(&pkg_key)
                     && package.token == token
                 {
+                    let unclaimed = package.amount - package.claimed_amount;
+                    total_claimed += package.claimed_amount;
                     match package.status {
                         PackageStatus::Created => {
-                            total_committed += package.amount;
+                            total_committed += unclaimed;
                         }
                         PackageStatus::Claimed => {
-                            total_claimed += package.amount;
+                            total_claimed += unclaimed;
                         }
                         PackageStatus::Expired
                         | PackageStatus::Cancelled
                         | PackageStatus::Refunded => {
-                            total_expired_cancelled += package.amount;
+                            total_expired_cancelled += unclaimed;
                         }
                     }
                 }
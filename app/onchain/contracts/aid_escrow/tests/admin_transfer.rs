@@ -0,0 +1,147 @@
+#![cfg(test)]
+
+use aid_escrow::{AidEscrow, AidEscrowClient, Error, PackageStatus, PauseScope, PendingAdmin};
+use soroban_sdk::{
+    Address, Env, Vec,
+    testutils::{Address as _, Ledger},
+    token::{StellarAssetClient, TokenClient},
+};
+
+fn setup_token(env: &Env, admin: &Address) -> (TokenClient<'static>, StellarAssetClient<'static>) {
+    let token_contract = env.register_stellar_asset_contract_v2(admin.clone());
+    let token_client = TokenClient::new(env, &token_contract.address());
+    let token_admin_client = StellarAssetClient::new(env, &token_contract.address());
+    (token_client, token_admin_client)
+}
+
+/// Helper: set up contract, token, fund, and return the client + token client.
+fn setup_funded(
+    env: &Env,
+    fund_amount: i128,
+) -> (
+    AidEscrowClient<'static>,
+    TokenClient<'static>,
+    Address,
+    StellarAssetClient<'static>,
+) {
+    let admin = Address::generate(env);
+    let token_admin = Address::generate(env);
+    let (token_client, token_admin_client) = setup_token(env, &token_admin);
+
+    let contract_id = env.register(AidEscrow, ());
+    let client = AidEscrowClient::new(env, &contract_id);
+
+    client.init(&admin);
+    token_admin_client.mint(&admin, &fund_amount);
+    client.fund(&token_client.address, &admin, &fund_amount);
+
+    (client, token_client, admin, token_admin_client)
+}
+
+#[test]
+fn test_two_step_admin_transfer() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.ledger().set_timestamp(1000);
+
+    let (client, token_client, admin, _) = setup_funded(&env, 10_000);
+    let partner = Address::generate(&env);
+    let recipient = Address::generate(&env);
+
+    client.propose_admin(&partner, &3600);
+    assert_eq!(
+        client.get_pending_admin(),
+        Some(PendingAdmin {
+            new_admin: partner.clone(),
+            proposed_at: 1000,
+            accept_after: 4600,
+        })
+    );
+
+    // Nothing changes until the partner accepts after the delay
+    assert_eq!(
+        client.try_accept_admin(),
+        Err(Ok(Error::AdminTransferNotReady))
+    );
+    assert_eq!(client.get_admin(), admin);
+
+    env.ledger().set_timestamp(4600);
+    client.accept_admin();
+    assert_eq!(client.get_admin(), partner);
+    assert_eq!(client.get_pending_admin(), None);
+
+    // The old admin is now just another address
+    let result =
+        client.try_create_package(&admin, &1, &recipient, &1000, &token_client.address, &0);
+    assert_eq!(result, Err(Ok(Error::NotAuthorized)));
+    client.create_package(&partner, &1, &recipient, &1000, &token_client.address, &0);
+}
+
+#[test]
+fn test_cancel_admin_transfer() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (client, _, admin, _) = setup_funded(&env, 10_000);
+    let partner = Address::generate(&env);
+
+    assert_eq!(
+        client.try_cancel_admin_transfer(),
+        Err(Ok(Error::NoPendingAdmin))
+    );
+    assert_eq!(
+        client.try_propose_admin(&admin, &0),
+        Err(Ok(Error::InvalidState))
+    );
+
+    client.propose_admin(&partner, &0);
+    client.cancel_admin_transfer();
+    assert_eq!(client.try_accept_admin(), Err(Ok(Error::NoPendingAdmin)));
+    assert_eq!(client.get_admin(), admin);
+}
+
+#[test]
+fn test_renounce_admin_leaves_claims_and_expiry() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.ledger().set_timestamp(1000);
+
+    let (client, token_client, admin, token_admin_client) = setup_funded(&env, 10_000);
+    let recipient = Address::generate(&env);
+    let late = Address::generate(&env);
+
+    client.create_package(&admin, &1, &recipient, &1000, &token_client.address, &0);
+    client.create_package(&admin, &2, &late, &1000, &token_client.address, &2000);
+    client.pause(&admin, &PauseScope::All, &None, &None, &0);
+    client.renounce_admin();
+    assert!(client.is_renounced());
+    assert!(!client.is_paused(&PauseScope::All, &None));
+
+    // Admin, distributor and funding paths are closed for good
+    assert_eq!(client.try_get_admin(), Err(Ok(Error::AdminRenounced)));
+    assert_eq!(
+        client.try_pause(&admin, &PauseScope::All, &None, &None, &0),
+        Err(Ok(Error::AdminRenounced))
+    );
+    assert_eq!(
+        client.try_revoke(&admin, &1),
+        Err(Ok(Error::AdminRenounced))
+    );
+    let result = client.try_create_package(&admin, &3, &recipient, &1, &token_client.address, &0);
+    assert_eq!(result, Err(Ok(Error::AdminRenounced)));
+    token_admin_client.mint(&admin, &500);
+    let result = client.try_fund(&token_client.address, &admin, &500);
+    assert_eq!(result, Err(Ok(Error::AdminRenounced)));
+    let result = client.try_set_signers(&Vec::new(&env), &0);
+    assert_eq!(result, Err(Ok(Error::AdminRenounced)));
+
+    // Recipients still claim and overdue packages still expire
+    client.claim(&1);
+    assert_eq!(token_client.balance(&recipient), 1000);
+
+    env.ledger().set_timestamp(2001);
+    let mut ids = Vec::new(&env);
+    ids.push_back(2);
+    assert_eq!(client.expire_packages(&ids), 1);
+    assert_eq!(client.get_package(&2).status, PackageStatus::Expired);
+}
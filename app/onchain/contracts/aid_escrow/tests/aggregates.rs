@@ -111,7 +111,7 @@ fn test_aggregates_mixed_statuses() {
 
     // Package 3 — will be Cancelled (via revoke)
     client.create_package(&admin, &3, &r3, &500, &token_client.address, &expiry);
-    client.revoke(&3);
+    client.revoke(&admin, &3);
 
     // Package 4 — will be Expired then Refunded
     client.create_package(&admin, &4, &r4, &750, &token_client.address, &short_expiry);
@@ -160,8 +160,8 @@ fn test_aggregates_all_cancelled() {
 
     client.create_package(&admin, &20, &r1, &1500, &token_client.address, &expiry);
     client.create_package(&admin, &21, &r2, &2500, &token_client.address, &expiry);
-    client.cancel_package(&20);
-    client.cancel_package(&21);
+    client.cancel_package(&admin, &20);
+    client.cancel_package(&admin, &21);
 
     let agg = client.get_aggregates(&token_client.address);
     assert_eq!(agg.total_committed, 0);
@@ -203,7 +203,7 @@ fn test_aggregates_filters_by_token() {
 
     // Token B packages
     client.create_package(&admin, &3, &r1, &5000, &token_b.address, &expiry);
-    client.revoke(&3);
+    client.revoke(&admin, &3);
 
     // Aggregates for Token A
     let agg_a = client.get_aggregates(&token_a.address);
@@ -284,7 +284,7 @@ fn test_aggregates_many_packages() {
         if i % 2 == 0 {
             client.claim(&i);
         } else {
-            client.cancel_package(&i);
+            client.cancel_package(&admin, &i);
         }
     }
 
@@ -338,7 +338,7 @@ fn test_aggregates_revoke_then_refund() {
     assert_eq!(agg1.total_committed, 4000);
 
     // After revoke (Cancelled)
-    client.revoke(&1);
+    client.revoke(&admin, &1);
     let agg2 = client.get_aggregates(&token_client.address);
     assert_eq!(agg2.total_committed, 0);
     assert_eq!(agg2.total_expired_cancelled, 4000);
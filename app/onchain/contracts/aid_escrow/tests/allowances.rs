@@ -0,0 +1,218 @@
+#![cfg(test)]
+
+use aid_escrow::{AidEscrow, AidEscrowClient, DistributorRecord, Error};
+use soroban_sdk::{
+    Address, Env, Vec,
+    testutils::{Address as _, Ledger},
+    token::{StellarAssetClient, TokenClient},
+};
+
+fn setup_token(env: &Env, admin: &Address) -> (TokenClient<'static>, StellarAssetClient<'static>) {
+    let token_contract = env.register_stellar_asset_contract_v2(admin.clone());
+    let token_client = TokenClient::new(env, &token_contract.address());
+    let token_admin_client = StellarAssetClient::new(env, &token_contract.address());
+    (token_client, token_admin_client)
+}
+
+/// Helper: funded contract with one distributor; returns client, token client, admin and
+/// distributor.
+fn setup_distributor(
+    env: &Env,
+) -> (
+    AidEscrowClient<'static>,
+    TokenClient<'static>,
+    Address,
+    Address,
+) {
+    let admin = Address::generate(env);
+    let distributor = Address::generate(env);
+    let token_admin = Address::generate(env);
+    let (token_client, token_admin_client) = setup_token(env, &token_admin);
+
+    let contract_id = env.register(AidEscrow, ());
+    let client = AidEscrowClient::new(env, &contract_id);
+
+    client.init(&admin);
+    client.add_distributor(
+        &distributor,
+        &DistributorRecord {
+            allowed_tokens: Vec::new(env),
+            max_amount: 0,
+            max_expires_in: 0,
+            campaign: None,
+            active_until: 0,
+            packages_created: 0,
+            total_committed: 0,
+            last_active: 0,
+        },
+    );
+    token_admin_client.mint(&admin, &100_000);
+    client.fund(&token_client.address, &admin, &100_000);
+
+    (client, token_client, admin, distributor)
+}
+
+#[test]
+fn test_allowance_limits_distributor() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (client, token_client, admin, distributor) = setup_distributor(&env);
+    let recipient = Address::generate(&env);
+
+    // No allowance configured: unlimited
+    assert_eq!(
+        client.get_remaining_allowance(&distributor, &token_client.address),
+        None
+    );
+
+    client.set_allowance(&distributor, &token_client.address, &5000, &0, &0);
+    client.create_package(
+        &distributor,
+        &1,
+        &recipient,
+        &3000,
+        &token_client.address,
+        &0,
+    );
+    assert_eq!(
+        client.get_remaining_allowance(&distributor, &token_client.address),
+        Some(2000)
+    );
+
+    let result = client.try_create_package(
+        &distributor,
+        &2,
+        &recipient,
+        &2500,
+        &token_client.address,
+        &0,
+    );
+    assert_eq!(result, Err(Ok(Error::AllowanceExceeded)));
+
+    let mut recipients = Vec::new(&env);
+    recipients.push_back(recipient.clone());
+    recipients.push_back(recipient.clone());
+    let mut amounts = Vec::new(&env);
+    amounts.push_back(1000_i128);
+    amounts.push_back(1500_i128);
+    let result = client.try_batch_create_packages(
+        &distributor,
+        &recipients,
+        &amounts,
+        &token_client.address,
+        &0,
+    );
+    assert_eq!(result, Err(Ok(Error::AllowanceExceeded)));
+
+    // The admin is never limited
+    client.create_package(&admin, &3, &recipient, &50_000, &token_client.address, &0);
+}
+
+#[test]
+fn test_allowance_returned_on_revoke_and_expiry() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.ledger().set_timestamp(1000);
+
+    let (client, token_client, admin, distributor) = setup_distributor(&env);
+    let recipient = Address::generate(&env);
+    client.set_allowance(&distributor, &token_client.address, &5000, &0, &0);
+
+    client.create_package(
+        &distributor,
+        &1,
+        &recipient,
+        &2000,
+        &token_client.address,
+        &0,
+    );
+    client.create_package(
+        &distributor,
+        &2,
+        &recipient,
+        &2000,
+        &token_client.address,
+        &2000,
+    );
+    client.create_package(
+        &distributor,
+        &3,
+        &recipient,
+        &1000,
+        &token_client.address,
+        &0,
+    );
+    assert_eq!(
+        client.get_remaining_allowance(&distributor, &token_client.address),
+        Some(0)
+    );
+
+    // Claimed packages stay counted
+    client.claim(&3);
+    client.revoke(&admin, &1);
+    env.ledger().set_timestamp(2001);
+    let mut ids = Vec::new(&env);
+    ids.push_back(2u64);
+    client.expire_packages(&ids);
+
+    let allowance = client
+        .get_allowance(&distributor, &token_client.address)
+        .unwrap();
+    assert_eq!(allowance.used, 1000);
+    assert_eq!(
+        client.get_remaining_allowance(&distributor, &token_client.address),
+        Some(4000)
+    );
+}
+
+#[test]
+fn test_allowance_period_cap() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.ledger().set_timestamp(1000);
+
+    let (client, token_client, admin, distributor) = setup_distributor(&env);
+    let recipient = Address::generate(&env);
+    client.set_allowance(&distributor, &token_client.address, &50_000, &86_400, &3000);
+
+    client.create_package(
+        &distributor,
+        &1,
+        &recipient,
+        &3000,
+        &token_client.address,
+        &0,
+    );
+    assert_eq!(
+        client.get_remaining_allowance(&distributor, &token_client.address),
+        Some(0)
+    );
+
+    // Revoking does not reopen the daily cap
+    client.revoke(&admin, &1);
+    let result = client.try_create_package(
+        &distributor,
+        &2,
+        &recipient,
+        &100,
+        &token_client.address,
+        &0,
+    );
+    assert_eq!(result, Err(Ok(Error::AllowanceExceeded)));
+
+    // The next day the cap is available again
+    env.ledger().set_timestamp(1000 + 86_400);
+    assert_eq!(
+        client.get_remaining_allowance(&distributor, &token_client.address),
+        Some(3000)
+    );
+    client.create_package(
+        &distributor,
+        &2,
+        &recipient,
+        &3000,
+        &token_client.address,
+        &0,
+    );
+}
@@ -3,7 +3,7 @@
 use aid_escrow::{AidEscrow, AidEscrowClient, Error, PackageStatus};
 use soroban_sdk::{
     Address, Env, Vec,
-    testutils::Address as _,
+    testutils::{Address as _, Ledger},
     token::{StellarAssetClient, TokenClient},
 };
 
@@ -271,3 +271,122 @@ fn test_batch_then_individual_no_id_collision() {
     let pkg1 = client.get_package(&1);
     assert_eq!(pkg1.recipient, recipient2);
 }
+
+/// Creates `count` packages of 1000 each with ids 0..count and returns the client,
+/// token client and admin.
+fn setup_packages(
+    env: &Env,
+    count: u64,
+    expires_at: u64,
+) -> (AidEscrowClient<'static>, TokenClient<'static>, Address) {
+    let admin = Address::generate(env);
+    let token_admin = Address::generate(env);
+    let (token_client, token_admin_client) = setup_token(env, &token_admin);
+
+    let contract_id = env.register(AidEscrow, ());
+    let client = AidEscrowClient::new(env, &contract_id);
+
+    client.init(&admin);
+    token_admin_client.mint(&admin, &10_000);
+    client.fund(&token_client.address, &admin, &10_000);
+
+    for id in 0..count {
+        let recipient = Address::generate(env);
+        client.create_package(
+            &admin,
+            &id,
+            &recipient,
+            &1000,
+            &token_client.address,
+            &expires_at,
+        );
+    }
+
+    (client, token_client, admin)
+}
+
+#[test]
+fn test_batch_disburse_and_claim() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (client, token_client, _) = setup_packages(&env, 4, 0);
+
+    let mut ids = Vec::new(&env);
+    ids.push_back(0u64);
+    ids.push_back(1u64);
+    let results = client.batch_disburse(&ids, &true);
+    assert_eq!(results, Vec::from_array(&env, [0u32, 0]));
+
+    let mut ids = Vec::new(&env);
+    ids.push_back(2u64);
+    ids.push_back(3u64);
+    client.batch_claim(&ids, &true);
+
+    for id in 0..4u64 {
+        let pkg = client.get_package(&id);
+        assert_eq!(pkg.status, PackageStatus::Claimed);
+        assert_eq!(token_client.balance(&pkg.recipient), 1000);
+    }
+    let aggregates = client.get_aggregates(&token_client.address);
+    assert_eq!(aggregates.total_committed, 0);
+    assert_eq!(aggregates.total_claimed, 4000);
+}
+
+#[test]
+fn test_batch_revoke_atomic_rolls_back_on_failure() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (client, _, admin) = setup_packages(&env, 3, 0);
+    client.revoke(&admin, &1);
+
+    let mut ids = Vec::new(&env);
+    ids.push_back(0u64);
+    ids.push_back(1u64);
+    ids.push_back(2u64);
+    let result = client.try_batch_revoke(&admin, &ids, &true);
+    assert_eq!(result, Err(Ok(Error::InvalidState)));
+
+    // Nothing was revoked
+    assert_eq!(client.get_package(&0).status, PackageStatus::Created);
+    assert_eq!(client.get_package(&2).status, PackageStatus::Created);
+}
+
+#[test]
+fn test_batch_refund_reports_per_item_results() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.ledger().set_timestamp(1000);
+
+    let (client, token_client, admin) = setup_packages(&env, 3, 2000);
+    client.revoke(&admin, &0);
+    client.claim(&2);
+    env.ledger().set_timestamp(2001);
+
+    // 0 is cancelled, 1 is overdue, 2 was claimed and 7 does not exist
+    let mut ids = Vec::new(&env);
+    for id in [0u64, 1, 2, 7] {
+        ids.push_back(id);
+    }
+    let results = client.batch_refund(&ids, &false);
+    assert_eq!(
+        results,
+        Vec::from_array(
+            &env,
+            [
+                0u32,
+                0,
+                Error::InvalidState as u32,
+                Error::PackageNotFound as u32
+            ]
+        )
+    );
+
+    assert_eq!(client.get_package(&0).status, PackageStatus::Refunded);
+    assert_eq!(client.get_package(&1).status, PackageStatus::Refunded);
+    assert_eq!(token_client.balance(&admin), 2000);
+
+    // No funds remain locked, so everything left can be withdrawn
+    client.withdraw_surplus(&admin, &admin, &7000, &token_client.address);
+}
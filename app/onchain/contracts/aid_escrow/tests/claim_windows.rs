@@ -0,0 +1,171 @@
+#![cfg(test)]
+
+use aid_escrow::{AidEscrow, AidEscrowClient, Config, Error, PackageOptions, PackageStatus};
+use soroban_sdk::{
+    Address, Env, Map, Vec,
+    testutils::{Address as _, Ledger},
+    token::{StellarAssetClient, TokenClient},
+};
+
+fn setup_token(env: &Env, admin: &Address) -> (TokenClient<'static>, StellarAssetClient<'static>) {
+    let token_contract = env.register_stellar_asset_contract_v2(admin.clone());
+    let token_client = TokenClient::new(env, &token_contract.address());
+    let token_admin_client = StellarAssetClient::new(env, &token_contract.address());
+    (token_client, token_admin_client)
+}
+
+/// Helper: set up contract, token, fund, and return the client + token client.
+fn setup_funded(
+    env: &Env,
+    fund_amount: i128,
+) -> (
+    AidEscrowClient<'static>,
+    TokenClient<'static>,
+    Address,
+    Address,
+) {
+    let admin = Address::generate(env);
+    let token_admin = Address::generate(env);
+    let (token_client, token_admin_client) = setup_token(env, &token_admin);
+
+    let contract_id = env.register(AidEscrow, ());
+    let client = AidEscrowClient::new(env, &contract_id);
+
+    client.init(&admin);
+    token_admin_client.mint(&admin, &fund_amount);
+    client.fund(&token_client.address, &admin, &fund_amount);
+
+    (client, token_client, admin, contract_id)
+}
+
+fn window_from(env: &Env, claimable_from: u64) -> PackageOptions {
+    PackageOptions {
+        metadata: Map::new(env),
+        claimable_from,
+        funder: None,
+        earmark: None,
+    }
+}
+
+#[test]
+fn test_claim_window_opens_at_claimable_from() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.ledger().set_timestamp(1000);
+
+    let (client, token_client, admin, _) = setup_funded(&env, 10_000);
+    let recipient = Address::generate(&env);
+
+    client.create_package_with_options(
+        &admin,
+        &1,
+        &recipient,
+        &1000,
+        &token_client.address,
+        &10_000,
+        &window_from(&env, 5000),
+    );
+    assert_eq!(client.get_package(&1).claimable_from, 5000);
+    assert_eq!(client.get_claimable(&1), 0);
+
+    // Locked but not yet claimable, even for the admin
+    assert_eq!(client.try_claim(&1), Err(Ok(Error::PackageNotYetClaimable)));
+    assert_eq!(
+        client.try_disburse(&1),
+        Err(Ok(Error::PackageNotYetClaimable))
+    );
+
+    env.ledger().set_timestamp(5000);
+    assert_eq!(client.get_claimable(&1), 1000);
+    client.claim(&1);
+    assert_eq!(token_client.balance(&recipient), 1000);
+    assert_eq!(client.get_package(&1).status, PackageStatus::Claimed);
+}
+
+#[test]
+fn test_claim_window_must_open_before_expiry() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.ledger().set_timestamp(1000);
+
+    let (client, token_client, admin, _) = setup_funded(&env, 10_000);
+    let recipient = Address::generate(&env);
+
+    let result = client.try_create_package_with_options(
+        &admin,
+        &1,
+        &recipient,
+        &1000,
+        &token_client.address,
+        &5000,
+        &window_from(&env, 5000),
+    );
+    assert_eq!(result, Err(Ok(Error::InvalidState)));
+
+    // Batches count `expires_in` from the window start
+    let mut recipients = Vec::new(&env);
+    recipients.push_back(recipient.clone());
+    let mut amounts = Vec::new(&env);
+    amounts.push_back(500_i128);
+    let ids = client.batch_create_with_options(
+        &admin,
+        &recipients,
+        &amounts,
+        &token_client.address,
+        &3600,
+        &window_from(&env, 5000),
+    );
+    let pkg = client.get_package(&ids.get(0).unwrap());
+    assert_eq!(pkg.claimable_from, 5000);
+    assert_eq!(pkg.expires_at, 8600);
+}
+
+#[test]
+fn test_max_expires_in_measured_from_window_start() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.ledger().set_timestamp(1000);
+
+    let (client, token_client, admin, _) = setup_funded(&env, 10_000);
+    let recipient = Address::generate(&env);
+    client.set_config(&Config {
+        min_amount: 1,
+        max_expires_in: 3600,
+        allowed_tokens: Vec::new(&env),
+        reassignment_delay: 0,
+        metadata_keys: Vec::new(&env),
+        max_metadata_entries: 0,
+        max_metadata_value_len: 0,
+    });
+
+    // Staged three days ahead with a one hour claim window
+    let opens_at = 1000 + 3 * 86400;
+    client.create_package_with_options(
+        &admin,
+        &1,
+        &recipient,
+        &1000,
+        &token_client.address,
+        &(opens_at + 3600),
+        &window_from(&env, opens_at),
+    );
+
+    // A window longer than the limit is rejected
+    let result = client.try_create_package_with_options(
+        &admin,
+        &2,
+        &recipient,
+        &1000,
+        &token_client.address,
+        &(opens_at + 3601),
+        &window_from(&env, opens_at),
+    );
+    assert_eq!(result, Err(Ok(Error::InvalidState)));
+
+    // Extensions are also limited relative to the window start
+    let result = client.try_extend_expiration(&admin, &1, &1);
+    assert_eq!(result, Err(Ok(Error::InvalidState)));
+    env.ledger().set_timestamp(opens_at + 1800);
+    client.extend_expiration(&admin, &1, &1800);
+    assert_eq!(client.get_package(&1).expires_at, opens_at + 5400);
+}
@@ -0,0 +1,355 @@
+#![cfg(test)]
+
+use aid_escrow::{AidEscrow, AidEscrowClient, DistributorRecord, Error, PackageOptions, Role};
+use soroban_sdk::{
+    Address, Env, Map, Symbol, Vec,
+    testutils::{Address as _, Ledger},
+    token::{StellarAssetClient, TokenClient},
+};
+
+fn setup_token(env: &Env, admin: &Address) -> (TokenClient<'static>, StellarAssetClient<'static>) {
+    let token_contract = env.register_stellar_asset_contract_v2(admin.clone());
+    let token_client = TokenClient::new(env, &token_contract.address());
+    let token_admin_client = StellarAssetClient::new(env, &token_contract.address());
+    (token_client, token_admin_client)
+}
+
+/// Helper: set up contract, token, fund, and return the client + token client.
+fn setup_funded(
+    env: &Env,
+    fund_amount: i128,
+) -> (
+    AidEscrowClient<'static>,
+    TokenClient<'static>,
+    Address,
+    StellarAssetClient<'static>,
+) {
+    let admin = Address::generate(env);
+    let token_admin = Address::generate(env);
+    let (token_client, token_admin_client) = setup_token(env, &token_admin);
+
+    let contract_id = env.register(AidEscrow, ());
+    let client = AidEscrowClient::new(env, &contract_id);
+
+    client.init(&admin);
+    token_admin_client.mint(&admin, &fund_amount);
+    client.fund(&token_client.address, &admin, &fund_amount);
+
+    (client, token_client, admin, token_admin_client)
+}
+
+fn unrestricted(env: &Env) -> DistributorRecord {
+    DistributorRecord {
+        allowed_tokens: Vec::new(env),
+        max_amount: 0,
+        max_expires_in: 0,
+        campaign: None,
+        active_until: 0,
+        packages_created: 0,
+        total_committed: 0,
+        last_active: 0,
+    }
+}
+
+#[test]
+fn test_distributor_token_and_amount_scope() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (client, token_client, admin, _) = setup_funded(&env, 10_000);
+    let (other_token, other_admin_client) = setup_token(&env, &admin);
+    other_admin_client.mint(&admin, &10_000);
+    client.fund(&other_token.address, &admin, &10_000);
+
+    let distributor = Address::generate(&env);
+    let recipient = Address::generate(&env);
+    let mut record = unrestricted(&env);
+    record
+        .allowed_tokens
+        .push_back(token_client.address.clone());
+    record.max_amount = 500;
+    client.add_distributor(&distributor, &record);
+    assert_eq!(client.get_distributor(&distributor), Some(record));
+
+    let result =
+        client.try_create_package(&distributor, &1, &recipient, &100, &other_token.address, &0);
+    assert_eq!(result, Err(Ok(Error::DistributorScopeViolation)));
+    let result = client.try_create_package(
+        &distributor,
+        &1,
+        &recipient,
+        &501,
+        &token_client.address,
+        &0,
+    );
+    assert_eq!(result, Err(Ok(Error::DistributorScopeViolation)));
+    client.create_package(
+        &distributor,
+        &1,
+        &recipient,
+        &500,
+        &token_client.address,
+        &0,
+    );
+
+    // Batches check every package
+    let mut recipients = Vec::new(&env);
+    recipients.push_back(Address::generate(&env));
+    recipients.push_back(Address::generate(&env));
+    let mut amounts = Vec::new(&env);
+    amounts.push_back(200_i128);
+    amounts.push_back(800_i128);
+    let result = client.try_batch_create_packages(
+        &distributor,
+        &recipients,
+        &amounts,
+        &token_client.address,
+        &3600,
+    );
+    assert_eq!(result, Err(Ok(Error::DistributorScopeViolation)));
+
+    // The admin is never scoped
+    client.create_package(&admin, &2, &recipient, &5000, &other_token.address, &0);
+}
+
+#[test]
+fn test_distributor_horizon_and_campaign_scope() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.ledger().set_timestamp(1000);
+
+    let (client, token_client, admin, token_admin_client) = setup_funded(&env, 10_000);
+    let distributor = Address::generate(&env);
+    let recipient = Address::generate(&env);
+    let campaign = Symbol::new(&env, "flood");
+    token_admin_client.mint(&admin, &5000);
+    client.fund_earmarked(&token_client.address, &admin, &5000, &campaign);
+
+    let mut record = unrestricted(&env);
+    record.max_expires_in = 86_400;
+    record.campaign = Some(campaign.clone());
+    client.add_distributor(&distributor, &record);
+
+    let earmarked = PackageOptions {
+        metadata: Map::new(&env),
+        claimable_from: 0,
+        funder: None,
+        earmark: Some(campaign),
+    };
+
+    // Open-ended and too-distant packages are out of scope
+    let result = client.try_create_package_with_options(
+        &distributor,
+        &1,
+        &recipient,
+        &100,
+        &token_client.address,
+        &0,
+        &earmarked,
+    );
+    assert_eq!(result, Err(Ok(Error::DistributorScopeViolation)));
+    let result = client.try_create_package_with_options(
+        &distributor,
+        &1,
+        &recipient,
+        &100,
+        &token_client.address,
+        &(1000 + 86_401),
+        &earmarked,
+    );
+    assert_eq!(result, Err(Ok(Error::DistributorScopeViolation)));
+
+    // As in the config check, the horizon is measured from when the claim window opens
+    let opens_at = 1000 + 10 * 86_400;
+    let staged = PackageOptions {
+        claimable_from: opens_at,
+        ..earmarked.clone()
+    };
+    let result = client.try_create_package_with_options(
+        &distributor,
+        &90,
+        &recipient,
+        &100,
+        &token_client.address,
+        &(opens_at + 86_401),
+        &staged,
+    );
+    assert_eq!(result, Err(Ok(Error::DistributorScopeViolation)));
+    client.create_package_with_options(
+        &distributor,
+        &90,
+        &recipient,
+        &100,
+        &token_client.address,
+        &(opens_at + 86_400),
+        &staged,
+    );
+
+    // Packages outside the campaign earmark are out of scope
+    let result = client.try_create_package(
+        &distributor,
+        &1,
+        &recipient,
+        &100,
+        &token_client.address,
+        &5000,
+    );
+    assert_eq!(result, Err(Ok(Error::DistributorScopeViolation)));
+
+    client.create_package_with_options(
+        &distributor,
+        &1,
+        &recipient,
+        &100,
+        &token_client.address,
+        &5000,
+        &earmarked,
+    );
+    assert_eq!(client.get_package(&1).amount, 100);
+}
+
+#[test]
+fn test_distributor_active_until() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.ledger().set_timestamp(1000);
+
+    let (client, token_client, _, _) = setup_funded(&env, 10_000);
+    let distributor = Address::generate(&env);
+    let recipient = Address::generate(&env);
+
+    let mut record = unrestricted(&env);
+    record.active_until = 2000;
+    client.add_distributor(&distributor, &record);
+    client.create_package(
+        &distributor,
+        &1,
+        &recipient,
+        &100,
+        &token_client.address,
+        &0,
+    );
+
+    env.ledger().set_timestamp(2001);
+    let result = client.try_create_package(
+        &distributor,
+        &2,
+        &recipient,
+        &100,
+        &token_client.address,
+        &0,
+    );
+    assert_eq!(result, Err(Ok(Error::NotAuthorized)));
+
+    // Re-adding with a new scope restores access
+    record.active_until = 0;
+    client.add_distributor(&distributor, &record);
+    client.create_package(
+        &distributor,
+        &2,
+        &recipient,
+        &100,
+        &token_client.address,
+        &0,
+    );
+
+    client.remove_distributor(&distributor);
+    assert_eq!(client.get_distributor(&distributor), None);
+}
+
+#[test]
+fn test_distributor_stats() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.ledger().set_timestamp(1000);
+
+    let (client, token_client, admin, _) = setup_funded(&env, 10_000);
+    let distributor = Address::generate(&env);
+    let recipient = Address::generate(&env);
+    client.add_distributor(&distributor, &unrestricted(&env));
+
+    client.create_package(
+        &distributor,
+        &1,
+        &recipient,
+        &300,
+        &token_client.address,
+        &0,
+    );
+    env.ledger().set_timestamp(2000);
+    let mut recipients = Vec::new(&env);
+    recipients.push_back(Address::generate(&env));
+    recipients.push_back(Address::generate(&env));
+    let mut amounts = Vec::new(&env);
+    amounts.push_back(200_i128);
+    amounts.push_back(100_i128);
+    client.batch_create_packages(
+        &distributor,
+        &recipients,
+        &amounts,
+        &token_client.address,
+        &3600,
+    );
+
+    // Admin-created packages are not attributed to the distributor
+    client.create_package(&admin, &50, &recipient, &1000, &token_client.address, &0);
+
+    let record = client.get_distributor(&distributor).unwrap();
+    assert_eq!(record.packages_created, 3);
+    assert_eq!(record.total_committed, 600);
+    assert_eq!(record.last_active, 2000);
+
+    // Updating the scope keeps the stats, whatever the caller passes
+    let mut scoped = unrestricted(&env);
+    scoped.max_amount = 100;
+    scoped.packages_created = 99;
+    client.add_distributor(&distributor, &scoped);
+    let record = client.get_distributor(&distributor).unwrap();
+    assert_eq!(record.max_amount, 100);
+    assert_eq!(record.packages_created, 3);
+}
+
+#[test]
+fn test_list_distributors_paginates() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (client, _, _, _) = setup_funded(&env, 10_000);
+    for _ in 0..5 {
+        client.add_distributor(&Address::generate(&env), &unrestricted(&env));
+    }
+
+    let first = client.list_distributors(&0, &2);
+    let second = client.list_distributors(&2, &2);
+    let last = client.list_distributors(&4, &2);
+    assert_eq!(first.len(), 2);
+    assert_eq!(second.len(), 2);
+    assert_eq!(last.len(), 1);
+    assert!(client.list_distributors(&5, &2).is_empty());
+
+    // Pages do not overlap and cover every distributor
+    let mut seen = Vec::new(&env);
+    for (addr, record) in first.iter().chain(second.iter()).chain(last.iter()) {
+        assert!(!seen.contains(&addr));
+        assert_eq!(record, unrestricted(&env));
+        seen.push_back(addr);
+    }
+    assert_eq!(seen.len(), 5);
+
+    // Removing one moves the last distributor into its place
+    let removed = seen.get(0).unwrap();
+    client.remove_distributor(&removed);
+    let rest = client.list_distributors(&0, &10);
+    assert_eq!(rest.len(), 4);
+    assert_eq!(rest.get(0).unwrap().0, seen.get(4).unwrap());
+    for (addr, _) in rest.iter() {
+        assert!(addr != removed);
+        assert!(seen.contains(&addr));
+    }
+    assert!(!client.has_role(&Role::Distributor, &removed));
+    assert_eq!(client.get_role_members(&Role::Distributor).len(), 4);
+
+    // Removing the last one needs no move
+    client.remove_distributor(&rest.get(3).unwrap().0);
+    assert_eq!(client.list_distributors(&0, &10).len(), 3);
+}
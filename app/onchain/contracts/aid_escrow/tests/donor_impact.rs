@@ -0,0 +1,326 @@
+#![cfg(test)]
+
+use aid_escrow::{AidEscrow, AidEscrowClient, DonorImpact, FundingShare, PackageOptions};
+use soroban_sdk::{
+    Address, Env, Map, Symbol, Vec,
+    testutils::{Address as _, Ledger},
+    token::{StellarAssetClient, TokenClient},
+};
+
+fn setup_token(env: &Env, admin: &Address) -> (TokenClient<'static>, StellarAssetClient<'static>) {
+    let token_contract = env.register_stellar_asset_contract_v2(admin.clone());
+    let token_client = TokenClient::new(env, &token_contract.address());
+    let token_admin_client = StellarAssetClient::new(env, &token_contract.address());
+    (token_client, token_admin_client)
+}
+
+/// Helper: contract funded by two donors in order (first 3000, then 5000); returns client,
+/// token client, admin and the donors.
+fn setup_donors(
+    env: &Env,
+) -> (
+    AidEscrowClient<'static>,
+    TokenClient<'static>,
+    Address,
+    Address,
+    Address,
+) {
+    let admin = Address::generate(env);
+    let first = Address::generate(env);
+    let second = Address::generate(env);
+    let token_admin = Address::generate(env);
+    let (token_client, token_admin_client) = setup_token(env, &token_admin);
+
+    let contract_id = env.register(AidEscrow, ());
+    let client = AidEscrowClient::new(env, &contract_id);
+
+    client.init(&admin);
+    token_admin_client.mint(&first, &3000);
+    token_admin_client.mint(&second, &5000);
+    client.fund(&token_client.address, &first, &3000);
+    client.fund(&token_client.address, &second, &5000);
+
+    (client, token_client, admin, first, second)
+}
+
+#[test]
+fn test_packages_attributed_fifo() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (client, token_client, admin, first, second) = setup_donors(&env);
+    let recipient = Address::generate(&env);
+
+    client.create_package(&admin, &1, &recipient, &2000, &token_client.address, &0);
+    client.create_package(&admin, &2, &recipient, &2000, &token_client.address, &0);
+
+    // Package 2 uses up the first donor and spills over to the second
+    let shares = client.get_funding_shares(&2);
+    assert_eq!(
+        shares,
+        Vec::from_array(
+            &env,
+            [
+                FundingShare {
+                    funder: first.clone(),
+                    amount: 1000,
+                },
+                FundingShare {
+                    funder: second.clone(),
+                    amount: 1000,
+                },
+            ]
+        )
+    );
+
+    assert_eq!(
+        client.get_donor_impact(&first, &token_client.address),
+        DonorImpact {
+            contributed: 3000,
+            committed: 3000,
+            claimed: 0,
+            refunded: 0,
+            available: 0,
+            packages_funded: 2,
+        }
+    );
+    assert_eq!(
+        client
+            .get_donor_impact(&second, &token_client.address)
+            .available,
+        4000
+    );
+}
+
+#[test]
+fn test_claims_settle_shares_in_order() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.ledger().set_timestamp(1000);
+
+    let (client, token_client, admin, first, second) = setup_donors(&env);
+    let recipient = Address::generate(&env);
+
+    client.create_package(&admin, &1, &recipient, &2000, &token_client.address, &0);
+    client.create_stream_package(
+        &admin,
+        &2,
+        &recipient,
+        &2000,
+        &token_client.address,
+        &1000,
+        &2000,
+    );
+
+    // Half of the stream accrues: the first donor's 1000 share is paid out first
+    env.ledger().set_timestamp(1500);
+    client.claim(&2);
+    let first_impact = client.get_donor_impact(&first, &token_client.address);
+    assert_eq!(first_impact.claimed, 1000);
+    assert_eq!(first_impact.committed, 2000);
+    let second_impact = client.get_donor_impact(&second, &token_client.address);
+    assert_eq!(second_impact.claimed, 0);
+    assert_eq!(second_impact.committed, 1000);
+
+    // Stopping the stream keeps the second donor's share reserved until it is refunded
+    client.stop_stream(&2);
+    let second_impact = client.get_donor_impact(&second, &token_client.address);
+    assert_eq!(second_impact.committed, 1000);
+    assert_eq!(second_impact.available, 4000);
+
+    client.refund(&2);
+    let second_impact = client.get_donor_impact(&second, &token_client.address);
+    assert_eq!(second_impact.committed, 0);
+    assert_eq!(second_impact.refunded, 1000);
+    assert_eq!(second_impact.available, 4000);
+    assert_eq!(token_client.balance(&second), 1000);
+}
+
+#[test]
+fn test_refund_split_between_donors() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.ledger().set_timestamp(1000);
+
+    let (client, token_client, admin, first, second) = setup_donors(&env);
+    let recipient = Address::generate(&env);
+
+    client.create_package(&admin, &1, &recipient, &4000, &token_client.address, &2000);
+    env.ledger().set_timestamp(2001);
+    client.refund(&1);
+
+    assert_eq!(token_client.balance(&first), 3000);
+    assert_eq!(token_client.balance(&second), 1000);
+    assert_eq!(token_client.balance(&admin), 0);
+
+    let impact = client.get_donor_impact(&second, &token_client.address);
+    assert_eq!(impact.refunded, 1000);
+    assert_eq!(impact.committed, 0);
+    assert_eq!(impact.available, 4000);
+}
+
+#[test]
+fn test_revoked_shares_not_reused_before_refund() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (client, token_client, admin, first, second) = setup_donors(&env);
+    let recipient = Address::generate(&env);
+
+    client.create_package(&admin, &1, &recipient, &3000, &token_client.address, &0);
+    client.revoke(&admin, &1);
+
+    // The revoked package still holds the first donor's money, so the next package
+    // is attributed to the second donor
+    client.create_package(&admin, &2, &recipient, &3000, &token_client.address, &0);
+    assert_eq!(
+        client.get_funding_shares(&2),
+        Vec::from_array(
+            &env,
+            [FundingShare {
+                funder: second.clone(),
+                amount: 3000,
+            }]
+        )
+    );
+
+    client.refund(&1);
+    assert_eq!(token_client.balance(&first), 3000);
+    assert_eq!(
+        client.get_donor_impact(&first, &token_client.address),
+        DonorImpact {
+            contributed: 3000,
+            committed: 0,
+            claimed: 0,
+            refunded: 3000,
+            available: 0,
+            packages_funded: 1,
+        }
+    );
+    assert_eq!(
+        client
+            .get_donor_impact(&second, &token_client.address)
+            .committed,
+        3000
+    );
+
+    client.claim(&2);
+    assert_eq!(token_client.balance(&recipient), 3000);
+}
+
+#[test]
+fn test_dust_funders_cannot_block_creation() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (client, token_client, admin, first, _) = setup_donors(&env);
+    let token_admin = StellarAssetClient::new(&env, &token_client.address);
+    let recipient = Address::generate(&env);
+
+    // First package uses up the first donor, so the queue head moves past them
+    client.create_package(&admin, &1, &recipient, &3000, &token_client.address, &0);
+    client.create_package(&admin, &2, &recipient, &5000, &token_client.address, &0);
+
+    let mut dust = Vec::new(&env);
+    for _ in 0..40 {
+        let donor = Address::generate(&env);
+        token_admin.mint(&donor, &1);
+        client.fund(&token_client.address, &donor, &1);
+        dust.push_back(donor);
+    }
+
+    // Each package visits a bounded number of funders; the rest stays unattributed
+    client.create_package(&admin, &3, &recipient, &20, &token_client.address, &0);
+    assert_eq!(client.get_funding_shares(&3).len(), 16);
+    client.create_package(&admin, &4, &recipient, &20, &token_client.address, &0);
+    assert_eq!(client.get_funding_shares(&4).len(), 16);
+    assert_eq!(
+        client.get_funding_shares(&4).get(0).unwrap().funder,
+        dust.get(16).unwrap()
+    );
+
+    // A returning donor rejoins at the back of the queue
+    token_admin.mint(&first, &500);
+    client.fund(&token_client.address, &first, &500);
+    token_admin.mint(&admin, &600);
+    client.fund(&token_client.address, &admin, &600);
+    client.create_package(&admin, &5, &recipient, &508, &token_client.address, &0);
+    let shares = client.get_funding_shares(&5);
+    assert_eq!(shares.len(), 9);
+    assert_eq!(
+        shares.get(8).unwrap(),
+        FundingShare {
+            funder: first.clone(),
+            amount: 500,
+        }
+    );
+}
+
+#[test]
+fn test_earmarked_gifts_attributed_to_earmark_donors() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (client, token_client, admin, first, second) = setup_donors(&env);
+    let giver = Address::generate(&env);
+    let recipient = Address::generate(&env);
+    let water = Symbol::new(&env, "water");
+    StellarAssetClient::new(&env, &token_client.address).mint(&giver, &1000);
+    client.fund_earmarked(&token_client.address, &giver, &1000, &water);
+
+    // Earmarked money is counted for the giver but not offered to general packages
+    assert_eq!(
+        client
+            .get_donor_impact(&giver, &token_client.address)
+            .available,
+        1000
+    );
+    assert_eq!(client.get_funder_balance(&giver, &token_client.address), 0);
+
+    let options = PackageOptions {
+        metadata: Map::new(&env),
+        claimable_from: 0,
+        funder: None,
+        earmark: Some(water),
+    };
+    client.create_package_with_options(
+        &admin,
+        &1,
+        &recipient,
+        &600,
+        &token_client.address,
+        &0,
+        &options,
+    );
+    assert_eq!(
+        client.get_funding_shares(&1),
+        Vec::from_array(
+            &env,
+            [FundingShare {
+                funder: giver.clone(),
+                amount: 600,
+            }]
+        )
+    );
+    client.claim(&1);
+
+    assert_eq!(
+        client.get_donor_impact(&giver, &token_client.address),
+        DonorImpact {
+            contributed: 1000,
+            committed: 0,
+            claimed: 600,
+            refunded: 0,
+            available: 400,
+            packages_funded: 1,
+        }
+    );
+    for donor in [&first, &second] {
+        assert_eq!(
+            client
+                .get_donor_impact(donor, &token_client.address)
+                .packages_funded,
+            0
+        );
+    }
+}
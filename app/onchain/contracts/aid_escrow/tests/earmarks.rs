@@ -0,0 +1,199 @@
+#![cfg(test)]
+
+use aid_escrow::{AidEscrow, AidEscrowClient, Earmark, Error, PackageOptions};
+use soroban_sdk::{
+    Address, Env, Map, Symbol, Vec,
+    testutils::Address as _,
+    token::{StellarAssetClient, TokenClient},
+};
+
+fn setup_token(env: &Env, admin: &Address) -> (TokenClient<'static>, StellarAssetClient<'static>) {
+    let token_contract = env.register_stellar_asset_contract_v2(admin.clone());
+    let token_client = TokenClient::new(env, &token_contract.address());
+    let token_admin_client = StellarAssetClient::new(env, &token_contract.address());
+    (token_client, token_admin_client)
+}
+
+/// Helper: 2000 in the general pool and 5000 earmarked for "water"; returns client,
+/// token client, admin and the earmark's donor.
+fn setup_earmarked(
+    env: &Env,
+) -> (
+    AidEscrowClient<'static>,
+    TokenClient<'static>,
+    Address,
+    Address,
+) {
+    let admin = Address::generate(env);
+    let donor = Address::generate(env);
+    let token_admin = Address::generate(env);
+    let (token_client, token_admin_client) = setup_token(env, &token_admin);
+
+    let contract_id = env.register(AidEscrow, ());
+    let client = AidEscrowClient::new(env, &contract_id);
+
+    client.init(&admin);
+    token_admin_client.mint(&admin, &2000);
+    token_admin_client.mint(&donor, &5000);
+    client.fund(&token_client.address, &admin, &2000);
+    client.fund_earmarked(
+        &token_client.address,
+        &donor,
+        &5000,
+        &Symbol::new(env, "water"),
+    );
+
+    (client, token_client, admin, donor)
+}
+
+fn earmarked(env: &Env, tag: &str) -> PackageOptions {
+    PackageOptions {
+        metadata: Map::new(env),
+        claimable_from: 0,
+        funder: None,
+        earmark: Some(Symbol::new(env, tag)),
+    }
+}
+
+#[test]
+fn test_earmarked_package_draws_on_earmark() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (client, token_client, admin, _) = setup_earmarked(&env);
+    let recipient = Address::generate(&env);
+    let water = Symbol::new(&env, "water");
+
+    client.create_package_with_options(
+        &admin,
+        &1,
+        &recipient,
+        &4000,
+        &token_client.address,
+        &0,
+        &earmarked(&env, "water"),
+    );
+    assert_eq!(
+        client.get_earmark(&water, &token_client.address),
+        Earmark {
+            balance: 5000,
+            locked: 4000,
+        }
+    );
+
+    // Only 1000 of the earmark is left, even though the contract holds 3000 unlocked
+    let result = client.try_create_package_with_options(
+        &admin,
+        &2,
+        &recipient,
+        &1500,
+        &token_client.address,
+        &0,
+        &earmarked(&env, "water"),
+    );
+    assert_eq!(result, Err(Ok(Error::InsufficientFunds)));
+
+    client.claim(&1);
+    assert_eq!(
+        client.get_earmark(&water, &token_client.address),
+        Earmark {
+            balance: 1000,
+            locked: 0,
+        }
+    );
+}
+
+#[test]
+fn test_general_pool_cannot_use_earmarked_money() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (client, token_client, admin, _) = setup_earmarked(&env);
+    let recipient = Address::generate(&env);
+
+    let result =
+        client.try_create_package(&admin, &1, &recipient, &2500, &token_client.address, &0);
+    assert_eq!(result, Err(Ok(Error::InsufficientFunds)));
+
+    let mut recipients = Vec::new(&env);
+    recipients.push_back(recipient.clone());
+    recipients.push_back(recipient.clone());
+    let mut amounts = Vec::new(&env);
+    amounts.push_back(1500_i128);
+    amounts.push_back(1000_i128);
+    let result =
+        client.try_batch_create_packages(&admin, &recipients, &amounts, &token_client.address, &0);
+    assert_eq!(result, Err(Ok(Error::InsufficientFunds)));
+
+    // Nor can it be withdrawn as surplus
+    let result = client.try_withdraw_surplus(&admin, &admin, &2001, &token_client.address);
+    assert_eq!(result, Err(Ok(Error::InsufficientSurplus)));
+    client.withdraw_surplus(&admin, &admin, &2000, &token_client.address);
+
+    // An unknown earmark has nothing to draw on
+    let result = client.try_create_package_with_options(
+        &admin,
+        &2,
+        &recipient,
+        &100,
+        &token_client.address,
+        &0,
+        &earmarked(&env, "food"),
+    );
+    assert_eq!(result, Err(Ok(Error::InsufficientFunds)));
+}
+
+#[test]
+fn test_revoked_earmarked_package_held_for_refund() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (client, token_client, admin, donor) = setup_earmarked(&env);
+    let recipient = Address::generate(&env);
+    let water = Symbol::new(&env, "water");
+
+    client.create_package_with_options(
+        &admin,
+        &1,
+        &recipient,
+        &5000,
+        &token_client.address,
+        &0,
+        &earmarked(&env, "water"),
+    );
+    client.revoke(&admin, &1);
+    // Held for the refund: neither reusable by the earmark nor general surplus
+    assert_eq!(
+        client.get_earmark(&water, &token_client.address).locked,
+        5000
+    );
+    let result = client.try_create_package_with_options(
+        &admin,
+        &2,
+        &recipient,
+        &100,
+        &token_client.address,
+        &0,
+        &earmarked(&env, "water"),
+    );
+    assert_eq!(result, Err(Ok(Error::InsufficientFunds)));
+    let result = client.try_withdraw_surplus(&admin, &admin, &2001, &token_client.address);
+    assert_eq!(result, Err(Ok(Error::InsufficientSurplus)));
+
+    // The refund goes back to the earmark's donor, not the admin
+    client.refund(&1);
+    assert_eq!(
+        client.get_earmark(&water, &token_client.address),
+        Earmark {
+            balance: 0,
+            locked: 0,
+        }
+    );
+    assert_eq!(token_client.balance(&donor), 5000);
+    let impact = client.get_donor_impact(&donor, &token_client.address);
+    assert_eq!(impact.contributed, 5000);
+    assert_eq!(impact.refunded, 5000);
+    assert_eq!(impact.packages_funded, 1);
+    client.withdraw_surplus(&admin, &admin, &2000, &token_client.address);
+    assert_eq!(token_client.balance(&admin), 2000);
+}
@@ -2,9 +2,12 @@
 
 #![cfg(test)]
 
-use aid_escrow::{AidEscrow, AidEscrowClient};
+use aid_escrow::{
+    AdminAction, AidEscrow, AidEscrowClient, DistributorRecord, PackageOptions, PauseScope, Role,
+    Tranche,
+};
 use soroban_sdk::{
-    Address, Env, Symbol, TryFromVal, Val, Vec,
+    Address, Env, Map, Symbol, TryFromVal, Val, Vec,
     testutils::{Address as _, Events, Ledger},
     token::{StellarAssetClient, TokenClient},
 };
@@ -167,6 +170,116 @@ fn test_package_claimed_event() {
     assert_field_exists(&env, &data, "timestamp");
 }
 
+#[test]
+fn test_tranche_claimed_event() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let recipient = Address::generate(&env);
+    let (token_client, token_admin_client) = setup_token(&env, &admin);
+
+    let contract_id = env.register(AidEscrow, ());
+    let client = AidEscrowClient::new(&env, &contract_id);
+    client.init(&admin);
+    token_admin_client.mint(&admin, &10_000);
+    client.fund(&token_client.address, &admin, &5000);
+
+    let now = env.ledger().timestamp();
+    let mut tranches = Vec::new(&env);
+    tranches.push_back(Tranche {
+        amount: 400,
+        unlocks_at: now,
+    });
+    tranches.push_back(Tranche {
+        amount: 600,
+        unlocks_at: now + 86400,
+    });
+    client.create_tranche_package(
+        &admin,
+        &0u64,
+        &recipient,
+        &token_client.address,
+        &tranches,
+        &0,
+    );
+    client.claim(&0u64);
+
+    let data = last_event_data(&env, &contract_id, "tranche_claimed");
+    assert_eq!(data_u64(&env, &data, "package_id"), 0);
+    assert_eq!(data_address(&env, &data, "recipient"), recipient);
+    assert_eq!(data_i128(&env, &data, "amount"), 400);
+    assert_eq!(data_i128(&env, &data, "claimed_total"), 400);
+    assert_eq!(data_i128(&env, &data, "remaining"), 600);
+    assert_eq!(data_address(&env, &data, "actor"), recipient);
+    assert_field_exists(&env, &data, "timestamp");
+
+    let data = last_event_data(&env, &contract_id, "package_claimed");
+    assert_eq!(data_i128(&env, &data, "amount"), 400);
+}
+
+#[test]
+fn test_guardian_claim_event_records_guardian_as_actor() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let recipient = Address::generate(&env);
+    let guardian = Address::generate(&env);
+    let (token_client, token_admin_client) = setup_token(&env, &admin);
+
+    let contract_id = env.register(AidEscrow, ());
+    let client = AidEscrowClient::new(&env, &contract_id);
+    client.init(&admin);
+    token_admin_client.mint(&admin, &10_000);
+    client.fund(&token_client.address, &admin, &5000);
+
+    client.set_guardian(&recipient, &guardian, &None);
+    client.create_package(&admin, &0u64, &recipient, &1000, &token_client.address, &0);
+    client.claim_as_guardian(&guardian, &0u64);
+
+    let data = last_event_data(&env, &contract_id, "package_claimed");
+    assert_eq!(data_u64(&env, &data, "package_id"), 0);
+    assert_eq!(data_address(&env, &data, "recipient"), recipient);
+    assert_eq!(data_i128(&env, &data, "amount"), 1000);
+    assert_eq!(data_address(&env, &data, "actor"), guardian);
+}
+
+#[test]
+fn test_recipient_reassigned_event() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let old_recipient = Address::generate(&env);
+    let new_recipient = Address::generate(&env);
+    let (token_client, token_admin_client) = setup_token(&env, &admin);
+
+    let contract_id = env.register(AidEscrow, ());
+    let client = AidEscrowClient::new(&env, &contract_id);
+    client.init(&admin);
+    token_admin_client.mint(&admin, &10_000);
+    client.fund(&token_client.address, &admin, &5000);
+
+    client.create_package(
+        &admin,
+        &0u64,
+        &old_recipient,
+        &1000,
+        &token_client.address,
+        &0,
+    );
+    client.request_reassignment(&admin, &0u64, &new_recipient);
+    client.approve_reassignment(&0u64);
+
+    let data = last_event_data(&env, &contract_id, "recipient_reassigned");
+    assert_eq!(data_u64(&env, &data, "package_id"), 0);
+    assert_eq!(data_address(&env, &data, "old_recipient"), old_recipient);
+    assert_eq!(data_address(&env, &data, "new_recipient"), new_recipient);
+    assert_eq!(data_address(&env, &data, "actor"), admin);
+    assert_field_exists(&env, &data, "timestamp");
+}
+
 #[test]
 fn test_package_disbursed_event() {
     let env = Env::default();
@@ -225,7 +338,7 @@ fn test_package_revoked_event() {
         &token_client.address,
         &expires_at,
     );
-    client.revoke(&0u64);
+    client.revoke(&admin, &0u64);
 
     let data = last_event_data(&env, &contract_id, "package_revoked");
     assert_eq!(data_u64(&env, &data, "package_id"), 0);
@@ -270,3 +383,418 @@ fn test_package_refunded_event() {
     assert_eq!(data_address(&env, &data, "actor"), admin);
     assert_field_exists(&env, &data, "timestamp");
 }
+
+#[test]
+fn test_package_expired_event() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let recipient = Address::generate(&env);
+    let (token_client, token_admin_client) = setup_token(&env, &admin);
+
+    let contract_id = env.register(AidEscrow, ());
+    let client = AidEscrowClient::new(&env, &contract_id);
+    client.init(&admin);
+    token_admin_client.mint(&admin, &10_000);
+    client.fund(&token_client.address, &admin, &5000);
+
+    let expires_at = env.ledger().timestamp() + 1;
+    client.create_package(
+        &admin,
+        &0u64,
+        &recipient,
+        &1000,
+        &token_client.address,
+        &expires_at,
+    );
+
+    env.ledger().set_timestamp(env.ledger().timestamp() + 2);
+    let mut ids = Vec::new(&env);
+    ids.push_back(0u64);
+    client.expire_packages(&ids);
+
+    let data = last_event_data(&env, &contract_id, "package_expired");
+    assert_eq!(data_u64(&env, &data, "package_id"), 0);
+    assert_eq!(data_address(&env, &data, "recipient"), recipient);
+    assert_eq!(data_i128(&env, &data, "amount"), 1000);
+    assert_field_exists(&env, &data, "timestamp");
+}
+
+#[test]
+fn test_batch_disbursed_event() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let (token_client, token_admin_client) = setup_token(&env, &admin);
+
+    let contract_id = env.register(AidEscrow, ());
+    let client = AidEscrowClient::new(&env, &contract_id);
+    client.init(&admin);
+    token_admin_client.mint(&admin, &10_000);
+    client.fund(&token_client.address, &admin, &5000);
+
+    let mut ids = Vec::new(&env);
+    for id in 0..3u64 {
+        let recipient = Address::generate(&env);
+        client.create_package(&admin, &id, &recipient, &1000, &token_client.address, &0);
+        ids.push_back(id);
+    }
+    client.batch_disburse(&ids, &true);
+
+    let data = last_event_data(&env, &contract_id, "batch_disbursed_event");
+    assert_eq!(data_address(&env, &data, "admin"), admin);
+    assert_eq!(data_i128(&env, &data, "total_amount"), 3000);
+    assert_field_exists(&env, &data, "ids");
+}
+
+#[test]
+fn test_donor_refunded_event() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let donor = Address::generate(&env);
+    let recipient = Address::generate(&env);
+    let (token_client, token_admin_client) = setup_token(&env, &admin);
+
+    let contract_id = env.register(AidEscrow, ());
+    let client = AidEscrowClient::new(&env, &contract_id);
+    client.init(&admin);
+    token_admin_client.mint(&donor, &5000);
+    client.fund(&token_client.address, &donor, &5000);
+
+    client.create_package_with_options(
+        &admin,
+        &0u64,
+        &recipient,
+        &1000,
+        &token_client.address,
+        &0,
+        &PackageOptions {
+            metadata: Map::new(&env),
+            claimable_from: 0,
+            funder: Some(donor.clone()),
+            earmark: None,
+        },
+    );
+    client.revoke(&admin, &0u64);
+    client.refund(&0u64);
+
+    let data = last_event_data(&env, &contract_id, "donor_refunded");
+    assert_eq!(data_address(&env, &data, "funder"), donor);
+    assert_eq!(data_address(&env, &data, "token"), token_client.address);
+    assert_eq!(data_i128(&env, &data, "amount"), 1000);
+    assert_eq!(data_address(&env, &data, "actor"), admin);
+    assert_field_exists(&env, &data, "package_id");
+    assert_field_exists(&env, &data, "timestamp");
+}
+
+#[test]
+fn test_earmark_funded_event() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let donor = Address::generate(&env);
+    let (token_client, token_admin_client) = setup_token(&env, &admin);
+
+    let contract_id = env.register(AidEscrow, ());
+    let client = AidEscrowClient::new(&env, &contract_id);
+    client.init(&admin);
+    token_admin_client.mint(&donor, &5000);
+    client.fund_earmarked(&token_client.address, &donor, &5000, &sym(&env, "water"));
+
+    let data = last_event_data(&env, &contract_id, "earmark_funded");
+    assert_eq!(data_address(&env, &data, "from"), donor);
+    assert_eq!(data_address(&env, &data, "token"), token_client.address);
+    assert_eq!(data_i128(&env, &data, "amount"), 5000);
+    assert_field_exists(&env, &data, "tag");
+    assert_field_exists(&env, &data, "timestamp");
+}
+
+#[test]
+fn test_donation_matched_event() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let sponsor = Address::generate(&env);
+    let donor = Address::generate(&env);
+    let (token_client, token_admin_client) = setup_token(&env, &admin);
+
+    let contract_id = env.register(AidEscrow, ());
+    let client = AidEscrowClient::new(&env, &contract_id);
+    client.init(&admin);
+    token_admin_client.mint(&sponsor, &5000);
+    token_admin_client.mint(&donor, &1000);
+
+    let deadline = env.ledger().timestamp() + 86400;
+    client.create_matching_pool(&sponsor, &token_client.address, &10_000, &5000, &deadline);
+    client.fund(&token_client.address, &donor, &1000);
+
+    let data = last_event_data(&env, &contract_id, "donation_matched");
+    assert_eq!(data_address(&env, &data, "sponsor"), sponsor);
+    assert_eq!(data_address(&env, &data, "donor"), donor);
+    assert_eq!(data_i128(&env, &data, "amount"), 1000);
+    assert_eq!(data_i128(&env, &data, "matched"), 1000);
+    assert_field_exists(&env, &data, "timestamp");
+}
+
+#[test]
+fn test_allowance_updated_event() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let distributor = Address::generate(&env);
+    let recipient = Address::generate(&env);
+    let (token_client, token_admin_client) = setup_token(&env, &admin);
+
+    let contract_id = env.register(AidEscrow, ());
+    let client = AidEscrowClient::new(&env, &contract_id);
+    client.init(&admin);
+    client.add_distributor(
+        &distributor,
+        &DistributorRecord {
+            allowed_tokens: Vec::new(&env),
+            max_amount: 0,
+            max_expires_in: 0,
+            campaign: None,
+            active_until: 0,
+            packages_created: 0,
+            total_committed: 0,
+            last_active: 0,
+        },
+    );
+    token_admin_client.mint(&admin, &10_000);
+    client.fund(&token_client.address, &admin, &5000);
+
+    client.set_allowance(&distributor, &token_client.address, &4000, &0, &0);
+    client.create_package(
+        &distributor,
+        &0u64,
+        &recipient,
+        &1000,
+        &token_client.address,
+        &0,
+    );
+
+    let data = last_event_data(&env, &contract_id, "allowance_updated");
+    assert_eq!(data_address(&env, &data, "distributor"), distributor);
+    assert_eq!(data_address(&env, &data, "token"), token_client.address);
+    assert_eq!(data_i128(&env, &data, "limit"), 4000);
+    assert_eq!(data_i128(&env, &data, "used"), 1000);
+    assert_field_exists(&env, &data, "period_used");
+    assert_field_exists(&env, &data, "timestamp");
+}
+
+#[test]
+fn test_proposal_events() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let signer_a = Address::generate(&env);
+    let signer_b = Address::generate(&env);
+    let contract_id = env.register(AidEscrow, ());
+    let client = AidEscrowClient::new(&env, &contract_id);
+    client.init(&admin);
+
+    let mut signers = Vec::new(&env);
+    signers.push_back(signer_a.clone());
+    signers.push_back(signer_b.clone());
+    client.set_signers(&signers, &2);
+
+    let disable = AdminAction::SetSigners(Vec::new(&env), 0);
+    let id = client.propose(&signer_a, &disable, &3600);
+    let data = last_event_data(&env, &contract_id, "proposal_created");
+    assert_eq!(data_u64(&env, &data, "proposal_id"), id);
+    assert_eq!(data_address(&env, &data, "proposer"), signer_a);
+    assert_field_exists(&env, &data, "action");
+    assert_field_exists(&env, &data, "expires_at");
+
+    client.approve(&signer_b, &id);
+    let data = last_event_data(&env, &contract_id, "proposal_approved");
+    assert_eq!(data_address(&env, &data, "signer"), signer_b);
+    assert_field_exists(&env, &data, "approvals");
+
+    client.execute_proposal(&signer_b, &id);
+    let data = last_event_data(&env, &contract_id, "proposal_executed");
+    assert_eq!(data_u64(&env, &data, "proposal_id"), id);
+    assert_eq!(data_address(&env, &data, "actor"), signer_b);
+    assert_field_exists(&env, &data, "timestamp");
+}
+
+#[test]
+fn test_admin_transfer_events() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let partner = Address::generate(&env);
+    let contract_id = env.register(AidEscrow, ());
+    let client = AidEscrowClient::new(&env, &contract_id);
+    client.init(&admin);
+
+    client.propose_admin(&partner, &0);
+    let data = last_event_data(&env, &contract_id, "admin_transfer_proposed");
+    assert_eq!(data_address(&env, &data, "admin"), admin);
+    assert_eq!(data_address(&env, &data, "new_admin"), partner);
+    assert_field_exists(&env, &data, "accept_after");
+
+    client.accept_admin();
+    let data = last_event_data(&env, &contract_id, "admin_transferred");
+    assert_eq!(data_address(&env, &data, "previous_admin"), admin);
+    assert_eq!(data_address(&env, &data, "new_admin"), partner);
+
+    client.renounce_admin();
+    let data = last_event_data(&env, &contract_id, "admin_renounced");
+    assert_eq!(data_address(&env, &data, "admin"), partner);
+    assert_field_exists(&env, &data, "timestamp");
+}
+
+#[test]
+fn test_role_granted_event() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let pauser = Address::generate(&env);
+    let contract_id = env.register(AidEscrow, ());
+    let client = AidEscrowClient::new(&env, &contract_id);
+    client.init(&admin);
+
+    client.grant_role(&Role::Pauser, &pauser);
+    let data = last_event_data(&env, &contract_id, "role_granted");
+    assert_eq!(data_address(&env, &data, "account"), pauser);
+    assert_eq!(data_address(&env, &data, "admin"), admin);
+    assert_field_exists(&env, &data, "role");
+    assert_field_exists(&env, &data, "timestamp");
+
+    client.revoke_role(&Role::Pauser, &pauser);
+    let data = last_event_data(&env, &contract_id, "role_revoked");
+    assert_eq!(data_address(&env, &data, "account"), pauser);
+}
+
+#[test]
+fn test_action_queued_event() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let contract_id = env.register(AidEscrow, ());
+    let client = AidEscrowClient::new(&env, &contract_id);
+    client.init(&admin);
+    client.set_timelock(&3600, &0);
+
+    let action = AdminAction::SetTokenAllowed(Address::generate(&env), true);
+    let id = client.queue_action(&admin, &action);
+    let data = last_event_data(&env, &contract_id, "action_queued");
+    assert_eq!(data_u64(&env, &data, "queue_id"), id);
+    assert_eq!(data_address(&env, &data, "queued_by"), admin);
+    assert_eq!(data_u64(&env, &data, "eta"), 3600);
+    assert_field_exists(&env, &data, "action");
+
+    env.ledger().set_timestamp(3600);
+    client.execute_queued(&admin, &id);
+    let data = last_event_data(&env, &contract_id, "queued_action_executed");
+    assert_eq!(data_u64(&env, &data, "queue_id"), id);
+    assert_eq!(data_address(&env, &data, "actor"), admin);
+}
+
+#[test]
+fn test_queued_withdrawal_records_queuer_as_actor() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let treasurer = Address::generate(&env);
+    let token_admin = Address::generate(&env);
+    let (token_client, token_admin_client) = setup_token(&env, &token_admin);
+    let contract_id = env.register(AidEscrow, ());
+    let client = AidEscrowClient::new(&env, &contract_id);
+    client.init(&admin);
+    client.grant_role(&Role::Treasurer, &treasurer);
+    client.set_timelock(&3600, &0);
+    token_admin_client.mint(&treasurer, &1000);
+    client.fund(&token_client.address, &treasurer, &1000);
+
+    let action =
+        AdminAction::WithdrawSurplus(treasurer.clone(), 1000, token_client.address.clone());
+    let id = client.queue_action(&treasurer, &action);
+    env.ledger().set_timestamp(3600);
+    client.execute_queued(&admin, &id);
+    let data = last_event_data(&env, &contract_id, "donor_refunded");
+    assert_eq!(data_address(&env, &data, "actor"), treasurer);
+}
+
+#[test]
+fn test_distributor_lifecycle_events() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.ledger().set_timestamp(1234);
+
+    let admin = Address::generate(&env);
+    let distributor = Address::generate(&env);
+    let contract_id = env.register(AidEscrow, ());
+    let client = AidEscrowClient::new(&env, &contract_id);
+    client.init(&admin);
+
+    client.grant_role(&Role::Distributor, &distributor);
+    let data = last_event_data(&env, &contract_id, "distributor_added");
+    assert_eq!(data_address(&env, &data, "distributor"), distributor);
+    assert_eq!(data_address(&env, &data, "actor"), admin);
+    assert_eq!(data_u64(&env, &data, "timestamp"), 1234);
+
+    client.remove_distributor(&distributor);
+    let data = last_event_data(&env, &contract_id, "distributor_removed");
+    assert_eq!(data_address(&env, &data, "distributor"), distributor);
+    assert_eq!(data_address(&env, &data, "actor"), admin);
+    assert_field_exists(&env, &data, "timestamp");
+}
+
+#[test]
+fn test_contract_paused_event() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let (token_client, _) = setup_token(&env, &admin);
+    let contract_id = env.register(AidEscrow, ());
+    let client = AidEscrowClient::new(&env, &contract_id);
+    client.init(&admin);
+
+    let token = Some(token_client.address.clone());
+    let reason = Some(soroban_sdk::String::from_str(&env, "audit"));
+    client.pause(&admin, &PauseScope::Claims, &token, &reason, &5000);
+    let data = last_event_data(&env, &contract_id, "contract_paused_event");
+    assert_eq!(data_address(&env, &data, "admin"), admin);
+    assert_eq!(data_address(&env, &data, "token"), token_client.address);
+    assert_eq!(data_u64(&env, &data, "expires_at"), 5000);
+    assert_field_exists(&env, &data, "scope");
+    assert_field_exists(&env, &data, "reason");
+
+    client.unpause(&admin, &PauseScope::Claims, &token);
+    let data = last_event_data(&env, &contract_id, "contract_unpaused_event");
+    assert_eq!(data_address(&env, &data, "admin"), admin);
+    assert_field_exists(&env, &data, "scope");
+}
+
+#[test]
+fn test_ttl_config_updated_event() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let contract_id = env.register(AidEscrow, ());
+    let client = AidEscrowClient::new(&env, &contract_id);
+    client.init(&admin);
+
+    client.set_ttl_config(&1000, &5000);
+    let data = last_event_data(&env, &contract_id, "ttl_config_updated");
+    assert_field_exists(&env, &data, "threshold");
+    assert_field_exists(&env, &data, "extend_to");
+    assert_field_exists(&env, &data, "timestamp");
+}
@@ -0,0 +1,150 @@
+#![cfg(test)]
+
+use aid_escrow::{AidEscrow, AidEscrowClient, Error, PackageStatus, Tranche};
+use soroban_sdk::{
+    Address, Env, Vec,
+    testutils::{Address as _, Ledger},
+    token::{StellarAssetClient, TokenClient},
+};
+
+fn setup_token(env: &Env, admin: &Address) -> (TokenClient<'static>, StellarAssetClient<'static>) {
+    let token_contract = env.register_stellar_asset_contract_v2(admin.clone());
+    let token_client = TokenClient::new(env, &token_contract.address());
+    let token_admin_client = StellarAssetClient::new(env, &token_contract.address());
+    (token_client, token_admin_client)
+}
+
+/// Helper: set up contract, token, fund, and return the client + token client.
+fn setup_funded(
+    env: &Env,
+    fund_amount: i128,
+) -> (
+    AidEscrowClient<'static>,
+    TokenClient<'static>,
+    Address,
+    Address,
+) {
+    let admin = Address::generate(env);
+    let token_admin = Address::generate(env);
+    let (token_client, token_admin_client) = setup_token(env, &token_admin);
+
+    let contract_id = env.register(AidEscrow, ());
+    let client = AidEscrowClient::new(env, &contract_id);
+
+    client.init(&admin);
+    token_admin_client.mint(&admin, &fund_amount);
+    client.fund(&token_client.address, &admin, &fund_amount);
+
+    (client, token_client, admin, contract_id)
+}
+
+#[test]
+fn test_expire_packages_unlocks_overdue_funds() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.ledger().set_timestamp(1000);
+
+    let (client, token_client, admin, _) = setup_funded(&env, 10_000);
+    let recipient = Address::generate(&env);
+
+    client.create_package(&admin, &1, &recipient, &1000, &token_client.address, &2000);
+    client.create_package(&admin, &2, &recipient, &2000, &token_client.address, &2000);
+    client.create_package(&admin, &3, &recipient, &3000, &token_client.address, &9000);
+    client.create_package(&admin, &4, &recipient, &500, &token_client.address, &0);
+    assert_eq!(
+        client.get_aggregates(&token_client.address).total_committed,
+        6500
+    );
+
+    env.ledger().set_timestamp(2001);
+
+    // Anyone can sweep; only overdue packages are touched and unknown ids are skipped
+    let mut ids = Vec::new(&env);
+    for id in [1u64, 2, 3, 4, 99] {
+        ids.push_back(id);
+    }
+    assert_eq!(client.expire_packages(&ids), 2);
+
+    assert_eq!(client.get_package(&1).status, PackageStatus::Expired);
+    assert_eq!(client.get_package(&2).status, PackageStatus::Expired);
+    assert_eq!(client.get_package(&3).status, PackageStatus::Created);
+    assert_eq!(client.get_package(&4).status, PackageStatus::Created);
+
+    // The expired 3000 is held for refund, so only the untouched 3500 can back new packages
+    let result =
+        client.try_create_package(&admin, &5, &recipient, &3501, &token_client.address, &0);
+    assert_eq!(result, Err(Ok(Error::InsufficientFunds)));
+    client.create_package(&admin, &5, &recipient, &3500, &token_client.address, &0);
+
+    // Sweeping again is a no-op
+    assert_eq!(client.expire_packages(&ids), 0);
+}
+
+#[test]
+fn test_refund_after_expire_does_not_unlock_twice() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.ledger().set_timestamp(1000);
+
+    let (client, token_client, admin, _) = setup_funded(&env, 10_000);
+    let recipient = Address::generate(&env);
+
+    client.create_package(&admin, &1, &recipient, &1000, &token_client.address, &2000);
+    client.create_package(&admin, &2, &recipient, &4000, &token_client.address, &0);
+
+    env.ledger().set_timestamp(2001);
+    let mut ids = Vec::new(&env);
+    ids.push_back(1u64);
+    client.expire_packages(&ids);
+
+    assert_eq!(client.try_claim(&1), Err(Ok(Error::PackageNotActive)));
+
+    client.refund(&1);
+    assert_eq!(client.get_package(&1).status, PackageStatus::Refunded);
+    // Package 2 stays fully locked: surplus is 10_000 - 1000 refunded - 4000 locked
+    assert_eq!(
+        client.try_withdraw_surplus(&admin, &admin, &5001, &token_client.address),
+        Err(Ok(Error::InsufficientSurplus))
+    );
+    client.withdraw_surplus(&admin, &admin, &5000, &token_client.address);
+}
+
+#[test]
+fn test_expire_partly_claimed_tranche_package() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.ledger().set_timestamp(1000);
+
+    let (client, token_client, admin, _) = setup_funded(&env, 10_000);
+    let recipient = Address::generate(&env);
+
+    let mut tranches = Vec::new(&env);
+    tranches.push_back(Tranche {
+        amount: 400,
+        unlocks_at: 1000,
+    });
+    tranches.push_back(Tranche {
+        amount: 600,
+        unlocks_at: 1500,
+    });
+    client.create_tranche_package(
+        &admin,
+        &1,
+        &recipient,
+        &token_client.address,
+        &tranches,
+        &2000,
+    );
+    client.claim(&1);
+
+    env.ledger().set_timestamp(2001);
+    let mut ids = Vec::new(&env);
+    ids.push_back(1u64);
+    assert_eq!(client.expire_packages(&ids), 1);
+
+    // Only the unclaimed 600 is unlocked
+    let aggregates = client.get_aggregates(&token_client.address);
+    assert_eq!(aggregates.total_committed, 0);
+    assert_eq!(aggregates.total_claimed, 400);
+    assert_eq!(aggregates.total_expired_cancelled, 600);
+}
@@ -0,0 +1,276 @@
+#![cfg(test)]
+
+use aid_escrow::{AidEscrow, AidEscrowClient, Error, FunderAccount, PackageOptions};
+use soroban_sdk::{
+    Address, Env, Map, Vec,
+    testutils::{Address as _, Ledger},
+    token::{StellarAssetClient, TokenClient},
+};
+
+fn setup_token(env: &Env, admin: &Address) -> (TokenClient<'static>, StellarAssetClient<'static>) {
+    let token_contract = env.register_stellar_asset_contract_v2(admin.clone());
+    let token_client = TokenClient::new(env, &token_contract.address());
+    let token_admin_client = StellarAssetClient::new(env, &token_contract.address());
+    (token_client, token_admin_client)
+}
+
+/// Helper: contract funded by two donors (6000 and 4000); returns client, token client,
+/// admin and the donors.
+fn setup_donors(
+    env: &Env,
+) -> (
+    AidEscrowClient<'static>,
+    TokenClient<'static>,
+    Address,
+    Address,
+    Address,
+) {
+    let admin = Address::generate(env);
+    let donor_a = Address::generate(env);
+    let donor_b = Address::generate(env);
+    let token_admin = Address::generate(env);
+    let (token_client, token_admin_client) = setup_token(env, &token_admin);
+
+    let contract_id = env.register(AidEscrow, ());
+    let client = AidEscrowClient::new(env, &contract_id);
+
+    client.init(&admin);
+    token_admin_client.mint(&donor_a, &6000);
+    token_admin_client.mint(&donor_b, &4000);
+    client.fund(&token_client.address, &donor_a, &6000);
+    client.fund(&token_client.address, &donor_b, &4000);
+
+    (client, token_client, admin, donor_a, donor_b)
+}
+
+fn funded_by(env: &Env, funder: &Address) -> PackageOptions {
+    PackageOptions {
+        metadata: Map::new(env),
+        claimable_from: 0,
+        funder: Some(funder.clone()),
+        earmark: None,
+    }
+}
+
+#[test]
+fn test_refund_returns_to_funder() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.ledger().set_timestamp(1000);
+
+    let (client, token_client, admin, donor_a, _) = setup_donors(&env);
+    let recipient = Address::generate(&env);
+
+    client.create_package_with_options(
+        &admin,
+        &1,
+        &recipient,
+        &2500,
+        &token_client.address,
+        &2000,
+        &funded_by(&env, &donor_a),
+    );
+    assert_eq!(client.get_package(&1).funder, Some(donor_a.clone()));
+    assert_eq!(
+        client.get_funder_balance(&donor_a, &token_client.address),
+        3500
+    );
+
+    env.ledger().set_timestamp(2001);
+    client.refund(&1);
+
+    assert_eq!(token_client.balance(&donor_a), 2500);
+    assert_eq!(token_client.balance(&admin), 0);
+    assert_eq!(
+        client.get_funder(&donor_a, &token_client.address),
+        FunderAccount {
+            contributed: 6000,
+            earmarked: 0,
+            locked: 0,
+            released: 0,
+            refunded: 2500,
+            packages: 1,
+        }
+    );
+    assert_eq!(
+        client.get_funder_balance(&donor_a, &token_client.address),
+        3500
+    );
+}
+
+#[test]
+fn test_funder_balance_limits_allocation() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (client, token_client, admin, donor_a, donor_b) = setup_donors(&env);
+    let recipient = Address::generate(&env);
+
+    // The pool holds 10_000 but donor B only gave 4000
+    let result = client.try_create_package_with_options(
+        &admin,
+        &1,
+        &recipient,
+        &5000,
+        &token_client.address,
+        &0,
+        &funded_by(&env, &donor_b),
+    );
+    assert_eq!(result, Err(Ok(Error::InsufficientFunds)));
+
+    // Batch allocations count against the funder too
+    let mut recipients = Vec::new(&env);
+    recipients.push_back(Address::generate(&env));
+    recipients.push_back(Address::generate(&env));
+    let mut amounts = Vec::new(&env);
+    amounts.push_back(3000_i128);
+    amounts.push_back(1000_i128);
+    client.batch_create_with_options(
+        &admin,
+        &recipients,
+        &amounts,
+        &token_client.address,
+        &86400,
+        &funded_by(&env, &donor_b),
+    );
+    assert_eq!(
+        client.get_funder_balance(&donor_b, &token_client.address),
+        0
+    );
+
+    // Claims count as released, not refundable
+    client.claim(&0);
+    let account = client.get_funder(&donor_b, &token_client.address);
+    assert_eq!(account.locked, 1000);
+    assert_eq!(account.released, 3000);
+
+    // Revoking holds the allocation for the refund, which returns it to the funder
+    client.revoke(&admin, &1);
+    assert_eq!(
+        client.get_funder_balance(&donor_b, &token_client.address),
+        0
+    );
+    client.refund(&1);
+    let account = client.get_funder(&donor_b, &token_client.address);
+    assert_eq!(account.locked, 0);
+    assert_eq!(account.refunded, 1000);
+    assert_eq!(
+        client.get_funder_balance(&donor_a, &token_client.address),
+        6000
+    );
+}
+
+#[test]
+fn test_withdraw_surplus_to_funder() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (client, token_client, admin, donor_a, donor_b) = setup_donors(&env);
+    let recipient = Address::generate(&env);
+
+    client.create_package_with_options(
+        &admin,
+        &1,
+        &recipient,
+        &5000,
+        &token_client.address,
+        &0,
+        &funded_by(&env, &donor_a),
+    );
+
+    // Return donor A's unused 1000
+    client.withdraw_surplus(&admin, &donor_a, &1000, &token_client.address);
+    assert_eq!(token_client.balance(&donor_a), 1000);
+    let account = client.get_funder(&donor_a, &token_client.address);
+    assert_eq!(account.refunded, 1000);
+    assert_eq!(
+        client.get_funder_balance(&donor_a, &token_client.address),
+        0
+    );
+
+    // Packages without a funder are attributed to donors with unallocated money
+    client.create_package(&admin, &2, &recipient, &1000, &token_client.address, &0);
+    client.revoke(&admin, &2);
+    client.refund(&2);
+    assert_eq!(token_client.balance(&donor_b), 1000);
+    assert_eq!(token_client.balance(&admin), 0);
+}
+
+#[test]
+fn test_revoked_remainder_held_until_refund() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    // The whole pool (10000) backs one package
+    let (client, token_client, admin, donor_a, donor_b) = setup_donors(&env);
+    let recipient = Address::generate(&env);
+    client.create_package(&admin, &1, &recipient, &10_000, &token_client.address, &0);
+    client.revoke(&admin, &1);
+
+    // The revoked money is still owed to the donors, so it backs nothing else
+    let result = client.try_create_package(&admin, &2, &recipient, &1, &token_client.address, &0);
+    assert_eq!(result, Err(Ok(Error::InsufficientFunds)));
+    let result = client.try_withdraw_surplus(&admin, &admin, &1, &token_client.address);
+    assert_eq!(result, Err(Ok(Error::InsufficientSurplus)));
+
+    client.refund(&1);
+    assert_eq!(token_client.balance(&donor_a), 6000);
+    assert_eq!(token_client.balance(&donor_b), 4000);
+    assert_eq!(token_client.balance(&client.address), 0);
+
+    // Fresh money backs new packages again, and they can be claimed
+    let token_admin_client = StellarAssetClient::new(&env, &token_client.address);
+    token_admin_client.mint(&donor_a, &100);
+    client.fund(&token_client.address, &donor_a, &100);
+    client.create_package(&admin, &2, &recipient, &100, &token_client.address, &0);
+    client.claim(&2);
+    assert_eq!(token_client.balance(&recipient), 100);
+}
+
+#[test]
+fn test_refunding_funder_keeps_single_queue_place() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let token_admin = Address::generate(&env);
+    let (token_client, token_admin_client) = setup_token(&env, &token_admin);
+    let client = AidEscrowClient::new(&env, &env.register(AidEscrow, ()));
+    client.init(&admin);
+
+    let donor_a = Address::generate(&env);
+    let donor_b = Address::generate(&env);
+    let donor_c = Address::generate(&env);
+    let recipient = Address::generate(&env);
+    token_admin_client.mint(&donor_a, &50);
+    token_admin_client.mint(&donor_b, &150);
+    token_admin_client.mint(&donor_c, &200);
+
+    client.fund(&token_client.address, &donor_a, &50);
+    client.fund(&token_client.address, &donor_b, &100);
+    client.create_package_with_options(
+        &admin,
+        &1,
+        &recipient,
+        &100,
+        &token_client.address,
+        &0,
+        &funded_by(&env, &donor_b),
+    );
+
+    // B funds again while still queued behind A, then C joins
+    client.fund(&token_client.address, &donor_b, &50);
+    client.fund(&token_client.address, &donor_c, &200);
+    client.create_package(&admin, &2, &recipient, &300, &token_client.address, &0);
+
+    let shares = client.get_funding_shares(&2);
+    assert_eq!(shares.len(), 3);
+    let expected = [(&donor_a, 50), (&donor_b, 50), (&donor_c, 200)];
+    for (share, (funder, amount)) in shares.iter().zip(expected) {
+        assert_eq!(&share.funder, funder);
+        assert_eq!(share.amount, amount);
+    }
+    for donor in [&donor_a, &donor_b, &donor_c] {
+        assert_eq!(client.get_funder_balance(donor, &token_client.address), 0);
+    }
+}
@@ -0,0 +1,114 @@
+#![cfg(test)]
+
+use aid_escrow::{AidEscrow, AidEscrowClient, Error, Guardian, PackageStatus};
+use soroban_sdk::{
+    Address, Env,
+    testutils::Address as _,
+    token::{StellarAssetClient, TokenClient},
+};
+
+fn setup_token(env: &Env, admin: &Address) -> (TokenClient<'static>, StellarAssetClient<'static>) {
+    let token_contract = env.register_stellar_asset_contract_v2(admin.clone());
+    let token_client = TokenClient::new(env, &token_contract.address());
+    let token_admin_client = StellarAssetClient::new(env, &token_contract.address());
+    (token_client, token_admin_client)
+}
+
+/// Helper: set up contract, token, fund, and return the client + token client.
+fn setup_funded(
+    env: &Env,
+    fund_amount: i128,
+) -> (
+    AidEscrowClient<'static>,
+    TokenClient<'static>,
+    Address,
+    Address,
+) {
+    let admin = Address::generate(env);
+    let token_admin = Address::generate(env);
+    let (token_client, token_admin_client) = setup_token(env, &token_admin);
+
+    let contract_id = env.register(AidEscrow, ());
+    let client = AidEscrowClient::new(env, &contract_id);
+
+    client.init(&admin);
+    token_admin_client.mint(&admin, &fund_amount);
+    client.fund(&token_client.address, &admin, &fund_amount);
+
+    (client, token_client, admin, contract_id)
+}
+
+#[test]
+fn test_guardian_claims_to_recipient() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (client, token_client, admin, _) = setup_funded(&env, 10_000);
+    let recipient = Address::generate(&env);
+    let guardian = Address::generate(&env);
+
+    client.set_guardian(&recipient, &guardian, &None);
+    assert_eq!(
+        client.get_guardian(&recipient),
+        Some(Guardian {
+            guardian: guardian.clone(),
+            payout_to: None,
+        })
+    );
+
+    client.create_package(&admin, &1, &recipient, &1000, &token_client.address, &0);
+    client.claim_as_guardian(&guardian, &1);
+
+    assert_eq!(token_client.balance(&recipient), 1000);
+    assert_eq!(token_client.balance(&guardian), 0);
+    assert_eq!(client.get_package(&1).status, PackageStatus::Claimed);
+}
+
+#[test]
+fn test_guardian_claims_to_payout_address() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (client, token_client, admin, _) = setup_funded(&env, 10_000);
+    let recipient = Address::generate(&env);
+    let guardian = Address::generate(&env);
+    let guardian_wallet = Address::generate(&env);
+
+    client.set_guardian(&recipient, &guardian, &Some(guardian_wallet.clone()));
+    client.create_package(&admin, &1, &recipient, &1000, &token_client.address, &0);
+    client.claim_as_guardian(&guardian, &1);
+
+    assert_eq!(token_client.balance(&guardian_wallet), 1000);
+    assert_eq!(token_client.balance(&recipient), 0);
+}
+
+#[test]
+fn test_unregistered_guardian_cannot_claim() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (client, token_client, admin, _) = setup_funded(&env, 10_000);
+    let recipient = Address::generate(&env);
+    let guardian = Address::generate(&env);
+    let stranger = Address::generate(&env);
+
+    client.create_package(&admin, &1, &recipient, &1000, &token_client.address, &0);
+
+    // No guardian registered
+    let result = client.try_claim_as_guardian(&guardian, &1);
+    assert_eq!(result, Err(Ok(Error::NotAuthorized)));
+
+    // Someone else's guardian
+    client.set_guardian(&recipient, &guardian, &None);
+    let result = client.try_claim_as_guardian(&stranger, &1);
+    assert_eq!(result, Err(Ok(Error::NotAuthorized)));
+
+    // Removed guardian
+    client.remove_guardian(&recipient);
+    let result = client.try_claim_as_guardian(&guardian, &1);
+    assert_eq!(result, Err(Ok(Error::NotAuthorized)));
+
+    // The recipient can still claim themselves
+    client.claim(&1);
+    assert_eq!(token_client.balance(&recipient), 1000);
+}
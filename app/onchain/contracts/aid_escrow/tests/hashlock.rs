@@ -0,0 +1,180 @@
+#![cfg(test)]
+
+use aid_escrow::{AidEscrow, AidEscrowClient, Error, PackageKind, PackageStatus};
+use soroban_sdk::{
+    Address, Bytes, BytesN, Env,
+    testutils::{Address as _, Ledger},
+    token::{StellarAssetClient, TokenClient},
+    xdr::ToXdr,
+};
+
+fn setup_token(env: &Env, admin: &Address) -> (TokenClient<'static>, StellarAssetClient<'static>) {
+    let token_contract = env.register_stellar_asset_contract_v2(admin.clone());
+    let token_client = TokenClient::new(env, &token_contract.address());
+    let token_admin_client = StellarAssetClient::new(env, &token_contract.address());
+    (token_client, token_admin_client)
+}
+
+/// Helper: set up contract, token, fund, and return the client + token client.
+fn setup_funded(
+    env: &Env,
+    fund_amount: i128,
+) -> (
+    AidEscrowClient<'static>,
+    TokenClient<'static>,
+    Address,
+    Address,
+) {
+    let admin = Address::generate(env);
+    let token_admin = Address::generate(env);
+    let (token_client, token_admin_client) = setup_token(env, &token_admin);
+
+    let contract_id = env.register(AidEscrow, ());
+    let client = AidEscrowClient::new(env, &contract_id);
+
+    client.init(&admin);
+    token_admin_client.mint(&admin, &fund_amount);
+    client.fund(&token_client.address, &admin, &fund_amount);
+
+    (client, token_client, admin, contract_id)
+}
+
+fn secret_hash(env: &Env, secret: &Bytes) -> BytesN<32> {
+    env.crypto().sha256(secret).to_bytes()
+}
+
+/// sha256(secret || destination XDR), as expected by `commit_claim`.
+fn commitment(env: &Env, secret: &Bytes, destination: &Address) -> BytesN<32> {
+    let mut preimage = secret.clone();
+    preimage.append(&destination.clone().to_xdr(env));
+    env.crypto().sha256(&preimage).to_bytes()
+}
+
+#[test]
+fn test_hashlock_commit_then_reveal() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.ledger().set_sequence_number(100);
+
+    let (client, token_client, admin, contract_id) = setup_funded(&env, 10_000);
+    let secret = Bytes::from_slice(&env, b"SOTER-4821-7731-9902-6617");
+    let destination = Address::generate(&env);
+
+    client.create_hashlock_package(
+        &admin,
+        &1,
+        &secret_hash(&env, &secret),
+        &1000,
+        &token_client.address,
+        &0,
+    );
+    let pkg = client.get_package(&1);
+    assert_eq!(pkg.kind, PackageKind::HashLock);
+    assert_eq!(pkg.recipient, contract_id);
+    assert_eq!(client.view_package_status(&1), PackageStatus::Created);
+
+    client.commit_claim(&1, &commitment(&env, &secret, &destination));
+
+    // Revealing in the same ledger as the commitment is rejected
+    let result = client.try_claim_with_secret(&1, &destination, &secret);
+    assert_eq!(result, Err(Ok(Error::InvalidCommitment)));
+
+    env.ledger().set_sequence_number(101);
+    client.claim_with_secret(&1, &destination, &secret);
+
+    assert_eq!(token_client.balance(&destination), 1000);
+    let pkg = client.get_package(&1);
+    assert_eq!(pkg.status, PackageStatus::Claimed);
+    assert_eq!(pkg.recipient, destination);
+}
+
+#[test]
+fn test_hashlock_rejects_short_secrets() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.ledger().set_sequence_number(100);
+
+    let (client, token_client, admin, _) = setup_funded(&env, 10_000);
+    let secret = Bytes::from_slice(&env, b"SOTER-4821-7731");
+    let destination = Address::generate(&env);
+
+    client.create_hashlock_package(
+        &admin,
+        &1,
+        &secret_hash(&env, &secret),
+        &1000,
+        &token_client.address,
+        &0,
+    );
+    client.commit_claim(&1, &commitment(&env, &secret, &destination));
+    env.ledger().set_sequence_number(101);
+
+    let result = client.try_claim_with_secret(&1, &destination, &secret);
+    assert_eq!(result, Err(Ok(Error::InvalidSecret)));
+}
+
+#[test]
+fn test_hashlock_reveal_requires_matching_commitment() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.ledger().set_sequence_number(100);
+
+    let (client, token_client, admin, _) = setup_funded(&env, 10_000);
+    let secret = Bytes::from_slice(&env, b"SOTER-4821-7731-9902-6617");
+    let destination = Address::generate(&env);
+    let front_runner = Address::generate(&env);
+
+    client.create_hashlock_package(
+        &admin,
+        &1,
+        &secret_hash(&env, &secret),
+        &1000,
+        &token_client.address,
+        &0,
+    );
+    client.commit_claim(&1, &commitment(&env, &secret, &destination));
+    env.ledger().set_sequence_number(101);
+
+    // Someone who copies the secret from a pending reveal has no prior commitment
+    let result = client.try_claim_with_secret(&1, &front_runner, &secret);
+    assert_eq!(result, Err(Ok(Error::InvalidCommitment)));
+
+    // A wrong secret is rejected outright
+    let wrong = Bytes::from_slice(&env, b"SOTER-0000-0000");
+    let result = client.try_claim_with_secret(&1, &destination, &wrong);
+    assert_eq!(result, Err(Ok(Error::InvalidSecret)));
+
+    client.claim_with_secret(&1, &destination, &secret);
+    assert_eq!(token_client.balance(&front_runner), 0);
+    assert_eq!(token_client.balance(&destination), 1000);
+}
+
+#[test]
+fn test_hashlock_rejects_other_paths() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (client, token_client, admin, _) = setup_funded(&env, 10_000);
+    let secret = Bytes::from_slice(&env, b"SOTER-4821-7731-9902-6617");
+    let recipient = Address::generate(&env);
+
+    client.create_hashlock_package(
+        &admin,
+        &1,
+        &secret_hash(&env, &secret),
+        &1000,
+        &token_client.address,
+        &0,
+    );
+    assert_eq!(client.try_claim(&1), Err(Ok(Error::WrongPackageKind)));
+    assert_eq!(client.try_disburse(&1), Err(Ok(Error::WrongPackageKind)));
+
+    // Commitments are only accepted for hash-locked packages
+    client.create_package(&admin, &2, &recipient, &1000, &token_client.address, &0);
+    let result = client.try_commit_claim(&2, &commitment(&env, &secret, &recipient));
+    assert_eq!(result, Err(Ok(Error::WrongPackageKind)));
+
+    // Revoking still works and unlocks the funds
+    client.revoke(&admin, &1);
+    assert_eq!(client.view_package_status(&1), PackageStatus::Cancelled);
+}
@@ -196,6 +196,10 @@ fn test_set_get_config() {
         min_amount: 50,
         max_expires_in: 3600,
         allowed_tokens,
+        reassignment_delay: 0,
+        metadata_keys: Vec::new(&env),
+        max_metadata_entries: 0,
+        max_metadata_value_len: 0,
     };
     client.set_config(&config);
 
@@ -229,6 +233,10 @@ fn test_config_constraints_on_create_package() {
         min_amount: 100,
         max_expires_in: 1000,
         allowed_tokens,
+        reassignment_delay: 0,
+        metadata_keys: Vec::new(&env),
+        max_metadata_entries: 0,
+        max_metadata_value_len: 0,
     });
 
     let now = env.ledger().timestamp();
@@ -283,6 +291,10 @@ fn test_config_constraints_on_extend_expiration() {
         min_amount: 1,
         max_expires_in: 1000,
         allowed_tokens: Vec::new(&env),
+        reassignment_delay: 0,
+        metadata_keys: Vec::new(&env),
+        max_metadata_entries: 0,
+        max_metadata_value_len: 0,
     });
 
     let now = env.ledger().timestamp();
@@ -296,7 +308,7 @@ fn test_config_constraints_on_extend_expiration() {
         &(now + 500),
     );
 
-    let result = client.try_extend_expiration(&pkg_id, &700);
+    let result = client.try_extend_expiration(&admin, &pkg_id, &700);
     assert_eq!(result, Err(Ok(Error::InvalidState)));
 }
 
@@ -336,7 +348,7 @@ fn test_extend_expiration_success() {
 
     // Extend expiration by 500 units
     let additional_time = 500;
-    client.extend_expiration(&pkg_id, &additional_time);
+    client.extend_expiration(&admin, &pkg_id, &additional_time);
 
     // Verify new expiration
     let pkg_extended = client.get_package(&pkg_id);
@@ -361,7 +373,7 @@ fn test_extend_expiration_non_existent_package() {
     client.fund(&token_client.address, &admin, &5000);
 
     // Try to extend non-existent package
-    let result = client.try_extend_expiration(&999, &500);
+    let result = client.try_extend_expiration(&admin, &999, &500);
     assert_eq!(result, Err(Ok(Error::PackageNotFound)));
 }
 
@@ -396,7 +408,7 @@ fn test_extend_expiration_claimed_package() {
     client.claim(&pkg_id);
 
     // Try to extend claimed package
-    let result = client.try_extend_expiration(&pkg_id, &500);
+    let result = client.try_extend_expiration(&admin, &pkg_id, &500);
     assert_eq!(result, Err(Ok(Error::PackageNotActive)));
 }
 
@@ -434,7 +446,7 @@ fn test_extend_expiration_expired_package() {
     env.ledger().set_timestamp(expiry + 1);
 
     // Try to extend expired package
-    let result = client.try_extend_expiration(&pkg_id, &500);
+    let result = client.try_extend_expiration(&admin, &pkg_id, &500);
     assert_eq!(result, Err(Ok(Error::PackageExpired)));
 }
 
@@ -468,7 +480,7 @@ fn test_extend_expiration_zero_additional_time() {
     );
 
     // Try to extend with zero additional time
-    let result = client.try_extend_expiration(&pkg_id, &0);
+    let result = client.try_extend_expiration(&admin, &pkg_id, &0);
     assert_eq!(result, Err(Ok(Error::InvalidAmount)));
 }
 
@@ -501,7 +513,7 @@ fn test_extend_expiration_unbounded_package() {
     );
 
     // Try to extend unbounded package
-    let result = client.try_extend_expiration(&pkg_id, &500);
+    let result = client.try_extend_expiration(&admin, &pkg_id, &500);
     assert_eq!(result, Err(Ok(Error::InvalidState)));
 }
 
@@ -535,15 +547,15 @@ fn test_extend_expiration_multiple_extends() {
     );
 
     // Extend multiple times
-    client.extend_expiration(&pkg_id, &100);
+    client.extend_expiration(&admin, &pkg_id, &100);
     let pkg1 = client.get_package(&pkg_id);
     assert_eq!(pkg1.expires_at, initial_expiry + 100);
 
-    client.extend_expiration(&pkg_id, &200);
+    client.extend_expiration(&admin, &pkg_id, &200);
     let pkg2 = client.get_package(&pkg_id);
     assert_eq!(pkg2.expires_at, initial_expiry + 300);
 
-    client.extend_expiration(&pkg_id, &500);
+    client.extend_expiration(&admin, &pkg_id, &500);
     let pkg3 = client.get_package(&pkg_id);
     assert_eq!(pkg3.expires_at, initial_expiry + 800);
 }
@@ -576,9 +588,9 @@ fn test_extend_expiration_cancelled_package() {
         &token_client.address,
         &expiry,
     );
-    client.cancel_package(&pkg_id);
+    client.cancel_package(&admin, &pkg_id);
 
     // Try to extend cancelled package
-    let result = client.try_extend_expiration(&pkg_id, &500);
+    let result = client.try_extend_expiration(&admin, &pkg_id, &500);
     assert_eq!(result, Err(Ok(Error::PackageNotActive)));
 }
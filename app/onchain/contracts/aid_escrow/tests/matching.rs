@@ -0,0 +1,159 @@
+#![cfg(test)]
+
+use aid_escrow::{AidEscrow, AidEscrowClient, Error};
+use soroban_sdk::{
+    Address, Env,
+    testutils::{Address as _, Ledger},
+    token::{StellarAssetClient, TokenClient},
+};
+
+fn setup_token(env: &Env, admin: &Address) -> (TokenClient<'static>, StellarAssetClient<'static>) {
+    let token_contract = env.register_stellar_asset_contract_v2(admin.clone());
+    let token_client = TokenClient::new(env, &token_contract.address());
+    let token_admin_client = StellarAssetClient::new(env, &token_contract.address());
+    (token_client, token_admin_client)
+}
+
+/// Helper: initialized contract with a 1:1 matching pool capped at 3000 until t = 2000.
+/// Returns client, token client, token admin client, admin and sponsor.
+fn setup_matching(
+    env: &Env,
+) -> (
+    AidEscrowClient<'static>,
+    TokenClient<'static>,
+    StellarAssetClient<'static>,
+    Address,
+    Address,
+) {
+    env.ledger().set_timestamp(1000);
+    let admin = Address::generate(env);
+    let sponsor = Address::generate(env);
+    let token_admin = Address::generate(env);
+    let (token_client, token_admin_client) = setup_token(env, &token_admin);
+
+    let contract_id = env.register(AidEscrow, ());
+    let client = AidEscrowClient::new(env, &contract_id);
+
+    client.init(&admin);
+    token_admin_client.mint(&sponsor, &3000);
+    client.create_matching_pool(&sponsor, &token_client.address, &10_000, &3000, &2000);
+
+    (client, token_client, token_admin_client, admin, sponsor)
+}
+
+#[test]
+fn test_gifts_are_matched_up_to_cap() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (client, token_client, token_admin_client, admin, sponsor) = setup_matching(&env);
+    let donor = Address::generate(&env);
+    token_admin_client.mint(&donor, &5000);
+
+    // The pre-funded cap is not usable by packages yet
+    let recipient = Address::generate(&env);
+    let result = client.try_create_package(&admin, &1, &recipient, &1, &token_client.address, &0);
+    assert_eq!(result, Err(Ok(Error::InsufficientFunds)));
+
+    client.fund(&token_client.address, &donor, &2000);
+    assert_eq!(
+        client
+            .get_matching_pool(&token_client.address)
+            .unwrap()
+            .matched,
+        2000
+    );
+
+    // Only 1000 of the cap is left to match
+    client.fund(&token_client.address, &donor, &2000);
+    assert_eq!(
+        client
+            .get_matching_pool(&token_client.address)
+            .unwrap()
+            .matched,
+        3000
+    );
+
+    // Gifts plus the full match are available to packages
+    client.create_package(&admin, &1, &recipient, &7000, &token_client.address, &0);
+    assert_eq!(
+        client
+            .get_donor_impact(&sponsor, &token_client.address)
+            .contributed,
+        3000
+    );
+}
+
+#[test]
+fn test_matching_ratio_and_deadline() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.ledger().set_timestamp(1000);
+
+    let admin = Address::generate(&env);
+    let sponsor = Address::generate(&env);
+    let donor = Address::generate(&env);
+    let token_admin = Address::generate(&env);
+    let (token_client, token_admin_client) = setup_token(&env, &token_admin);
+    let contract_id = env.register(AidEscrow, ());
+    let client = AidEscrowClient::new(&env, &contract_id);
+    client.init(&admin);
+    token_admin_client.mint(&sponsor, &10_000);
+    token_admin_client.mint(&donor, &2000);
+
+    // 1:2 matching
+    client.create_matching_pool(&sponsor, &token_client.address, &5000, &10_000, &2000);
+    client.fund(&token_client.address, &donor, &1000);
+    assert_eq!(
+        client
+            .get_matching_pool(&token_client.address)
+            .unwrap()
+            .matched,
+        500
+    );
+
+    // The sponsor's own gifts are not matched
+    token_admin_client.mint(&sponsor, &100);
+    client.fund(&token_client.address, &sponsor, &100);
+    assert_eq!(
+        client
+            .get_matching_pool(&token_client.address)
+            .unwrap()
+            .matched,
+        500
+    );
+
+    // Gifts after the deadline are not matched
+    env.ledger().set_timestamp(2001);
+    client.fund(&token_client.address, &donor, &1000);
+    assert_eq!(
+        client
+            .get_matching_pool(&token_client.address)
+            .unwrap()
+            .matched,
+        500
+    );
+}
+
+#[test]
+fn test_unused_match_returned_after_deadline() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (client, token_client, token_admin_client, admin, sponsor) = setup_matching(&env);
+    let donor = Address::generate(&env);
+    token_admin_client.mint(&donor, &1000);
+    client.fund(&token_client.address, &donor, &1000);
+
+    // Cannot close before the deadline
+    let result = client.try_close_matching_pool(&token_client.address);
+    assert_eq!(result, Err(Ok(Error::InvalidState)));
+
+    env.ledger().set_timestamp(2001);
+    assert_eq!(client.close_matching_pool(&token_client.address), 2000);
+    assert_eq!(token_client.balance(&sponsor), 2000);
+    assert_eq!(client.get_matching_pool(&token_client.address), None);
+
+    // The matched 1000 stays in the general pool
+    client.withdraw_surplus(&admin, &admin, &2000, &token_client.address);
+}
@@ -0,0 +1,252 @@
+#![cfg(test)]
+
+use aid_escrow::{AidEscrow, AidEscrowClient, Config, DistributorRecord, Error, PackageOptions};
+use soroban_sdk::{
+    Address, Env, Map, String, Symbol, Vec,
+    testutils::Address as _,
+    token::{StellarAssetClient, TokenClient},
+};
+
+fn setup_token(env: &Env, admin: &Address) -> (TokenClient<'static>, StellarAssetClient<'static>) {
+    let token_contract = env.register_stellar_asset_contract_v2(admin.clone());
+    let token_client = TokenClient::new(env, &token_contract.address());
+    let token_admin_client = StellarAssetClient::new(env, &token_contract.address());
+    (token_client, token_admin_client)
+}
+
+/// Helper: set up contract, token, fund, and return the client + token client.
+fn setup_funded(
+    env: &Env,
+    fund_amount: i128,
+) -> (
+    AidEscrowClient<'static>,
+    TokenClient<'static>,
+    Address,
+    Address,
+) {
+    let admin = Address::generate(env);
+    let token_admin = Address::generate(env);
+    let (token_client, token_admin_client) = setup_token(env, &token_admin);
+
+    let contract_id = env.register(AidEscrow, ());
+    let client = AidEscrowClient::new(env, &contract_id);
+
+    client.init(&admin);
+    token_admin_client.mint(&admin, &fund_amount);
+    client.fund(&token_client.address, &admin, &fund_amount);
+
+    (client, token_client, admin, contract_id)
+}
+
+fn campaign_metadata(env: &Env) -> Map<Symbol, String> {
+    let mut metadata = Map::new(env);
+    metadata.set(
+        Symbol::new(env, "campaign"),
+        String::from_str(env, "flood-2026"),
+    );
+    metadata.set(Symbol::new(env, "region"), String::from_str(env, "KE-47"));
+    metadata
+}
+
+#[test]
+fn test_create_package_with_metadata() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (client, token_client, admin, _) = setup_funded(&env, 10_000);
+    let recipient = Address::generate(&env);
+    let options = PackageOptions {
+        metadata: campaign_metadata(&env),
+        claimable_from: 0,
+        funder: None,
+        earmark: None,
+    };
+
+    client.create_package_with_options(
+        &admin,
+        &1,
+        &recipient,
+        &1000,
+        &token_client.address,
+        &0,
+        &options,
+    );
+    assert_eq!(client.get_package(&1).metadata, campaign_metadata(&env));
+
+    // Batch creation applies the same metadata to every package
+    let mut recipients = Vec::new(&env);
+    recipients.push_back(Address::generate(&env));
+    recipients.push_back(Address::generate(&env));
+    let mut amounts = Vec::new(&env);
+    amounts.push_back(100_i128);
+    amounts.push_back(200_i128);
+    let ids = client.batch_create_with_options(
+        &admin,
+        &recipients,
+        &amounts,
+        &token_client.address,
+        &86400,
+        &options,
+    );
+    for id in ids.iter() {
+        assert_eq!(client.get_package(&id).metadata, campaign_metadata(&env));
+    }
+
+    // Plain creation still starts empty
+    client.create_package(&admin, &50, &recipient, &100, &token_client.address, &0);
+    assert!(client.get_package(&50).metadata.is_empty());
+}
+
+#[test]
+fn test_set_and_remove_package_metadata() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (client, token_client, admin, _) = setup_funded(&env, 10_000);
+    let recipient = Address::generate(&env);
+    let key = Symbol::new(&env, "verif_ref");
+
+    client.create_package(&admin, &1, &recipient, &1000, &token_client.address, &0);
+
+    client.set_package_metadata(&admin, &1, &key, &String::from_str(&env, "KYC-778"));
+    assert_eq!(
+        client.get_package(&1).metadata.get(key.clone()),
+        Some(String::from_str(&env, "KYC-778"))
+    );
+
+    client.remove_package_metadata(&admin, &1, &key);
+    assert!(client.get_package(&1).metadata.is_empty());
+
+    // Removing a missing key fails
+    let result = client.try_remove_package_metadata(&admin, &1, &key);
+    assert_eq!(result, Err(Ok(Error::InvalidMetadata)));
+
+    // Only operators can edit metadata
+    let stranger = Address::generate(&env);
+    let result = client.try_set_package_metadata(&stranger, &1, &key, &String::from_str(&env, "x"));
+    assert_eq!(result, Err(Ok(Error::NotAuthorized)));
+
+    // Metadata is frozen once the package is claimed
+    client.claim(&1);
+    let result = client.try_set_package_metadata(&admin, &1, &key, &String::from_str(&env, "x"));
+    assert_eq!(result, Err(Ok(Error::PackageNotActive)));
+}
+
+fn scoped_to(env: &Env, token: &Address) -> DistributorRecord {
+    let mut allowed_tokens = Vec::new(env);
+    allowed_tokens.push_back(token.clone());
+    DistributorRecord {
+        allowed_tokens,
+        max_amount: 0,
+        max_expires_in: 0,
+        campaign: None,
+        active_until: 0,
+        packages_created: 0,
+        total_committed: 0,
+        last_active: 0,
+    }
+}
+
+#[test]
+fn test_metadata_edits_limited_to_creator_and_scope() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (client, token_client, admin, _) = setup_funded(&env, 10_000);
+    let recipient = Address::generate(&env);
+    let key = Symbol::new(&env, "verif_ref");
+    let value = String::from_str(&env, "KYC-778");
+
+    let creator = Address::generate(&env);
+    let same_token = Address::generate(&env);
+    let other_token = Address::generate(&env);
+    client.add_distributor(&creator, &scoped_to(&env, &token_client.address));
+    client.add_distributor(&same_token, &scoped_to(&env, &token_client.address));
+    client.add_distributor(&other_token, &scoped_to(&env, &Address::generate(&env)));
+
+    client.create_package(&creator, &1, &recipient, &1000, &token_client.address, &0);
+
+    client.set_package_metadata(&creator, &1, &key, &value);
+    client.remove_package_metadata(&same_token, &1, &key);
+    let result = client.try_set_package_metadata(&other_token, &1, &key, &value);
+    assert_eq!(result, Err(Ok(Error::DistributorScopeViolation)));
+
+    // The admin can edit any package
+    client.create_package(&admin, &2, &recipient, &1000, &token_client.address, &0);
+    client.set_package_metadata(&admin, &1, &key, &value);
+    client.set_package_metadata(&same_token, &2, &key, &value);
+}
+
+#[test]
+fn test_metadata_config_limits() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (client, token_client, admin, _) = setup_funded(&env, 10_000);
+    let recipient = Address::generate(&env);
+
+    let mut metadata_keys = Vec::new(&env);
+    metadata_keys.push_back(Symbol::new(&env, "campaign"));
+    metadata_keys.push_back(Symbol::new(&env, "region"));
+    client.set_config(&Config {
+        min_amount: 1,
+        max_expires_in: 0,
+        allowed_tokens: Vec::new(&env),
+        reassignment_delay: 0,
+        metadata_keys,
+        max_metadata_entries: 2,
+        max_metadata_value_len: 12,
+    });
+
+    client.create_package_with_options(
+        &admin,
+        &1,
+        &recipient,
+        &1000,
+        &token_client.address,
+        &0,
+        &PackageOptions {
+            metadata: campaign_metadata(&env),
+            claimable_from: 0,
+            funder: None,
+            earmark: None,
+        },
+    );
+
+    // Key not on the allowlist
+    let result = client.try_set_package_metadata(
+        &admin,
+        &1,
+        &Symbol::new(&env, "phone"),
+        &String::from_str(&env, "555"),
+    );
+    assert_eq!(result, Err(Ok(Error::InvalidMetadata)));
+
+    // Value too long
+    let result = client.try_set_package_metadata(
+        &admin,
+        &1,
+        &Symbol::new(&env, "region"),
+        &String::from_str(&env, "a-very-long-region-code"),
+    );
+    assert_eq!(result, Err(Ok(Error::InvalidMetadata)));
+
+    // Too many entries at creation
+    let mut too_many = campaign_metadata(&env);
+    too_many.set(Symbol::new(&env, "extra"), String::from_str(&env, "1"));
+    let result = client.try_create_package_with_options(
+        &admin,
+        &2,
+        &recipient,
+        &1000,
+        &token_client.address,
+        &0,
+        &PackageOptions {
+            metadata: too_many,
+            claimable_from: 0,
+            funder: None,
+            earmark: None,
+        },
+    );
+    assert_eq!(result, Err(Ok(Error::InvalidMetadata)));
+}
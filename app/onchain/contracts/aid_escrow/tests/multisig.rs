@@ -0,0 +1,259 @@
+#![cfg(test)]
+
+use aid_escrow::{
+    AdminAction, AidEscrow, AidEscrowClient, DistributorRecord, Error, PackageStatus,
+    ProposalStatus, Role,
+};
+use soroban_sdk::{
+    Address, Env, Vec,
+    testutils::{Address as _, Ledger},
+    token::{StellarAssetClient, TokenClient},
+};
+
+fn setup_token(env: &Env, admin: &Address) -> (TokenClient<'static>, StellarAssetClient<'static>) {
+    let token_contract = env.register_stellar_asset_contract_v2(admin.clone());
+    let token_client = TokenClient::new(env, &token_contract.address());
+    let token_admin_client = StellarAssetClient::new(env, &token_contract.address());
+    (token_client, token_admin_client)
+}
+
+/// Helper: set up a funded contract with a 2-of-3 signer set.
+fn setup_multisig(
+    env: &Env,
+    fund_amount: i128,
+) -> (
+    AidEscrowClient<'static>,
+    TokenClient<'static>,
+    Address,
+    Vec<Address>,
+) {
+    let admin = Address::generate(env);
+    let token_admin = Address::generate(env);
+    let (token_client, token_admin_client) = setup_token(env, &token_admin);
+
+    let contract_id = env.register(AidEscrow, ());
+    let client = AidEscrowClient::new(env, &contract_id);
+
+    client.init(&admin);
+    token_admin_client.mint(&admin, &fund_amount);
+    client.fund(&token_client.address, &admin, &fund_amount);
+
+    let mut signers = Vec::new(env);
+    for _ in 0..3 {
+        signers.push_back(Address::generate(env));
+    }
+    client.set_signers(&signers, &2);
+
+    (client, token_client, admin, signers)
+}
+
+#[test]
+fn test_high_risk_actions_need_proposal() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (client, token_client, admin, signers) = setup_multisig(&env, 10_000);
+    let treasury = Address::generate(&env);
+
+    // Direct admin calls are blocked once multisig is enabled
+    let result = client.try_withdraw_surplus(&admin, &treasury, &500, &token_client.address);
+    assert_eq!(result, Err(Ok(Error::MultisigRequired)));
+    assert_eq!(client.try_migrate(&2), Err(Ok(Error::MultisigRequired)));
+    let result = client.try_set_signers(&Vec::new(&env), &0);
+    assert_eq!(result, Err(Ok(Error::MultisigRequired)));
+
+    // Everyday operations are unaffected
+    client.create_package(&admin, &1, &treasury, &1000, &token_client.address, &0);
+
+    let action = AdminAction::WithdrawSurplus(treasury.clone(), 500, token_client.address.clone());
+    let id = client.propose(&signers.get(0).unwrap(), &action, &3600);
+    let executor = Address::generate(&env);
+
+    let result = client.try_execute_proposal(&executor, &id);
+    assert_eq!(result, Err(Ok(Error::ThresholdNotMet)));
+
+    client.approve(&signers.get(1).unwrap(), &id);
+    client.execute_proposal(&executor, &id);
+    assert_eq!(token_client.balance(&treasury), 500);
+    assert_eq!(client.get_proposal(&id).status, ProposalStatus::Executed);
+
+    // Proposals run once
+    let result = client.try_execute_proposal(&executor, &id);
+    assert_eq!(result, Err(Ok(Error::ProposalNotPending)));
+}
+
+#[test]
+fn test_proposal_expiry_and_cancellation() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.ledger().set_timestamp(1000);
+
+    let (client, token_client, admin, signers) = setup_multisig(&env, 10_000);
+    let recipient = Address::generate(&env);
+    let signer_a = signers.get(0).unwrap();
+    let signer_b = signers.get(1).unwrap();
+
+    client.create_package(&admin, &1, &recipient, &1000, &token_client.address, &1500);
+    env.ledger().set_timestamp(2000);
+    assert_eq!(client.try_refund(&1), Err(Ok(Error::MultisigRequired)));
+
+    // Expired proposals cannot be approved or executed
+    let stale = client.propose(&signer_a, &AdminAction::Refund(1), &100);
+    env.ledger().set_timestamp(2101);
+    let result = client.try_approve(&signer_b, &stale);
+    assert_eq!(result, Err(Ok(Error::ProposalExpired)));
+
+    // Only the proposer can cancel
+    let id = client.propose(&signer_a, &AdminAction::Refund(1), &100);
+    let result = client.try_cancel_proposal(&signer_b, &id);
+    assert_eq!(result, Err(Ok(Error::NotAuthorized)));
+    client.cancel_proposal(&signer_a, &id);
+    let result = client.try_approve(&signer_b, &id);
+    assert_eq!(result, Err(Ok(Error::ProposalNotPending)));
+
+    // A fresh proposal goes through
+    let id = client.propose(&signer_a, &AdminAction::Refund(1), &100);
+    client.approve(&signer_b, &id);
+    client.execute_proposal(&signer_b, &id);
+    assert_eq!(client.get_package(&1).status, PackageStatus::Refunded);
+    assert_eq!(token_client.balance(&admin), 1000);
+}
+
+#[test]
+fn test_signer_rotation_and_disable() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (client, token_client, admin, signers) = setup_multisig(&env, 10_000);
+    let signer_a = signers.get(0).unwrap();
+    let signer_b = signers.get(1).unwrap();
+    let stranger = Address::generate(&env);
+    client.create_package(&admin, &1, &stranger, &1000, &token_client.address, &0);
+    client.revoke(&admin, &1);
+
+    // Non-signers cannot propose, signers cannot approve twice
+    let disable = AdminAction::SetSigners(Vec::new(&env), 0);
+    let result = client.try_propose(&stranger, &disable, &3600);
+    assert_eq!(result, Err(Ok(Error::NotAuthorized)));
+    let id = client.propose(&signer_a, &disable, &3600);
+    let result = client.try_approve(&signer_a, &id);
+    assert_eq!(result, Err(Ok(Error::InvalidState)));
+
+    // Approvals from signers rotated out no longer count
+    let mut rotated = Vec::new(&env);
+    rotated.push_back(signer_b.clone());
+    rotated.push_back(signers.get(2).unwrap());
+    let rotate = client.propose(&signer_b, &AdminAction::SetSigners(rotated, 2), &3600);
+    client.approve(&signers.get(2).unwrap(), &rotate);
+    client.execute_proposal(&signer_b, &rotate);
+    client.approve(&signer_b, &id);
+    let result = client.try_execute_proposal(&signer_b, &id);
+    assert_eq!(result, Err(Ok(Error::ThresholdNotMet)));
+
+    client.approve(&signers.get(2).unwrap(), &id);
+    client.execute_proposal(&signer_b, &id);
+    assert_eq!(client.get_multisig(), None);
+
+    // With multisig disabled the admin acts directly again
+    client.refund(&1);
+    assert_eq!(client.get_package(&1).status, PackageStatus::Refunded);
+}
+
+#[test]
+fn test_admin_transfer_and_renounce_need_proposal() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let contract_id = env.register(AidEscrow, ());
+    let client = AidEscrowClient::new(&env, &contract_id);
+    client.init(&admin);
+
+    // A nomination made by the single admin does not survive enabling multisig
+    let early = Address::generate(&env);
+    client.propose_admin(&early, &0);
+    let mut signers = Vec::new(&env);
+    for _ in 0..3 {
+        signers.push_back(Address::generate(&env));
+    }
+    client.set_signers(&signers, &2);
+    assert_eq!(client.get_pending_admin(), None);
+    assert_eq!(client.try_accept_admin(), Err(Ok(Error::NoPendingAdmin)));
+
+    let successor = Address::generate(&env);
+    let result = client.try_propose_admin(&successor, &0);
+    assert_eq!(result, Err(Ok(Error::MultisigRequired)));
+    assert_eq!(
+        client.try_renounce_admin(),
+        Err(Ok(Error::MultisigRequired))
+    );
+
+    let executor = Address::generate(&env);
+    let id = client.propose(
+        &signers.get(0).unwrap(),
+        &AdminAction::ProposeAdmin(successor.clone(), 0),
+        &3600,
+    );
+    client.approve(&signers.get(1).unwrap(), &id);
+    client.execute_proposal(&executor, &id);
+    client.accept_admin();
+    assert_eq!(client.get_admin(), successor);
+
+    let id = client.propose(&signers.get(0).unwrap(), &AdminAction::RenounceAdmin, &3600);
+    client.approve(&signers.get(2).unwrap(), &id);
+    client.execute_proposal(&executor, &id);
+    assert!(client.is_renounced());
+}
+
+#[test]
+fn test_fund_redirecting_calls_need_proposal() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.ledger().set_timestamp(1000);
+
+    let (client, token_client, admin, signers) = setup_multisig(&env, 10_000);
+    let recipient = Address::generate(&env);
+    let new_recipient = Address::generate(&env);
+    let guardian = Address::generate(&env);
+    let distributor = Address::generate(&env);
+    let record = DistributorRecord {
+        allowed_tokens: Vec::new(&env),
+        max_amount: 0,
+        max_expires_in: 0,
+        campaign: None,
+        active_until: 0,
+        packages_created: 0,
+        total_committed: 0,
+        last_active: 0,
+    };
+    client.create_package(&admin, &1, &recipient, &1000, &token_client.address, &0);
+    client.request_reassignment(&admin, &1, &new_recipient);
+    env.ledger().set_timestamp(1_000_000);
+
+    // Each of these could send a package's money elsewhere, so the admin alone cannot
+    let result = client.try_set_guardian(&recipient, &guardian, &Some(guardian.clone()));
+    assert_eq!(result, Err(Ok(Error::MultisigRequired)));
+    let result = client.try_approve_reassignment(&1);
+    assert_eq!(result, Err(Ok(Error::MultisigRequired)));
+    let result = client.try_add_distributor(&distributor, &record);
+    assert_eq!(result, Err(Ok(Error::MultisigRequired)));
+    let result = client.try_grant_role(&Role::Distributor, &distributor);
+    assert_eq!(result, Err(Ok(Error::MultisigRequired)));
+
+    let executor = Address::generate(&env);
+    let actions = [
+        AdminAction::SetGuardian(recipient.clone(), guardian.clone(), None),
+        AdminAction::ApproveReassignment(1),
+        AdminAction::AddDistributor(distributor.clone(), record.clone()),
+        AdminAction::GrantRole(Role::Canceller, distributor.clone()),
+    ];
+    for action in actions {
+        let id = client.propose(&signers.get(0).unwrap(), &action, &3600);
+        client.approve(&signers.get(1).unwrap(), &id);
+        client.execute_proposal(&executor, &id);
+    }
+    assert_eq!(client.get_guardian(&recipient).unwrap().guardian, guardian);
+    assert_eq!(client.get_package(&1).recipient, new_recipient);
+    assert_eq!(client.get_distributor(&distributor), Some(record));
+    assert!(client.has_role(&Role::Canceller, &distributor));
+}
@@ -0,0 +1,230 @@
+#![cfg(test)]
+
+use aid_escrow::{AidEscrow, AidEscrowClient, Error, PauseScope, Role};
+use soroban_sdk::{
+    Address, Env, String, Vec,
+    testutils::{Address as _, Ledger},
+    token::{StellarAssetClient, TokenClient},
+};
+
+fn setup_token(env: &Env, admin: &Address) -> (TokenClient<'static>, StellarAssetClient<'static>) {
+    let token_contract = env.register_stellar_asset_contract_v2(admin.clone());
+    let token_client = TokenClient::new(env, &token_contract.address());
+    let token_admin_client = StellarAssetClient::new(env, &token_contract.address());
+    (token_client, token_admin_client)
+}
+
+/// Helper: set up contract, token, fund, and return the client + token client.
+fn setup_funded(
+    env: &Env,
+    fund_amount: i128,
+) -> (
+    AidEscrowClient<'static>,
+    TokenClient<'static>,
+    Address,
+    Address,
+) {
+    let admin = Address::generate(env);
+    let token_admin = Address::generate(env);
+    let (token_client, token_admin_client) = setup_token(env, &token_admin);
+
+    let contract_id = env.register(AidEscrow, ());
+    let client = AidEscrowClient::new(env, &contract_id);
+
+    client.init(&admin);
+    token_admin_client.mint(&admin, &fund_amount);
+    client.fund(&token_client.address, &admin, &fund_amount);
+
+    (client, token_client, admin, contract_id)
+}
+
+#[test]
+fn test_pause_scopes_are_independent() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (client, token_client, admin, _) = setup_funded(&env, 10_000);
+    let recipient = Address::generate(&env);
+    client.create_package(&admin, &1, &recipient, &1000, &token_client.address, &0);
+    client.create_package(&admin, &2, &recipient, &1000, &token_client.address, &0);
+
+    // Pausing claims leaves creation and disbursement running
+    client.pause(&admin, &PauseScope::Claims, &None, &None, &0);
+    assert!(client.is_paused(&PauseScope::Claims, &None));
+    assert!(!client.is_paused(&PauseScope::Creation, &None));
+    assert_eq!(client.try_claim(&1), Err(Ok(Error::ContractPaused)));
+    client.create_package(&admin, &3, &recipient, &1000, &token_client.address, &0);
+    client.disburse(&1);
+
+    // Withdrawals and disbursements have their own switches
+    client.pause(&admin, &PauseScope::Withdrawal, &None, &None, &0);
+    let result = client.try_withdraw_surplus(&admin, &admin, &100, &token_client.address);
+    assert_eq!(result, Err(Ok(Error::ContractPaused)));
+    client.pause(&admin, &PauseScope::Disbursement, &None, &None, &0);
+    assert_eq!(client.try_disburse(&2), Err(Ok(Error::ContractPaused)));
+
+    client.unpause(&admin, &PauseScope::Claims, &None);
+    client.claim(&2);
+
+    // `All` covers every scope
+    client.pause(&admin, &PauseScope::All, &None, &None, &0);
+    assert!(client.is_paused(&PauseScope::Creation, &None));
+    let result =
+        client.try_create_package(&admin, &4, &recipient, &1000, &token_client.address, &0);
+    assert_eq!(result, Err(Ok(Error::ContractPaused)));
+}
+
+#[test]
+fn test_disbursement_pause_blocks_stop_stream() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.ledger().set_timestamp(1000);
+
+    let (client, token_client, admin, _) = setup_funded(&env, 10_000);
+    let recipient = Address::generate(&env);
+    client.create_stream_package(
+        &admin,
+        &1,
+        &recipient,
+        &1000,
+        &token_client.address,
+        &1000,
+        &2000,
+    );
+    env.ledger().set_timestamp(1500);
+
+    // Stopping pays out what has accrued, so it is a disbursement
+    let token = Some(token_client.address.clone());
+    client.pause(&admin, &PauseScope::Disbursement, &token, &None, &0);
+    assert_eq!(client.try_stop_stream(&1), Err(Ok(Error::ContractPaused)));
+    assert_eq!(token_client.balance(&recipient), 0);
+
+    client.unpause(&admin, &PauseScope::Disbursement, &token);
+    client.stop_stream(&1);
+    assert_eq!(token_client.balance(&recipient), 500);
+}
+
+#[test]
+fn test_token_pause_with_reason_and_expiry() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.ledger().set_timestamp(1000);
+
+    let (client, token_client, admin, _) = setup_funded(&env, 10_000);
+    let (other_token, other_admin_client) = setup_token(&env, &admin);
+    other_admin_client.mint(&admin, &10_000);
+    client.fund(&other_token.address, &admin, &10_000);
+    let recipient = Address::generate(&env);
+
+    let token = Some(token_client.address.clone());
+    let reason = Some(String::from_str(&env, "issuer incident"));
+    client.pause(&admin, &PauseScope::Creation, &token, &reason, &2000);
+    let pause = client.get_pause(&PauseScope::Creation, &token).unwrap();
+    assert_eq!(pause.reason, reason);
+    assert_eq!(pause.paused_by, admin);
+    assert_eq!(pause.expires_at, 2000);
+
+    // Only the paused token is affected
+    assert!(client.is_paused(&PauseScope::Creation, &token));
+    assert!(!client.is_paused(&PauseScope::Creation, &None));
+    let result = client.try_create_package(&admin, &1, &recipient, &100, &token_client.address, &0);
+    assert_eq!(result, Err(Ok(Error::ContractPaused)));
+    client.create_package(&admin, &1, &recipient, &100, &other_token.address, &0);
+
+    let mut recipients = Vec::new(&env);
+    recipients.push_back(recipient.clone());
+    let mut amounts = Vec::new(&env);
+    amounts.push_back(100_i128);
+    let result =
+        client.try_batch_create_packages(&admin, &recipients, &amounts, &token_client.address, &0);
+    assert_eq!(result, Err(Ok(Error::ContractPaused)));
+
+    // The pause lapses on its own
+    env.ledger().set_timestamp(2000);
+    assert!(!client.is_paused(&PauseScope::Creation, &token));
+    client.create_package(&admin, &2, &recipient, &100, &token_client.address, &0);
+
+    // Expiry must be in the future
+    let result = client.try_pause(&admin, &PauseScope::Claims, &None, &None, &2000);
+    assert_eq!(result, Err(Ok(Error::InvalidState)));
+    // Lapsed entries are dropped on the next pause change
+    client.pause(&admin, &PauseScope::Claims, &None, &None, &0);
+    assert_eq!(client.get_pause(&PauseScope::Creation, &token), None);
+    client.unpause(&admin, &PauseScope::Claims, &None);
+    assert_eq!(client.get_pause(&PauseScope::Claims, &None), None);
+}
+
+#[test]
+fn test_emergency_guardian_cannot_unpause() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (client, _, _, _) = setup_funded(&env, 10_000);
+    let guardian = Address::generate(&env);
+    let pauser = Address::generate(&env);
+    client.grant_role(&Role::EmergencyGuardian, &guardian);
+    client.grant_role(&Role::Pauser, &pauser);
+
+    client.pause(&guardian, &PauseScope::All, &None, &None, &0);
+    assert!(client.is_paused(&PauseScope::Claims, &None));
+    let result = client.try_unpause(&guardian, &PauseScope::All, &None);
+    assert_eq!(result, Err(Ok(Error::NotAuthorized)));
+
+    client.unpause(&pauser, &PauseScope::All, &None);
+    assert!(!client.is_paused(&PauseScope::Claims, &None));
+    assert_eq!(client.get_pause(&PauseScope::All, &None), None);
+
+    let stranger = Address::generate(&env);
+    let result = client.try_pause(&stranger, &PauseScope::All, &None, &None, &0);
+    assert_eq!(result, Err(Ok(Error::NotAuthorized)));
+}
+
+#[test]
+fn test_emergency_guardian_cannot_shorten_pause() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.ledger().set_timestamp(1000);
+
+    let (client, _, admin, _) = setup_funded(&env, 10_000);
+    let guardian = Address::generate(&env);
+    client.grant_role(&Role::EmergencyGuardian, &guardian);
+
+    // Replacing an indefinite pause with an expiring one would lift it
+    client.pause(&admin, &PauseScope::All, &None, &None, &0);
+    let result = client.try_pause(&guardian, &PauseScope::All, &None, &None, &1001);
+    assert_eq!(result, Err(Ok(Error::NotAuthorized)));
+    assert_eq!(
+        client
+            .get_pause(&PauseScope::All, &None)
+            .unwrap()
+            .expires_at,
+        0
+    );
+
+    // A timed pause can only be lengthened or made indefinite
+    client.pause(&admin, &PauseScope::Claims, &None, &None, &5000);
+    let result = client.try_pause(&guardian, &PauseScope::Claims, &None, &None, &4000);
+    assert_eq!(result, Err(Ok(Error::NotAuthorized)));
+    client.pause(&guardian, &PauseScope::Claims, &None, &None, &9000);
+    assert_eq!(
+        client
+            .get_pause(&PauseScope::Claims, &None)
+            .unwrap()
+            .expires_at,
+        9000
+    );
+    client.pause(&guardian, &PauseScope::Claims, &None, &None, &0);
+    assert_eq!(
+        client
+            .get_pause(&PauseScope::Claims, &None)
+            .unwrap()
+            .expires_at,
+        0
+    );
+
+    // Lapsed entries can be replaced freely
+    client.pause(&admin, &PauseScope::Creation, &None, &None, &2000);
+    env.ledger().set_timestamp(2000);
+    client.pause(&guardian, &PauseScope::Creation, &None, &None, &3000);
+    assert!(client.is_paused(&PauseScope::Creation, &None));
+}
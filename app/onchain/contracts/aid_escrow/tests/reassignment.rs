@@ -0,0 +1,144 @@
+#![cfg(test)]
+
+use aid_escrow::{AidEscrow, AidEscrowClient, Config, DistributorRecord, Error, PackageStatus};
+use soroban_sdk::{
+    Address, Env, Vec,
+    testutils::{Address as _, Ledger},
+    token::{StellarAssetClient, TokenClient},
+};
+
+fn setup_token(env: &Env, admin: &Address) -> (TokenClient<'static>, StellarAssetClient<'static>) {
+    let token_contract = env.register_stellar_asset_contract_v2(admin.clone());
+    let token_client = TokenClient::new(env, &token_contract.address());
+    let token_admin_client = StellarAssetClient::new(env, &token_contract.address());
+    (token_client, token_admin_client)
+}
+
+/// Helper: set up contract, token, fund, and return the client + token client.
+fn setup_funded(
+    env: &Env,
+    fund_amount: i128,
+) -> (
+    AidEscrowClient<'static>,
+    TokenClient<'static>,
+    Address,
+    Address,
+) {
+    let admin = Address::generate(env);
+    let token_admin = Address::generate(env);
+    let (token_client, token_admin_client) = setup_token(env, &token_admin);
+
+    let contract_id = env.register(AidEscrow, ());
+    let client = AidEscrowClient::new(env, &contract_id);
+
+    client.init(&admin);
+    token_admin_client.mint(&admin, &fund_amount);
+    client.fund(&token_client.address, &admin, &fund_amount);
+
+    (client, token_client, admin, contract_id)
+}
+
+#[test]
+fn test_reassignment_request_and_approve() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (client, token_client, admin, _) = setup_funded(&env, 10_000);
+    let distributor = Address::generate(&env);
+    let lost_wallet = Address::generate(&env);
+    let new_wallet = Address::generate(&env);
+    client.add_distributor(
+        &distributor,
+        &DistributorRecord {
+            allowed_tokens: Vec::new(&env),
+            max_amount: 0,
+            max_expires_in: 0,
+            campaign: None,
+            active_until: 0,
+            packages_created: 0,
+            total_committed: 0,
+            last_active: 0,
+        },
+    );
+
+    client.create_package(&admin, &1, &lost_wallet, &1000, &token_client.address, &0);
+    client.request_reassignment(&distributor, &1, &new_wallet);
+
+    let request = client.get_pending_reassignment(&1).unwrap();
+    assert_eq!(request.new_recipient, new_wallet);
+    assert_eq!(request.requested_by, distributor);
+    // Nothing changes until the admin approves
+    assert_eq!(client.get_package(&1).recipient, lost_wallet);
+
+    client.approve_reassignment(&1);
+    assert_eq!(client.get_package(&1).recipient, new_wallet);
+    assert_eq!(client.get_pending_reassignment(&1), None);
+
+    client.claim(&1);
+    assert_eq!(token_client.balance(&new_wallet), 1000);
+    assert_eq!(token_client.balance(&lost_wallet), 0);
+}
+
+#[test]
+fn test_reassignment_cool_down() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.ledger().set_timestamp(1000);
+
+    let (client, token_client, admin, _) = setup_funded(&env, 10_000);
+    let old_wallet = Address::generate(&env);
+    let new_wallet = Address::generate(&env);
+    client.set_config(&Config {
+        min_amount: 1,
+        max_expires_in: 0,
+        allowed_tokens: Vec::new(&env),
+        reassignment_delay: 3600,
+        metadata_keys: Vec::new(&env),
+        max_metadata_entries: 0,
+        max_metadata_value_len: 0,
+    });
+
+    client.create_package(&admin, &1, &old_wallet, &1000, &token_client.address, &0);
+    client.request_reassignment(&admin, &1, &new_wallet);
+    assert_eq!(
+        client.get_pending_reassignment(&1).unwrap().approvable_at,
+        4600
+    );
+
+    let result = client.try_approve_reassignment(&1);
+    assert_eq!(result, Err(Ok(Error::ReassignmentCoolingDown)));
+
+    env.ledger().set_timestamp(4600);
+    client.approve_reassignment(&1);
+    assert_eq!(client.get_package(&1).recipient, new_wallet);
+}
+
+#[test]
+fn test_reassignment_rejected_after_claim_or_cancel() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (client, token_client, admin, _) = setup_funded(&env, 10_000);
+    let recipient = Address::generate(&env);
+    let new_wallet = Address::generate(&env);
+
+    client.create_package(&admin, &1, &recipient, &1000, &token_client.address, &0);
+
+    // Same recipient is not a reassignment
+    let result = client.try_request_reassignment(&admin, &1, &recipient);
+    assert_eq!(result, Err(Ok(Error::InvalidState)));
+
+    // Cancelled requests cannot be approved
+    client.request_reassignment(&admin, &1, &new_wallet);
+    client.cancel_reassignment(&1);
+    let result = client.try_approve_reassignment(&1);
+    assert_eq!(result, Err(Ok(Error::NoPendingReassignment)));
+
+    // The original recipient claiming first wins
+    client.request_reassignment(&admin, &1, &new_wallet);
+    client.claim(&1);
+    let result = client.try_approve_reassignment(&1);
+    assert_eq!(result, Err(Ok(Error::PackageNotActive)));
+    assert_eq!(client.get_package(&1).status, PackageStatus::Claimed);
+    assert_eq!(client.get_package(&1).recipient, recipient);
+}
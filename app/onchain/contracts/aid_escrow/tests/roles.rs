@@ -0,0 +1,167 @@
+#![cfg(test)]
+
+use aid_escrow::{
+    AidEscrow, AidEscrowClient, DistributorRecord, Error, PackageStatus, PauseScope, Role,
+};
+use soroban_sdk::{
+    Address, Env, Vec,
+    testutils::{Address as _, Ledger},
+    token::{StellarAssetClient, TokenClient},
+};
+
+fn setup_token(env: &Env, admin: &Address) -> (TokenClient<'static>, StellarAssetClient<'static>) {
+    let token_contract = env.register_stellar_asset_contract_v2(admin.clone());
+    let token_client = TokenClient::new(env, &token_contract.address());
+    let token_admin_client = StellarAssetClient::new(env, &token_contract.address());
+    (token_client, token_admin_client)
+}
+
+/// Helper: set up contract, token, fund, and return the client + token client.
+fn setup_funded(
+    env: &Env,
+    fund_amount: i128,
+) -> (
+    AidEscrowClient<'static>,
+    TokenClient<'static>,
+    Address,
+    Address,
+) {
+    let admin = Address::generate(env);
+    let token_admin = Address::generate(env);
+    let (token_client, token_admin_client) = setup_token(env, &token_admin);
+
+    let contract_id = env.register(AidEscrow, ());
+    let client = AidEscrowClient::new(env, &contract_id);
+
+    client.init(&admin);
+    token_admin_client.mint(&admin, &fund_amount);
+    client.fund(&token_client.address, &admin, &fund_amount);
+
+    (client, token_client, admin, contract_id)
+}
+
+#[test]
+fn test_grant_and_revoke_roles() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (client, _, admin, _) = setup_funded(&env, 10_000);
+    let pauser = Address::generate(&env);
+    let auditor = Address::generate(&env);
+
+    assert!(!client.has_role(&Role::Pauser, &pauser));
+    assert_eq!(
+        client.try_pause(&pauser, &PauseScope::All, &None, &None, &0),
+        Err(Ok(Error::NotAuthorized))
+    );
+
+    client.grant_role(&Role::Pauser, &pauser);
+    client.grant_role(&Role::Auditor, &auditor);
+    // Granting twice does not duplicate the member
+    client.grant_role(&Role::Pauser, &pauser);
+    assert!(client.has_role(&Role::Pauser, &pauser));
+    assert_eq!(client.get_role_members(&Role::Pauser).len(), 1);
+    assert!(!client.has_role(&Role::Pauser, &auditor));
+
+    client.pause(&pauser, &PauseScope::All, &None, &None, &0);
+    assert!(client.is_paused(&PauseScope::All, &None));
+    client.unpause(&pauser, &PauseScope::All, &None);
+    assert!(!client.is_paused(&PauseScope::All, &None));
+
+    // The admin keeps every power without being listed
+    assert!(!client.has_role(&Role::Pauser, &admin));
+    client.pause(&admin, &PauseScope::All, &None, &None, &0);
+
+    client.revoke_role(&Role::Pauser, &pauser);
+    assert!(client.get_role_members(&Role::Pauser).is_empty());
+    assert_eq!(
+        client.try_unpause(&pauser, &PauseScope::All, &None),
+        Err(Ok(Error::NotAuthorized))
+    );
+}
+
+#[test]
+fn test_operational_roles_are_scoped() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.ledger().set_timestamp(1000);
+
+    let (client, token_client, admin, _) = setup_funded(&env, 10_000);
+    let canceller = Address::generate(&env);
+    let extender = Address::generate(&env);
+    let treasurer = Address::generate(&env);
+    let recipient = Address::generate(&env);
+    client.grant_role(&Role::Canceller, &canceller);
+    client.grant_role(&Role::Extender, &extender);
+    client.grant_role(&Role::Treasurer, &treasurer);
+
+    client.create_package(&admin, &1, &recipient, &1000, &token_client.address, &2000);
+    client.create_package(&admin, &2, &recipient, &1000, &token_client.address, &2000);
+
+    // Each role only unlocks its own action
+    let result = client.try_cancel_package(&extender, &1);
+    assert_eq!(result, Err(Ok(Error::NotAuthorized)));
+    let result = client.try_extend_expiration(&canceller, &1, &500);
+    assert_eq!(result, Err(Ok(Error::NotAuthorized)));
+    let result = client.try_withdraw_surplus(&canceller, &canceller, &100, &token_client.address);
+    assert_eq!(result, Err(Ok(Error::NotAuthorized)));
+
+    client.extend_expiration(&extender, &1, &500);
+    assert_eq!(client.get_package(&1).expires_at, 2500);
+    client.cancel_package(&canceller, &1);
+    client.revoke(&canceller, &2);
+    assert_eq!(client.get_package(&1).status, PackageStatus::Cancelled);
+    assert_eq!(client.get_package(&2).status, PackageStatus::Cancelled);
+
+    // The cancelled 2000 stays held for refund
+    client.withdraw_surplus(&treasurer, &treasurer, &8000, &token_client.address);
+    assert_eq!(token_client.balance(&treasurer), 8000);
+}
+
+#[test]
+fn test_distributor_role_matches_distributor_list() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (client, token_client, _, _) = setup_funded(&env, 10_000);
+    let distributor = Address::generate(&env);
+    let recipient = Address::generate(&env);
+
+    client.add_distributor(
+        &distributor,
+        &DistributorRecord {
+            allowed_tokens: Vec::new(&env),
+            max_amount: 0,
+            max_expires_in: 0,
+            campaign: None,
+            active_until: 0,
+            packages_created: 0,
+            total_committed: 0,
+            last_active: 0,
+        },
+    );
+    assert!(client.has_role(&Role::Distributor, &distributor));
+    assert_eq!(client.get_role_members(&Role::Distributor).len(), 1);
+
+    client.revoke_role(&Role::Distributor, &distributor);
+    let result = client.try_create_package(
+        &distributor,
+        &1,
+        &recipient,
+        &100,
+        &token_client.address,
+        &0,
+    );
+    assert_eq!(result, Err(Ok(Error::NotAuthorized)));
+
+    client.grant_role(&Role::Distributor, &distributor);
+    client.create_package(
+        &distributor,
+        &1,
+        &recipient,
+        &100,
+        &token_client.address,
+        &0,
+    );
+    assert_eq!(client.get_package(&1).created_by, distributor);
+}
@@ -0,0 +1,194 @@
+#![cfg(test)]
+
+use aid_escrow::{Aggregates, AidEscrow, AidEscrowClient, Error, PackageKind, PackageStatus};
+use soroban_sdk::{
+    Address, Env,
+    testutils::{Address as _, Ledger},
+    token::{StellarAssetClient, TokenClient},
+};
+
+fn setup_token(env: &Env, admin: &Address) -> (TokenClient<'static>, StellarAssetClient<'static>) {
+    let token_contract = env.register_stellar_asset_contract_v2(admin.clone());
+    let token_client = TokenClient::new(env, &token_contract.address());
+    let token_admin_client = StellarAssetClient::new(env, &token_contract.address());
+    (token_client, token_admin_client)
+}
+
+/// Helper: set up contract, token, fund, and return the client + token client.
+fn setup_funded(
+    env: &Env,
+    fund_amount: i128,
+) -> (
+    AidEscrowClient<'static>,
+    TokenClient<'static>,
+    Address,
+    Address,
+) {
+    let admin = Address::generate(env);
+    let token_admin = Address::generate(env);
+    let (token_client, token_admin_client) = setup_token(env, &token_admin);
+
+    let contract_id = env.register(AidEscrow, ());
+    let client = AidEscrowClient::new(env, &contract_id);
+
+    client.init(&admin);
+    token_admin_client.mint(&admin, &fund_amount);
+    client.fund(&token_client.address, &admin, &fund_amount);
+
+    (client, token_client, admin, contract_id)
+}
+
+#[test]
+fn test_stream_accrues_linearly() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.ledger().set_timestamp(1000);
+
+    let (client, token_client, admin, _) = setup_funded(&env, 10_000);
+    let recipient = Address::generate(&env);
+
+    // 1000 tokens streamed over 1000 seconds
+    client.create_stream_package(
+        &admin,
+        &1,
+        &recipient,
+        &1000,
+        &token_client.address,
+        &1000,
+        &2000,
+    );
+    assert_eq!(client.get_package(&1).kind, PackageKind::Stream);
+
+    // Nothing has accrued at the start
+    assert_eq!(client.try_claim(&1), Err(Ok(Error::NothingToClaim)));
+
+    env.ledger().set_timestamp(1250);
+    assert_eq!(client.get_claimable(&1), 250);
+    client.claim(&1);
+    assert_eq!(token_client.balance(&recipient), 250);
+
+    env.ledger().set_timestamp(1600);
+    client.claim(&1);
+    assert_eq!(token_client.balance(&recipient), 600);
+    assert_eq!(client.get_package(&1).status, PackageStatus::Created);
+
+    // Past the end everything is claimable
+    env.ledger().set_timestamp(5000);
+    client.claim(&1);
+    assert_eq!(token_client.balance(&recipient), 1000);
+    assert_eq!(client.get_package(&1).status, PackageStatus::Claimed);
+}
+
+#[test]
+fn test_stream_pause_and_resume_shift_window() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.ledger().set_timestamp(1000);
+
+    let (client, token_client, admin, _) = setup_funded(&env, 10_000);
+    let recipient = Address::generate(&env);
+
+    client.create_stream_package(
+        &admin,
+        &1,
+        &recipient,
+        &1000,
+        &token_client.address,
+        &1000,
+        &2000,
+    );
+
+    env.ledger().set_timestamp(1400);
+    client.pause_stream(&1);
+    assert_eq!(client.try_pause_stream(&1), Err(Ok(Error::InvalidState)));
+
+    // Accrual is frozen while paused, but accrued funds stay claimable
+    env.ledger().set_timestamp(1900);
+    assert_eq!(client.get_claimable(&1), 400);
+    client.claim(&1);
+
+    client.resume_stream(&1);
+    let stream = client.get_stream(&1);
+    assert_eq!(stream.start_at, 1500);
+    assert_eq!(stream.end_at, 2500);
+    assert!(!stream.paused);
+
+    env.ledger().set_timestamp(2000);
+    assert_eq!(client.get_claimable(&1), 100);
+    assert_eq!(client.try_resume_stream(&1), Err(Ok(Error::InvalidState)));
+}
+
+#[test]
+fn test_stop_stream_pays_accrued_and_returns_rest() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.ledger().set_timestamp(1000);
+
+    let (client, token_client, admin, _) = setup_funded(&env, 10_000);
+    let recipient = Address::generate(&env);
+
+    client.create_stream_package(
+        &admin,
+        &1,
+        &recipient,
+        &1000,
+        &token_client.address,
+        &1000,
+        &2000,
+    );
+
+    env.ledger().set_timestamp(1300);
+    client.claim(&1);
+    env.ledger().set_timestamp(1500);
+    client.stop_stream(&1);
+
+    assert_eq!(token_client.balance(&recipient), 500);
+    let pkg = client.get_package(&1);
+    assert_eq!(pkg.status, PackageStatus::Cancelled);
+    assert_eq!(pkg.claimed_amount, 500);
+
+    let agg = client.get_aggregates(&token_client.address);
+    assert_eq!(
+        agg,
+        Aggregates {
+            total_committed: 0,
+            total_claimed: 500,
+            total_expired_cancelled: 500,
+        }
+    );
+
+    // The unaccrued balance is held for refund; the rest of the pool is surplus
+    client.withdraw_surplus(&admin, &admin, &9000, &token_client.address);
+    client.refund(&1);
+    assert_eq!(token_client.balance(&admin), 9500);
+    assert_eq!(client.try_stop_stream(&1), Err(Ok(Error::PackageNotActive)));
+}
+
+#[test]
+fn test_stream_controls_reject_other_kinds() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (client, token_client, admin, _) = setup_funded(&env, 10_000);
+    let recipient = Address::generate(&env);
+
+    client.create_package(&admin, &1, &recipient, &1000, &token_client.address, &0);
+    assert_eq!(
+        client.try_pause_stream(&1),
+        Err(Ok(Error::WrongPackageKind))
+    );
+    assert_eq!(client.try_stop_stream(&1), Err(Ok(Error::WrongPackageKind)));
+    assert_eq!(client.try_get_stream(&1), Err(Ok(Error::WrongPackageKind)));
+
+    // End must come after start
+    let result = client.try_create_stream_package(
+        &admin,
+        &2,
+        &recipient,
+        &1000,
+        &token_client.address,
+        &500,
+        &500,
+    );
+    assert_eq!(result, Err(Ok(Error::InvalidSchedule)));
+}
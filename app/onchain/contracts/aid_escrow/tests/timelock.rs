@@ -0,0 +1,193 @@
+#![cfg(test)]
+
+use aid_escrow::{AdminAction, AidEscrow, AidEscrowClient, Error, ProposalStatus, Role};
+use soroban_sdk::{
+    Address, Env, Vec,
+    testutils::{Address as _, Ledger},
+    token::{StellarAssetClient, TokenClient},
+};
+
+fn setup_token(env: &Env, admin: &Address) -> (TokenClient<'static>, StellarAssetClient<'static>) {
+    let token_contract = env.register_stellar_asset_contract_v2(admin.clone());
+    let token_client = TokenClient::new(env, &token_contract.address());
+    let token_admin_client = StellarAssetClient::new(env, &token_contract.address());
+    (token_client, token_admin_client)
+}
+
+/// Helper: funded contract with a one-day timelock on withdrawals above 1000.
+fn setup_timelocked(
+    env: &Env,
+    fund_amount: i128,
+) -> (AidEscrowClient<'static>, TokenClient<'static>, Address) {
+    let admin = Address::generate(env);
+    let token_admin = Address::generate(env);
+    let (token_client, token_admin_client) = setup_token(env, &token_admin);
+
+    let contract_id = env.register(AidEscrow, ());
+    let client = AidEscrowClient::new(env, &contract_id);
+
+    client.init(&admin);
+    token_admin_client.mint(&admin, &fund_amount);
+    client.fund(&token_client.address, &admin, &fund_amount);
+    client.set_timelock(&86_400, &1000);
+
+    (client, token_client, admin)
+}
+
+#[test]
+fn test_config_change_waits_for_delay() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.ledger().set_timestamp(1000);
+
+    let (client, token_client, admin) = setup_timelocked(&env, 10_000);
+
+    let mut config = client.get_config();
+    config.min_amount = 50;
+    let result = client.try_set_config(&config);
+    assert_eq!(result, Err(Ok(Error::TimelockRequired)));
+    let result = client.try_set_token_allowed(&token_client.address, &true);
+    assert_eq!(result, Err(Ok(Error::TimelockRequired)));
+    assert_eq!(client.try_migrate(&2), Err(Ok(Error::TimelockRequired)));
+
+    let id = client.queue_action(&admin, &AdminAction::SetConfig(config));
+    let pending = client.get_pending_actions();
+    assert_eq!(pending.len(), 1);
+    assert_eq!(pending.get(0).unwrap().eta, 87_400);
+
+    // Anyone can execute, but only after the delay
+    let keeper = Address::generate(&env);
+    let result = client.try_execute_queued(&keeper, &id);
+    assert_eq!(result, Err(Ok(Error::TimelockNotReady)));
+    env.ledger().set_timestamp(87_400);
+    client.execute_queued(&keeper, &id);
+    assert_eq!(client.get_config().min_amount, 50);
+    assert!(client.get_pending_actions().is_empty());
+    assert_eq!(
+        client.get_queued_action(&id).status,
+        ProposalStatus::Executed
+    );
+
+    let id = client.queue_action(
+        &admin,
+        &AdminAction::SetTokenAllowed(token_client.address.clone(), true),
+    );
+    env.ledger().set_timestamp(87_400 + 86_400);
+    client.execute_queued(&keeper, &id);
+    assert_eq!(client.get_config().allowed_tokens.len(), 1);
+}
+
+#[test]
+fn test_large_withdrawals_are_queued() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (client, token_client, admin) = setup_timelocked(&env, 10_000);
+    let treasurer = Address::generate(&env);
+    client.grant_role(&Role::Treasurer, &treasurer);
+
+    // Immediate actions cannot be queued, and non-treasury actions need the admin
+    let small = AdminAction::WithdrawSurplus(treasurer.clone(), 500, token_client.address.clone());
+    assert_eq!(
+        client.try_queue_action(&treasurer, &small),
+        Err(Ok(Error::InvalidState))
+    );
+    let result = client.try_queue_action(&treasurer, &AdminAction::Migrate(2));
+    assert_eq!(result, Err(Ok(Error::NotAuthorized)));
+
+    // Small withdrawals stay immediate up to the threshold per window, however they are split
+    client.withdraw_surplus(&treasurer, &treasurer, &600, &token_client.address);
+    client.withdraw_surplus(&treasurer, &treasurer, &400, &token_client.address);
+    let result = client.try_withdraw_surplus(&treasurer, &treasurer, &1, &token_client.address);
+    assert_eq!(result, Err(Ok(Error::TimelockRequired)));
+    let result = client.try_withdraw_surplus(&treasurer, &treasurer, &1001, &token_client.address);
+    assert_eq!(result, Err(Ok(Error::TimelockRequired)));
+
+    let large = AdminAction::WithdrawSurplus(treasurer.clone(), 5000, token_client.address.clone());
+    let id = client.queue_action(&treasurer, &large);
+    env.ledger().set_timestamp(86_400);
+    client.execute_queued(&admin, &id);
+    assert_eq!(token_client.balance(&treasurer), 6000);
+
+    let result = client.try_execute_queued(&admin, &id);
+    assert_eq!(result, Err(Ok(Error::ProposalNotPending)));
+
+    // A new window starts once the delay has passed
+    client.withdraw_surplus(&treasurer, &treasurer, &1000, &token_client.address);
+    assert_eq!(token_client.balance(&treasurer), 7000);
+}
+
+#[test]
+fn test_queued_actions_recheck_multisig() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (client, token_client, admin) = setup_timelocked(&env, 10_000);
+    let treasury = Address::generate(&env);
+    let large = AdminAction::WithdrawSurplus(treasury.clone(), 5000, token_client.address.clone());
+    let id = client.queue_action(&admin, &large);
+
+    // Queued by the single admin before signers were set
+    let mut signers = Vec::new(&env);
+    signers.push_back(Address::generate(&env));
+    client.set_signers(&signers, &1);
+    env.ledger().set_timestamp(86_400);
+    let result = client.try_execute_queued(&admin, &id);
+    assert_eq!(result, Err(Ok(Error::MultisigRequired)));
+    assert!(!client.get_queued_action(&id).approved);
+
+    // The same withdrawal approved by the signers runs after the delay
+    let signer = signers.get(0).unwrap();
+    let proposal = client.propose(&signer, &large, &3600);
+    client.execute_proposal(&signer, &proposal);
+    let queued = client.get_pending_actions().get(1).unwrap();
+    assert!(queued.approved);
+    env.ledger().set_timestamp(2 * 86_400);
+    client.execute_queued(&signer, &queued.id);
+    assert_eq!(token_client.balance(&treasury), 5000);
+}
+
+#[test]
+fn test_cancel_queued_and_timelock_changes() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (client, token_client, admin) = setup_timelocked(&env, 10_000);
+    let allow = AdminAction::SetTokenAllowed(token_client.address.clone(), true);
+
+    // Lowering the delay is itself timelocked
+    assert_eq!(
+        client.try_set_timelock(&0, &0),
+        Err(Ok(Error::TimelockRequired))
+    );
+
+    let allowed = client.queue_action(&admin, &allow);
+    let disable = client.queue_action(&admin, &AdminAction::SetTimelock(0, 0));
+    assert_eq!(client.get_pending_actions().len(), 2);
+
+    client.cancel_queued(&allowed);
+    assert_eq!(client.get_pending_actions().len(), 1);
+    env.ledger().set_timestamp(86_400);
+    let result = client.try_execute_queued(&admin, &allowed);
+    assert_eq!(result, Err(Ok(Error::ProposalNotPending)));
+
+    client.execute_queued(&admin, &disable);
+    assert_eq!(client.get_timelock().delay, 0);
+    client.set_token_allowed(&token_client.address, &true);
+    assert_eq!(client.get_config().allowed_tokens.len(), 1);
+
+    // Multisig proposals for timelocked actions land in the queue
+    client.set_timelock(&3600, &0);
+    let mut signers = Vec::new(&env);
+    signers.push_back(Address::generate(&env));
+    client.set_signers(&signers, &1);
+    let signer = signers.get(0).unwrap();
+    let disallow = AdminAction::SetTokenAllowed(token_client.address.clone(), false);
+    let proposal = client.propose(&signer, &disallow, &7200);
+    client.execute_proposal(&signer, &proposal);
+    assert_eq!(client.get_config().allowed_tokens.len(), 1);
+    let queued = client.get_pending_actions().get(0).unwrap();
+    env.ledger().set_timestamp(86_400 + 3600);
+    client.execute_queued(&signer, &queued.id);
+    assert!(client.get_config().allowed_tokens.is_empty());
+}
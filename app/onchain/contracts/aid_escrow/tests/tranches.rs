@@ -0,0 +1,236 @@
+#![cfg(test)]
+
+use aid_escrow::{
+    Aggregates, AidEscrow, AidEscrowClient, Error, PackageKind, PackageStatus, Tranche,
+};
+use soroban_sdk::{
+    Address, Env, Vec,
+    testutils::{Address as _, Ledger},
+    token::{StellarAssetClient, TokenClient},
+};
+
+fn setup_token(env: &Env, admin: &Address) -> (TokenClient<'static>, StellarAssetClient<'static>) {
+    let token_contract = env.register_stellar_asset_contract_v2(admin.clone());
+    let token_client = TokenClient::new(env, &token_contract.address());
+    let token_admin_client = StellarAssetClient::new(env, &token_contract.address());
+    (token_client, token_admin_client)
+}
+
+/// Helper: set up contract, token, fund, and return the client + token client.
+fn setup_funded(
+    env: &Env,
+    fund_amount: i128,
+) -> (
+    AidEscrowClient<'static>,
+    TokenClient<'static>,
+    Address,
+    Address,
+) {
+    let admin = Address::generate(env);
+    let token_admin = Address::generate(env);
+    let (token_client, token_admin_client) = setup_token(env, &token_admin);
+
+    let contract_id = env.register(AidEscrow, ());
+    let client = AidEscrowClient::new(env, &contract_id);
+
+    client.init(&admin);
+    token_admin_client.mint(&admin, &fund_amount);
+    client.fund(&token_client.address, &admin, &fund_amount);
+
+    (client, token_client, admin, contract_id)
+}
+
+/// Three monthly tranches of 1000 starting at `start`.
+fn monthly_schedule(env: &Env, start: u64) -> Vec<Tranche> {
+    let month = 30 * 86400;
+    let mut tranches = Vec::new(env);
+    for i in 0..3 {
+        tranches.push_back(Tranche {
+            amount: 1000,
+            unlocks_at: start + i * month,
+        });
+    }
+    tranches
+}
+
+#[test]
+fn test_tranche_package_claims_follow_schedule() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.ledger().set_timestamp(1000);
+
+    let (client, token_client, admin, contract_id) = setup_funded(&env, 10_000);
+    let recipient = Address::generate(&env);
+    let schedule = monthly_schedule(&env, 1000);
+
+    client.create_tranche_package(&admin, &1, &recipient, &token_client.address, &schedule, &0);
+
+    let pkg = client.get_package(&1);
+    assert_eq!(pkg.kind, PackageKind::Tranched);
+    assert_eq!(pkg.amount, 3000);
+    assert_eq!(client.get_tranches(&1), schedule);
+
+    // First tranche is unlocked immediately
+    assert_eq!(client.get_claimable(&1), 1000);
+    client.claim(&1);
+    assert_eq!(token_client.balance(&recipient), 1000);
+    assert_eq!(client.get_package(&1).status, PackageStatus::Created);
+
+    // Nothing more until the next unlock
+    assert_eq!(client.try_claim(&1), Err(Ok(Error::NothingToClaim)));
+
+    // Skip past both remaining unlocks and claim them together
+    env.ledger().set_timestamp(1000 + 60 * 86400);
+    client.claim(&1);
+    assert_eq!(token_client.balance(&recipient), 3000);
+    assert_eq!(token_client.balance(&contract_id), 7000);
+
+    let pkg = client.get_package(&1);
+    assert_eq!(pkg.claimed_amount, 3000);
+    assert_eq!(pkg.status, PackageStatus::Claimed);
+}
+
+#[test]
+fn test_tranche_package_invalid_schedules() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.ledger().set_timestamp(1000);
+
+    let (client, token_client, admin, _) = setup_funded(&env, 10_000);
+    let recipient = Address::generate(&env);
+
+    // Empty schedule
+    let result = client.try_create_tranche_package(
+        &admin,
+        &1,
+        &recipient,
+        &token_client.address,
+        &Vec::new(&env),
+        &0,
+    );
+    assert_eq!(result, Err(Ok(Error::InvalidSchedule)));
+
+    // Unordered schedule
+    let mut unordered = Vec::new(&env);
+    unordered.push_back(Tranche {
+        amount: 100,
+        unlocks_at: 5000,
+    });
+    unordered.push_back(Tranche {
+        amount: 100,
+        unlocks_at: 2000,
+    });
+    let result = client.try_create_tranche_package(
+        &admin,
+        &1,
+        &recipient,
+        &token_client.address,
+        &unordered,
+        &0,
+    );
+    assert_eq!(result, Err(Ok(Error::InvalidSchedule)));
+
+    // Last tranche unlocks after the package expires
+    let result = client.try_create_tranche_package(
+        &admin,
+        &1,
+        &recipient,
+        &token_client.address,
+        &monthly_schedule(&env, 1000),
+        &(1000 + 86400),
+    );
+    assert_eq!(result, Err(Ok(Error::InvalidSchedule)));
+
+    // Total exceeds the pool
+    let mut too_big = Vec::new(&env);
+    too_big.push_back(Tranche {
+        amount: 20_000,
+        unlocks_at: 1000,
+    });
+    let result = client.try_create_tranche_package(
+        &admin,
+        &1,
+        &recipient,
+        &token_client.address,
+        &too_big,
+        &0,
+    );
+    assert_eq!(result, Err(Ok(Error::InsufficientFunds)));
+}
+
+#[test]
+fn test_tranche_package_partial_claim_then_revoke_and_refund() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.ledger().set_timestamp(1000);
+
+    let (client, token_client, admin, _) = setup_funded(&env, 10_000);
+    let recipient = Address::generate(&env);
+
+    client.create_tranche_package(
+        &admin,
+        &1,
+        &recipient,
+        &token_client.address,
+        &monthly_schedule(&env, 1000),
+        &0,
+    );
+    client.claim(&1);
+
+    let agg = client.get_aggregates(&token_client.address);
+    assert_eq!(
+        agg,
+        Aggregates {
+            total_committed: 2000,
+            total_claimed: 1000,
+            total_expired_cancelled: 0,
+        }
+    );
+
+    // Revoking unlocks only the unclaimed remainder
+    client.revoke(&admin, &1);
+    let agg = client.get_aggregates(&token_client.address);
+    assert_eq!(
+        agg,
+        Aggregates {
+            total_committed: 0,
+            total_claimed: 1000,
+            total_expired_cancelled: 2000,
+        }
+    );
+
+    // Refund returns only the remainder to the admin
+    client.refund(&1);
+    assert_eq!(token_client.balance(&admin), 2000);
+    assert_eq!(client.get_package(&1).status, PackageStatus::Refunded);
+
+    // The whole pool minus the claimed tranche is available again
+    client.withdraw_surplus(&admin, &admin, &7000, &token_client.address);
+}
+
+#[test]
+fn test_tranche_package_disburse_releases_remainder() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.ledger().set_timestamp(1000);
+
+    let (client, token_client, admin, _) = setup_funded(&env, 10_000);
+    let recipient = Address::generate(&env);
+
+    client.create_tranche_package(
+        &admin,
+        &1,
+        &recipient,
+        &token_client.address,
+        &monthly_schedule(&env, 1000),
+        &0,
+    );
+    client.claim(&1);
+    client.disburse(&1);
+
+    assert_eq!(token_client.balance(&recipient), 3000);
+    let pkg = client.get_package(&1);
+    assert_eq!(pkg.status, PackageStatus::Claimed);
+    assert_eq!(pkg.claimed_amount, 3000);
+    assert_eq!(client.get_claimable(&1), 0);
+}
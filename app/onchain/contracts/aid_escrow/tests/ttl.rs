@@ -0,0 +1,265 @@
+#![cfg(test)]
+
+use aid_escrow::{AidEscrow, AidEscrowClient, Error, PauseScope, Role, Tranche, TtlConfig};
+use soroban_sdk::{
+    Address, BytesN, Env, Symbol, Vec, symbol_short,
+    testutils::{Address as _, Ledger, storage::Instance as _, storage::Persistent as _},
+    token::{StellarAssetClient, TokenClient},
+    vec,
+};
+
+fn setup_token(env: &Env, admin: &Address) -> (TokenClient<'static>, StellarAssetClient<'static>) {
+    let token_contract = env.register_stellar_asset_contract_v2(admin.clone());
+    let token_client = TokenClient::new(env, &token_contract.address());
+    let token_admin_client = StellarAssetClient::new(env, &token_contract.address());
+    (token_client, token_admin_client)
+}
+
+/// Helper: funded contract with a 10k / 100k ledger TTL policy.
+fn setup_funded(
+    env: &Env,
+    fund_amount: i128,
+) -> (
+    AidEscrowClient<'static>,
+    TokenClient<'static>,
+    Address,
+    Address,
+) {
+    let admin = Address::generate(env);
+    let token_admin = Address::generate(env);
+    let (token_client, token_admin_client) = setup_token(env, &token_admin);
+
+    let contract_id = env.register(AidEscrow, ());
+    let client = AidEscrowClient::new(env, &contract_id);
+
+    client.init(&admin);
+    client.set_ttl_config(&10_000, &100_000);
+    token_admin_client.mint(&admin, &fund_amount);
+    client.fund(&token_client.address, &admin, &fund_amount);
+
+    (client, token_client, admin, contract_id)
+}
+
+/// TTL of the instance as the host sees it.
+fn host_instance_ttl(env: &Env, contract_id: &Address) -> u32 {
+    env.as_contract(contract_id, || env.storage().instance().get_ttl())
+}
+
+/// TTL of a package entry as the host sees it.
+fn host_package_ttl(env: &Env, contract_id: &Address, id: u64) -> u32 {
+    env.as_contract(contract_id, || {
+        env.storage()
+            .persistent()
+            .get_ttl(&(symbol_short!("pkg"), id))
+    })
+}
+
+#[test]
+fn test_packages_extended_when_touched() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.ledger().set_sequence_number(100);
+
+    let (client, token_client, admin, contract_id) = setup_funded(&env, 10_000);
+    let recipient = Address::generate(&env);
+
+    client.create_package(&admin, &1, &recipient, &1000, &token_client.address, &0);
+    assert_eq!(client.get_package_extended_ttl(&1), 100_000);
+    assert_eq!(host_package_ttl(&env, &contract_id, 1), 100_000);
+    assert_eq!(
+        client.get_instance_extended_ttl(),
+        host_instance_ttl(&env, &contract_id)
+    );
+
+    // Above the threshold nothing changes
+    env.ledger().set_sequence_number(100 + 80_000);
+    client.get_package(&1);
+    assert_eq!(client.get_package_extended_ttl(&1), 20_000);
+    assert_eq!(host_package_ttl(&env, &contract_id, 1), 20_000);
+
+    // Views never write, even below it
+    env.ledger().set_sequence_number(100 + 95_000);
+    client.get_package(&1);
+    client.view_package_status(&1);
+    assert_eq!(client.get_package_extended_ttl(&1), 5_000);
+    assert_eq!(host_package_ttl(&env, &contract_id, 1), 5_000);
+
+    // Any state change extends the package and the instance again
+    assert_eq!(client.expire_packages(&vec![&env, 1]), 0);
+    assert_eq!(client.get_package_extended_ttl(&1), 100_000);
+    assert_eq!(host_package_ttl(&env, &contract_id, 1), 100_000);
+    assert_eq!(
+        client.get_instance_extended_ttl(),
+        host_instance_ttl(&env, &contract_id)
+    );
+
+    client.claim(&1);
+    assert_eq!(token_client.balance(&recipient), 1000);
+}
+
+#[test]
+fn test_bump_packages_and_index() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (client, token_client, admin, contract_id) = setup_funded(&env, 10_000);
+    let recipient = Address::generate(&env);
+    client.create_package(&admin, &1, &recipient, &1000, &token_client.address, &0);
+    client.create_package(&admin, &2, &recipient, &1000, &token_client.address, &0);
+
+    // Anyone can keep packages alive; unknown ids are skipped
+    env.ledger().set_sequence_number(95_000);
+    env.set_auths(&[]);
+    assert_eq!(client.bump_packages(&vec![&env, 1, 2, 99]), 2);
+    assert_eq!(client.get_package_extended_ttl(&1), 100_000);
+    assert_eq!(host_package_ttl(&env, &contract_id, 2), 100_000);
+    assert!(client.get_instance_extended_ttl() >= 10_000);
+    assert_eq!(client.bump_packages(&Vec::new(&env)), 0);
+
+    assert_eq!(client.bump_index(&0, &10), 2);
+    assert_eq!(client.bump_index(&1, &10), 1);
+    let index_ttl = env.as_contract(&contract_id, || {
+        env.storage()
+            .persistent()
+            .get_ttl(&(symbol_short!("pidx"), 0u64))
+    });
+    assert_eq!(index_ttl, 100_000);
+
+    assert_eq!(
+        client.try_get_package_extended_ttl(&99),
+        Err(Ok(Error::PackageNotFound))
+    );
+}
+
+#[test]
+fn test_role_entries_extended_when_used() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (client, _, _, contract_id) = setup_funded(&env, 10_000);
+    let pauser = Address::generate(&env);
+    client.grant_role(&Role::Pauser, &pauser);
+    let role_ttl = || {
+        env.as_contract(&contract_id, || {
+            env.storage()
+                .persistent()
+                .get_ttl(&(symbol_short!("role"), Role::Pauser))
+        })
+    };
+    assert_eq!(role_ttl(), 100_000);
+
+    // A role check on a state-changing call keeps the role list alive
+    env.ledger().set_sequence_number(95_000);
+    assert!(client.has_role(&Role::Pauser, &pauser));
+    assert_eq!(role_ttl(), 5_000);
+    client.pause(&pauser, &PauseScope::Claims, &None, &None, &0);
+    assert_eq!(role_ttl(), 100_000);
+}
+
+#[test]
+fn test_package_side_entries_live_as_long_as_package() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.ledger().set_sequence_number(100);
+    env.ledger().set_timestamp(1000);
+
+    let (client, token_client, admin, contract_id) = setup_funded(&env, 10_000);
+    let recipient = Address::generate(&env);
+    let token = &token_client.address;
+    let tranches = vec![
+        &env,
+        Tranche {
+            amount: 500,
+            unlocks_at: 0,
+        },
+    ];
+    client.create_tranche_package(&admin, &1, &recipient, token, &tranches, &0);
+    client.create_voucher_package(
+        &admin,
+        &2,
+        &BytesN::from_array(&env, &[7; 32]),
+        &500,
+        token,
+        &0,
+    );
+    client.create_hashlock_package(
+        &admin,
+        &3,
+        &BytesN::from_array(&env, &[9; 32]),
+        &500,
+        token,
+        &0,
+    );
+    client.create_stream_package(&admin, &4, &recipient, &500, token, &1000, &5000);
+
+    let entry_ttl = |prefix: Symbol, id: u64| {
+        env.as_contract(&contract_id, || {
+            env.storage().persistent().get_ttl(&(prefix, id))
+        })
+    };
+    assert_eq!(entry_ttl(symbol_short!("sched"), 1), 100_000);
+    assert_eq!(entry_ttl(symbol_short!("vchr"), 2), 100_000);
+    assert_eq!(entry_ttl(symbol_short!("hlock"), 3), 100_000);
+    assert_eq!(entry_ttl(symbol_short!("stream"), 4), 100_000);
+    assert_eq!(entry_ttl(symbol_short!("share"), 4), 100_000);
+
+    // Rewriting the stream while the package is still fresh keeps it extended in full
+    env.ledger().set_sequence_number(100 + 50_000);
+    client.pause_stream(&4);
+    assert_eq!(host_package_ttl(&env, &contract_id, 4), 50_000);
+    assert_eq!(entry_ttl(symbol_short!("stream"), 4), 100_000);
+}
+
+#[test]
+fn test_distributor_views_do_not_extend() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (client, _, _, contract_id) = setup_funded(&env, 10_000);
+    let distributor = Address::generate(&env);
+    client.grant_role(&Role::Distributor, &distributor);
+    let record_ttl = || {
+        env.as_contract(&contract_id, || {
+            env.storage()
+                .persistent()
+                .get_ttl(&(symbol_short!("dist"), distributor.clone()))
+        })
+    };
+    assert_eq!(record_ttl(), 100_000);
+
+    env.ledger().set_sequence_number(95_000);
+    assert!(client.get_distributor(&distributor).is_some());
+    assert_eq!(client.list_distributors(&0, &10).len(), 1);
+    assert_eq!(client.get_role_members(&Role::Distributor).len(), 1);
+    assert_eq!(record_ttl(), 5_000);
+}
+
+#[test]
+fn test_ttl_config_validation() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let contract_id = env.register(AidEscrow, ());
+    let client = AidEscrowClient::new(&env, &contract_id);
+    client.init(&admin);
+
+    // 30 days / 120 days by default
+    assert_eq!(
+        client.get_ttl_config(),
+        TtlConfig {
+            threshold: 518_400,
+            extend_to: 2_073_600,
+        }
+    );
+
+    let result = client.try_set_ttl_config(&0, &1000);
+    assert_eq!(result, Err(Ok(Error::InvalidState)));
+    let result = client.try_set_ttl_config(&1000, &1000);
+    assert_eq!(result, Err(Ok(Error::InvalidState)));
+    let result = client.try_set_ttl_config(&1000, &u32::MAX);
+    assert_eq!(result, Err(Ok(Error::InvalidState)));
+
+    client.set_ttl_config(&1000, &5000);
+    assert_eq!(client.get_ttl_config().extend_to, 5000);
+}
@@ -1,7 +1,89 @@
 #![cfg(test)]
 
-use aid_escrow::{AidEscrow, AidEscrowClient};
-use soroban_sdk::{Address, Env, testutils::Address as _};
+use aid_escrow::{
+    AidEscrow, AidEscrowClient, DistributorRecord, Error, PackageKind, PackageStatus, PauseScope,
+    Role,
+};
+use soroban_sdk::{
+    Address, BytesN, Env, Map, String, Symbol, Vec, contracttype, symbol_short,
+    testutils::Address as _,
+    token::{StellarAssetClient, TokenClient},
+};
+
+/// Package layout written by v1 contracts.
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+struct PackageV1 {
+    pub id: u64,
+    pub recipient: Address,
+    pub amount: i128,
+    pub token: Address,
+    pub status: PackageStatus,
+    pub created_at: u64,
+    pub expires_at: u64,
+    pub metadata: Map<Symbol, String>,
+}
+
+/// Config layout written by v1 contracts.
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+struct ConfigV1 {
+    pub min_amount: i128,
+    pub max_expires_in: u64,
+    pub allowed_tokens: Vec<Address>,
+}
+
+/// Helper: a contract whose storage looks like a paused v1 deployment with `packages` packages.
+fn setup_v1(env: &Env, packages: u64) -> (AidEscrowClient<'static>, Address, Address) {
+    let admin = Address::generate(env);
+    let distributor = Address::generate(env);
+    let token = Address::generate(env);
+    let contract_id = env.register(AidEscrow, ());
+    let client = AidEscrowClient::new(env, &contract_id);
+    client.init(&admin);
+
+    env.as_contract(&contract_id, || {
+        let instance = env.storage().instance();
+        // Baseline contracts had no layout marker, only a label `migrate` could set freely
+        instance.remove(&symbol_short!("layout"));
+        instance.set(&symbol_short!("version"), &3u32);
+        instance.set(
+            &symbol_short!("config"),
+            &ConfigV1 {
+                min_amount: 5,
+                max_expires_in: 86_400,
+                allowed_tokens: Vec::new(env),
+            },
+        );
+        let mut distributors = Map::new(env);
+        distributors.set(distributor.clone(), true);
+        instance.set(&symbol_short!("dstrbtrs"), &distributors);
+        instance.set(&symbol_short!("paused"), &true);
+
+        for i in 0..packages {
+            let id = i + 1;
+            let package = PackageV1 {
+                id,
+                recipient: admin.clone(),
+                amount: 100,
+                token: token.clone(),
+                status: PackageStatus::Created,
+                created_at: 0,
+                expires_at: 0,
+                metadata: Map::new(env),
+            };
+            env.storage()
+                .persistent()
+                .set(&(symbol_short!("pkg"), id), &package);
+            env.storage()
+                .persistent()
+                .set(&(symbol_short!("pidx"), i), &id);
+        }
+        instance.set(&symbol_short!("pkg_idx"), &packages);
+    });
+
+    (client, admin, distributor)
+}
 
 #[test]
 fn test_version_set_on_init() {
@@ -14,49 +96,197 @@ fn test_version_set_on_init() {
 
     client.init(&admin);
 
-    assert_eq!(client.get_version(), 1);
+    // Fresh deployments start on the latest layout, nothing to migrate
+    assert_eq!(client.get_version(), 3);
+    assert_eq!(client.try_migrate(&3), Err(Ok(Error::InvalidMigration)));
+    assert_eq!(client.try_migrate(&4), Err(Ok(Error::InvalidMigration)));
+    assert_eq!(
+        client.try_continue_migration(&10),
+        Err(Ok(Error::InvalidState))
+    );
 }
 
 #[test]
-fn test_migrate_admin_only() {
+fn test_migration_refuses_skips_and_downgrades() {
     let env = Env::default();
+    env.mock_all_auths();
 
-    let admin = Address::generate(&env);
-    let contract_id = env.register(AidEscrow, ());
-    let client = AidEscrowClient::new(&env, &contract_id);
+    let (client, _, _) = setup_v1(&env, 1);
+    assert_eq!(client.get_version(), 1);
+    assert_eq!(client.try_migrate(&1), Err(Ok(Error::InvalidMigration)));
+    assert_eq!(client.try_migrate(&3), Err(Ok(Error::InvalidMigration)));
+
+    let hash = BytesN::from_array(&env, &[7; 32]);
+    assert_eq!(
+        client.try_upgrade(&hash, &0),
+        Err(Ok(Error::InvalidMigration))
+    );
+
+    client.migrate(&2);
+    assert_eq!(client.get_version(), 2);
+    assert_eq!(client.try_migrate(&2), Err(Ok(Error::InvalidMigration)));
+    assert_eq!(
+        client.try_upgrade(&hash, &1),
+        Err(Ok(Error::InvalidMigration))
+    );
+}
 
+#[test]
+fn test_v1_migration_resumes_in_batches() {
+    let env = Env::default();
     env.mock_all_auths();
-    client.init(&admin);
 
-    // Admin can migrate
+    let (client, admin, distributor) = setup_v1(&env, 60);
+
+    // The first batch runs inside `migrate`, the rest is picked up by anyone
+    client.migrate(&2);
+    let state = client.get_migration().unwrap();
+    assert_eq!(state.from_version, 1);
+    assert_eq!(state.to_version, 2);
+    assert_eq!(state.cursor, 50);
+    assert_eq!(client.get_version(), 1);
+
+    let hash = BytesN::from_array(&env, &[7; 32]);
+    assert_eq!(
+        client.try_upgrade(&hash, &2),
+        Err(Ok(Error::MigrationInProgress))
+    );
+    assert_eq!(client.try_migrate(&2), Err(Ok(Error::MigrationInProgress)));
+
+    // Half-migrated packages are never decoded and no funds move until it finishes
+    assert_eq!(
+        client.try_get_package(&55),
+        Err(Ok(Error::MigrationInProgress))
+    );
+    assert_eq!(client.try_claim(&1), Err(Ok(Error::MigrationInProgress)));
+    assert!(client.is_paused(&PauseScope::Claims, &None));
+
+    assert!(!client.continue_migration(&5));
+    assert_eq!(client.get_migration().unwrap().cursor, 55);
+    assert!(client.continue_migration(&10));
+    assert_eq!(client.get_migration(), None);
+    assert_eq!(client.get_version(), 2);
+
+    // v2 -> v3 has no per-package work and finishes at once
+    client.migrate(&3);
+    assert_eq!(client.get_migration(), None);
+    assert_eq!(client.get_version(), 3);
+
+    // Packages, config, distributors and the pause flag are all in the new layout
+    for id in [1u64, 50, 60] {
+        let package = client.get_package(&id);
+        assert_eq!(package.amount, 100);
+        assert_eq!(package.kind, PackageKind::Standard);
+        assert_eq!(package.created_by, admin);
+    }
+    let config = client.get_config();
+    assert_eq!(config.min_amount, 5);
+    assert_eq!(config.max_expires_in, 86_400);
+    assert_eq!(config.reassignment_delay, 0);
+    assert_eq!(client.get_distributor(&distributor).unwrap().max_amount, 0);
+    assert!(client.is_paused(&PauseScope::All, &None));
+    assert_eq!(
+        client.get_pause(&PauseScope::All, &None).unwrap().paused_by,
+        admin
+    );
+}
+
+#[test]
+fn test_migration_finishes_after_renounce() {
+    let env = Env::default();
     env.mock_all_auths();
+
+    let (client, admin, _) = setup_v1(&env, 60);
     client.migrate(&2);
+    client.renounce_admin();
+
+    assert!(client.continue_migration(&10));
     assert_eq!(client.get_version(), 2);
+    assert_eq!(client.get_package(&60).created_by, admin);
+}
 
-    // Non-admin cannot migrate (would fail auth check)
-    // This test verifies the function requires admin auth
-    env.mock_all_auths_allowing_non_root_auth();
-    let res = client.try_migrate(&3);
-    // Without proper auth, this should fail
-    // In mock_all_auths mode, it passes, but in real scenario only admin can call
-    assert!(res.is_ok()); // In mock mode, but structure is correct
+#[test]
+fn test_upgrade_target_blocks_until_migrated() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (client, _, _) = setup_v1(&env, 1);
+    // As left behind by `upgrade(hash, 2)`
+    env.as_contract(&client.address, || {
+        env.storage()
+            .instance()
+            .set(&symbol_short!("upg_ver"), &2u32);
+    });
+
+    assert_eq!(client.get_upgrade_target(), Some(2));
+    assert_eq!(
+        client.try_get_package(&1),
+        Err(Ok(Error::MigrationInProgress))
+    );
+    assert_eq!(client.try_claim(&1), Err(Ok(Error::MigrationInProgress)));
+
+    client.migrate(&2);
+    assert_eq!(client.get_upgrade_target(), None);
+    assert_eq!(client.get_package(&1).amount, 100);
 }
 
 #[test]
-fn test_migrate_version_progression() {
+fn test_v2_maps_move_to_persistent_entries() {
     let env = Env::default();
     env.mock_all_auths();
 
     let admin = Address::generate(&env);
+    let (distributor_a, distributor_b) = (Address::generate(&env), Address::generate(&env));
+    let token_admin = Address::generate(&env);
+    let token_contract = env.register_stellar_asset_contract_v2(token_admin);
+    let token_client = TokenClient::new(&env, &token_contract.address());
     let contract_id = env.register(AidEscrow, ());
     let client = AidEscrowClient::new(&env, &contract_id);
-
     client.init(&admin);
-    assert_eq!(client.get_version(), 1);
+    StellarAssetClient::new(&env, &token_client.address).mint(&admin, &1000);
+    client.fund(&token_client.address, &admin, &1000);
 
-    client.migrate(&2);
-    assert_eq!(client.get_version(), 2);
+    // A v2 deployment keeps distributors and locked totals in instance maps
+    let record = DistributorRecord {
+        allowed_tokens: Vec::new(&env),
+        max_amount: 500,
+        max_expires_in: 0,
+        campaign: None,
+        active_until: 0,
+        packages_created: 4,
+        total_committed: 600,
+        last_active: 0,
+    };
+    env.as_contract(&contract_id, || {
+        let instance = env.storage().instance();
+        instance.set(&symbol_short!("layout"), &2u32);
+        let mut distributors = Map::new(&env);
+        distributors.set(distributor_a.clone(), record.clone());
+        distributors.set(distributor_b.clone(), record.clone());
+        instance.set(&symbol_short!("dstrbtrs"), &distributors);
+        let mut locked = Map::new(&env);
+        locked.set(token_client.address.clone(), 600_i128);
+        instance.set(&symbol_short!("locked"), &locked);
+    });
 
     client.migrate(&3);
     assert_eq!(client.get_version(), 3);
+    env.as_contract(&contract_id, || {
+        let instance = env.storage().instance();
+        assert!(!instance.has(&symbol_short!("dstrbtrs")));
+        assert!(!instance.has(&symbol_short!("locked")));
+    });
+
+    assert_eq!(client.get_distributor(&distributor_a), Some(record.clone()));
+    assert_eq!(client.list_distributors(&0, &10).len(), 2);
+    assert!(client.has_role(&Role::Distributor, &distributor_b));
+
+    // The locked total carried over, so only the rest is surplus
+    let result = client.try_withdraw_surplus(&admin, &admin, &401, &token_client.address);
+    assert_eq!(result, Err(Ok(Error::InsufficientSurplus)));
+    client.withdraw_surplus(&admin, &admin, &400, &token_client.address);
+
+    client.remove_distributor(&distributor_a);
+    assert_eq!(client.get_distributor(&distributor_a), None);
+    assert_eq!(client.get_role_members(&Role::Distributor).len(), 1);
 }
@@ -0,0 +1,176 @@
+#![cfg(test)]
+
+use aid_escrow::{AidEscrow, AidEscrowClient, Error, PackageKind, PackageStatus};
+use ed25519_dalek::{Signer, SigningKey};
+use soroban_sdk::{
+    Address, BytesN, Env,
+    testutils::Address as _,
+    token::{StellarAssetClient, TokenClient},
+    xdr::ToXdr,
+};
+
+fn setup_token(env: &Env, admin: &Address) -> (TokenClient<'static>, StellarAssetClient<'static>) {
+    let token_contract = env.register_stellar_asset_contract_v2(admin.clone());
+    let token_client = TokenClient::new(env, &token_contract.address());
+    let token_admin_client = StellarAssetClient::new(env, &token_contract.address());
+    (token_client, token_admin_client)
+}
+
+/// Helper: set up contract, token, fund, and return the client + token client.
+fn setup_funded(
+    env: &Env,
+    fund_amount: i128,
+) -> (
+    AidEscrowClient<'static>,
+    TokenClient<'static>,
+    Address,
+    Address,
+) {
+    let admin = Address::generate(env);
+    let token_admin = Address::generate(env);
+    let (token_client, token_admin_client) = setup_token(env, &token_admin);
+
+    let contract_id = env.register(AidEscrow, ());
+    let client = AidEscrowClient::new(env, &contract_id);
+
+    client.init(&admin);
+    token_admin_client.mint(&admin, &fund_amount);
+    client.fund(&token_client.address, &admin, &fund_amount);
+
+    (client, token_client, admin, contract_id)
+}
+
+fn voucher_key(env: &Env, signer: &SigningKey) -> BytesN<32> {
+    BytesN::from_array(env, &signer.verifying_key().to_bytes())
+}
+
+/// Signs the voucher message `(contract, id, destination)` the way the contract expects.
+fn sign_voucher(
+    env: &Env,
+    signer: &SigningKey,
+    contract_id: &Address,
+    id: u64,
+    destination: &Address,
+) -> BytesN<64> {
+    let message = (contract_id.clone(), id, destination.clone()).to_xdr(env);
+    let mut buf = [0u8; 512];
+    let len = message.len() as usize;
+    message.copy_into_slice(&mut buf[..len]);
+    let signature = signer.sign(&buf[..len]);
+    BytesN::from_array(env, &signature.to_bytes())
+}
+
+#[test]
+fn test_voucher_claim_to_chosen_destination() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (client, token_client, admin, contract_id) = setup_funded(&env, 10_000);
+    let signer = SigningKey::from_bytes(&[7u8; 32]);
+    let destination = Address::generate(&env);
+
+    client.create_voucher_package(
+        &admin,
+        &1,
+        &voucher_key(&env, &signer),
+        &1000,
+        &token_client.address,
+        &0,
+    );
+    let pkg = client.get_package(&1);
+    assert_eq!(pkg.kind, PackageKind::Voucher);
+    assert_eq!(pkg.recipient, contract_id);
+
+    let signature = sign_voucher(&env, &signer, &contract_id, 1, &destination);
+    client.claim_with_voucher(&1, &destination, &signature);
+
+    assert_eq!(token_client.balance(&destination), 1000);
+    let pkg = client.get_package(&1);
+    assert_eq!(pkg.status, PackageStatus::Claimed);
+    assert_eq!(pkg.recipient, destination);
+
+    // Replaying the same voucher fails
+    let result = client.try_claim_with_voucher(&1, &destination, &signature);
+    assert_eq!(result, Err(Ok(Error::PackageNotActive)));
+}
+
+#[test]
+fn test_voucher_signature_is_bound_to_destination_and_package() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (client, token_client, admin, contract_id) = setup_funded(&env, 10_000);
+    let signer = SigningKey::from_bytes(&[7u8; 32]);
+    let destination = Address::generate(&env);
+    let thief = Address::generate(&env);
+
+    client.create_voucher_package(
+        &admin,
+        &1,
+        &voucher_key(&env, &signer),
+        &1000,
+        &token_client.address,
+        &0,
+    );
+    client.create_voucher_package(
+        &admin,
+        &2,
+        &voucher_key(&env, &signer),
+        &1000,
+        &token_client.address,
+        &0,
+    );
+
+    let signature = sign_voucher(&env, &signer, &contract_id, 1, &destination);
+
+    // Redirecting the signed voucher to another address fails
+    assert!(
+        client
+            .try_claim_with_voucher(&1, &thief, &signature)
+            .is_err()
+    );
+    // Reusing it for another package signed by the same key fails
+    assert!(
+        client
+            .try_claim_with_voucher(&2, &destination, &signature)
+            .is_err()
+    );
+    // A signature from a different key fails
+    let other = SigningKey::from_bytes(&[9u8; 32]);
+    let forged = sign_voucher(&env, &other, &contract_id, 1, &destination);
+    assert!(
+        client
+            .try_claim_with_voucher(&1, &destination, &forged)
+            .is_err()
+    );
+
+    assert_eq!(client.get_package(&1).status, PackageStatus::Created);
+    assert_eq!(token_client.balance(&thief), 0);
+}
+
+#[test]
+fn test_voucher_package_rejects_regular_claim_and_disburse() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (client, token_client, admin, _) = setup_funded(&env, 10_000);
+    let signer = SigningKey::from_bytes(&[7u8; 32]);
+    let recipient = Address::generate(&env);
+
+    client.create_voucher_package(
+        &admin,
+        &1,
+        &voucher_key(&env, &signer),
+        &1000,
+        &token_client.address,
+        &0,
+    );
+    assert_eq!(client.try_claim(&1), Err(Ok(Error::WrongPackageKind)));
+    assert_eq!(client.try_disburse(&1), Err(Ok(Error::WrongPackageKind)));
+
+    // And a regular package cannot be claimed with a voucher
+    client.create_package(&admin, &2, &recipient, &1000, &token_client.address, &0);
+    let signature = BytesN::from_array(&env, &[0u8; 64]);
+    let result = client.try_claim_with_voucher(&2, &recipient, &signature);
+    assert_eq!(result, Err(Ok(Error::WrongPackageKind)));
+}
@@ -52,11 +52,11 @@ fn test_withdraw_surplus_invalid_amount() {
     let (client, token_client, admin, _) = setup_funded(&env, 1000);
 
     // Try to withdraw zero amount
-    let result = client.try_withdraw_surplus(&admin, &0, &token_client.address);
+    let result = client.try_withdraw_surplus(&admin, &admin, &0, &token_client.address);
     assert_eq!(result, Err(Ok(Error::InvalidAmount)));
 
     // Try to withdraw negative amount
-    let result = client.try_withdraw_surplus(&admin, &-100, &token_client.address);
+    let result = client.try_withdraw_surplus(&admin, &admin, &-100, &token_client.address);
     assert_eq!(result, Err(Ok(Error::InvalidAmount)));
 }
 
@@ -79,7 +79,7 @@ fn test_withdraw_surplus_insufficient_surplus() {
     );
 
     // Try to withdraw 300 tokens from surplus (1000 balance - 800 locked = 200 surplus available)
-    let result = client.try_withdraw_surplus(&admin, &300, &token_client.address);
+    let result = client.try_withdraw_surplus(&admin, &admin, &300, &token_client.address);
     assert_eq!(result, Err(Ok(Error::InsufficientSurplus)));
 }
 
@@ -91,7 +91,7 @@ fn test_withdraw_surplus_no_locked_funds() {
     let (client, token_client, admin, _) = setup_funded(&env, 1000);
 
     // Withdraw 500 tokens (all should be surplus)
-    client.withdraw_surplus(&admin, &500, &token_client.address);
+    client.withdraw_surplus(&admin, &admin, &500, &token_client.address);
 
     // Verify events were emitted (EscrowFunded + token transfers + SurplusWithdrawn)
     let events = env.events().all();